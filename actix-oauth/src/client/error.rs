@@ -0,0 +1,16 @@
+use crate::dto::Oauth2Error;
+use thiserror::Error;
+
+/// Errors that can occur when fetching a token from a token endpoint.
+#[derive(Error, Debug)]
+pub enum TokenProviderError {
+    /// The request to the token endpoint failed.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// The token endpoint answered with an OAuth2 error.
+    #[error("token endpoint returned `{}`: {}", .0.error, .0.error_description)]
+    OAuth(Oauth2Error),
+    /// The token endpoint answered with something that is not a valid token response.
+    #[error("invalid response from token endpoint: {0}")]
+    InvalidResponse(String),
+}
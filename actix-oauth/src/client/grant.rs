@@ -0,0 +1,93 @@
+use crate::types::{RefreshToken, Scopes};
+
+/// `grant_type` value for the JWT bearer grant, as defined in RFC 7523, Section 2.1.
+pub const JWT_BEARER_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+
+/// The grant a [`TokenProvider`](super::TokenProvider) uses to obtain a new token when no
+/// cached token can be used.
+#[derive(Debug, Clone)]
+pub enum TokenGrant {
+    /// Client credentials grant, RFC 6749, Section 4.4.
+    ClientCredentials {
+        /// Scopes to request, the server default is used when `None`.
+        scopes: Option<Scopes>,
+    },
+    /// Refresh token grant, RFC 6749, Section 6.
+    RefreshToken {
+        /// The refresh token to exchange.
+        refresh_token: RefreshToken,
+        /// Scopes to request, the originally granted scopes are used when `None`.
+        scopes: Option<Scopes>,
+    },
+    /// JWT bearer grant, RFC 7523, Section 2.1.
+    JwtBearer {
+        /// The signed JWT to present as the authorization grant.
+        assertion: String,
+        /// Scopes to request, the server default is used when `None`.
+        scopes: Option<Scopes>,
+    },
+}
+
+impl TokenGrant {
+    pub fn client_credentials() -> Self {
+        Self::ClientCredentials { scopes: None }
+    }
+
+    pub fn refresh_token(refresh_token: RefreshToken) -> Self {
+        Self::RefreshToken {
+            refresh_token,
+            scopes: None,
+        }
+    }
+
+    pub fn jwt_bearer(assertion: impl Into<String>) -> Self {
+        Self::JwtBearer {
+            assertion: assertion.into(),
+            scopes: None,
+        }
+    }
+
+    /// Sets the scopes to request with the grant.
+    pub fn with_scopes(mut self, requested: Scopes) -> Self {
+        match &mut self {
+            Self::ClientCredentials { scopes }
+            | Self::RefreshToken { scopes, .. }
+            | Self::JwtBearer { scopes, .. } => *scopes = Some(requested),
+        }
+
+        self
+    }
+
+    /// Form parameters sent to the token endpoint, excluding client authentication.
+    pub(crate) fn form_params(&self) -> Vec<(&'static str, String)> {
+        let (mut params, scopes) = match self {
+            Self::ClientCredentials { scopes } => (
+                vec![("grant_type", "client_credentials".to_string())],
+                scopes,
+            ),
+            Self::RefreshToken {
+                refresh_token,
+                scopes,
+            } => (
+                vec![
+                    ("grant_type", "refresh_token".to_string()),
+                    ("refresh_token", refresh_token.secret().to_string()),
+                ],
+                scopes,
+            ),
+            Self::JwtBearer { assertion, scopes } => (
+                vec![
+                    ("grant_type", JWT_BEARER_GRANT_TYPE.to_string()),
+                    ("assertion", assertion.clone()),
+                ],
+                scopes,
+            ),
+        };
+
+        if let Some(scopes) = scopes.as_ref().filter(|scopes| !scopes.is_empty()) {
+            params.push(("scope", scopes.to_string()));
+        }
+
+        params
+    }
+}
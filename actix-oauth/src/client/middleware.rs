@@ -0,0 +1,50 @@
+use super::TokenProvider;
+use http::Extensions;
+use reqwest::header::{AUTHORIZATION, HeaderValue};
+use reqwest::{Request, Response, StatusCode};
+use reqwest_middleware::{Error, Middleware, Next, Result};
+
+impl TokenProvider {
+    async fn authorize(&self, req: &mut Request) -> Result<crate::types::AccessToken> {
+        let token = self.access_token().await.map_err(Error::middleware)?;
+        let value = HeaderValue::from_str(&format!("Bearer {}", token.secret()))
+            .map_err(Error::middleware)?;
+
+        req.headers_mut().insert(AUTHORIZATION, value);
+
+        Ok(token)
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for TokenProvider {
+    /// Sends the request with a bearer token.
+    ///
+    /// If the server answers `401 Unauthorized` the token is discarded, as it may have been
+    /// revoked before it expired, and the request is retried once with a new token when
+    /// its body can be cloned.
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let retry = req.try_clone();
+        let token = self.authorize(&mut req).await?;
+        let response = next.clone().run(req, extensions).await?;
+
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        self.discard(&token).await;
+
+        match retry {
+            Some(mut req) => {
+                self.authorize(&mut req).await?;
+                next.run(req, extensions).await
+            }
+            None => Ok(response),
+        }
+    }
+}
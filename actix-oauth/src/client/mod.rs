@@ -0,0 +1,42 @@
+//! Client side helpers for calling OAuth2 protected APIs.
+//!
+//! This module is only available with the `client` feature enabled. It provides a
+//! [`TokenProvider`] that fetches access tokens from a token endpoint, caches them until
+//! shortly before they expire and makes sure concurrent callers share a single refresh.
+//!
+//! The provider implements [`reqwest_middleware::Middleware`] so it can be attached to a
+//! [`reqwest_middleware::ClientWithMiddleware`], which then sends an `Authorization` header
+//! with every request.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use actix_oauth::client::{TokenGrant, TokenProvider};
+//! use actix_oauth::types::{ClientId, ClientSecret};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let provider = TokenProvider::builder(
+//!     "http://localhost:8000/api/v1/oauth/token",
+//!     ClientId::new("my-client"),
+//! )
+//! .client_secret(ClientSecret::new("my-secret"))
+//! .grant(TokenGrant::client_credentials())
+//! .build();
+//!
+//! let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+//!     .with(provider)
+//!     .build();
+//!
+//! let response = client.get("http://localhost:8000/api/v1/users").send().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod error;
+mod grant;
+mod middleware;
+mod provider;
+
+pub use error::*;
+pub use grant::*;
+pub use provider::*;
@@ -0,0 +1,475 @@
+use super::{TokenGrant, TokenProviderError};
+use crate::dto::Oauth2Error;
+use crate::types::{AccessToken, ClientId, ClientSecret, RefreshToken, Scopes};
+use oauth2::url::form_urlencoded;
+use reqwest::header::ACCEPT;
+use serde::Deserialize;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::debug;
+
+/// How long before expiry a cached token is replaced unless configured otherwise.
+pub const DEFAULT_EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// How the client authenticates against the token endpoint.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum ClientAuthMethod {
+    /// Send the credentials as `client_id` and `client_secret` form parameters.
+    #[default]
+    RequestBody,
+    /// Send the credentials in an HTTP Basic `Authorization` header.
+    ///
+    /// Both are form encoded before they are put in the header, as RFC 6749, Section 2.3.1
+    /// requires.
+    BasicAuth,
+}
+
+/// Successful response from a token endpoint.
+///
+/// Unlike [`TokenResponse`](crate::dto::TokenResponse) everything but the access token is
+/// optional, as servers are allowed to leave those fields out.
+#[derive(Deserialize)]
+struct TokenEndpointResponse {
+    access_token: AccessToken,
+    #[serde(default)]
+    refresh_token: Option<RefreshToken>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    scope: Option<Scopes>,
+}
+
+/// A token fetched from the token endpoint.
+#[derive(Debug, Clone)]
+pub struct CachedToken {
+    /// The access token to send with requests.
+    pub access_token: AccessToken,
+    /// Refresh token used to renew the access token, if the server issued one.
+    pub refresh_token: Option<RefreshToken>,
+    /// The granted scopes, if the server reported them.
+    pub scopes: Option<Scopes>,
+    /// When the access token expires, `None` if the server did not report a lifetime.
+    pub expires_at: Option<Instant>,
+}
+
+impl CachedToken {
+    fn is_fresh(&self, margin: Duration) -> bool {
+        self.expires_at
+            .is_none_or(|expires_at| Instant::now() + margin < expires_at)
+    }
+}
+
+struct Inner {
+    http: reqwest::Client,
+    token_url: String,
+    client_id: ClientId,
+    client_secret: Option<ClientSecret>,
+    auth_method: ClientAuthMethod,
+    grant: TokenGrant,
+    expiry_margin: Duration,
+    cache: Mutex<Option<CachedToken>>,
+}
+
+/// Fetches and caches access tokens from an OAuth2 token endpoint.
+///
+/// The token is cached until [`DEFAULT_EXPIRY_MARGIN`] (or the configured margin) before it
+/// expires. Renewal uses the refresh token when the server issued one, falling back to the
+/// configured [`TokenGrant`]. Only one renewal runs at a time; callers that ask for a token
+/// while it runs wait for it and share its result.
+///
+/// Cloning the provider is cheap and clones share the same cache.
+#[derive(Clone)]
+pub struct TokenProvider {
+    inner: Arc<Inner>,
+}
+
+impl Debug for TokenProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenProvider")
+            .field("token_url", &self.inner.token_url)
+            .field("client_id", &self.inner.client_id)
+            .field("auth_method", &self.inner.auth_method)
+            .field("expiry_margin", &self.inner.expiry_margin)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TokenProvider {
+    /// Creates a builder for a provider fetching tokens from `token_url`.
+    pub fn builder(token_url: impl Into<String>, client_id: ClientId) -> TokenProviderBuilder {
+        TokenProviderBuilder::new(token_url, client_id)
+    }
+
+    /// Returns a valid access token, fetching a new one if the cached token is about to expire.
+    pub async fn access_token(&self) -> Result<AccessToken, TokenProviderError> {
+        Ok(self.token().await?.access_token)
+    }
+
+    /// Returns the cached token, fetching a new one if it is missing or about to expire.
+    #[tracing::instrument(skip_all, level = "debug")]
+    pub async fn token(&self) -> Result<CachedToken, TokenProviderError> {
+        // The lock is held while fetching so concurrent callers wait for a single renewal.
+        let mut cache = self.inner.cache.lock().await;
+
+        if let Some(token) = cache
+            .as_ref()
+            .filter(|token| token.is_fresh(self.inner.expiry_margin))
+        {
+            return Ok(token.clone());
+        }
+
+        let refresh_token = cache.as_ref().and_then(|token| token.refresh_token.clone());
+
+        let token = match refresh_token {
+            Some(refresh_token) => {
+                let grant = TokenGrant::refresh_token(refresh_token.clone());
+
+                match self.request(&grant).await {
+                    Ok(token) => CachedToken {
+                        refresh_token: token.refresh_token.or(Some(refresh_token)),
+                        ..token
+                    },
+                    Err(error) => {
+                        debug!(error = %error, "Refreshing the token failed, requesting a new one");
+                        self.request(&self.inner.grant).await?
+                    }
+                }
+            }
+            None => self.request(&self.inner.grant).await?,
+        };
+
+        *cache = Some(token.clone());
+
+        Ok(token)
+    }
+
+    /// Drops the cached token so the next call fetches a new one.
+    pub async fn invalidate(&self) {
+        *self.inner.cache.lock().await = None;
+    }
+
+    /// Drops the cached token if it is still `access_token`, leaving a token that another
+    /// caller already renewed in place.
+    pub(crate) async fn discard(&self, access_token: &AccessToken) {
+        let mut cache = self.inner.cache.lock().await;
+
+        if cache
+            .as_ref()
+            .is_some_and(|token| &token.access_token == access_token)
+        {
+            *cache = None;
+        }
+    }
+
+    async fn request(&self, grant: &TokenGrant) -> Result<CachedToken, TokenProviderError> {
+        let inner = &self.inner;
+        let mut params = grant.form_params();
+        let mut request = inner
+            .http
+            .post(&inner.token_url)
+            .header(ACCEPT, mime::APPLICATION_JSON.as_ref());
+
+        match inner.auth_method {
+            ClientAuthMethod::RequestBody => {
+                params.push(("client_id", inner.client_id.to_string()));
+
+                if let Some(secret) = &inner.client_secret {
+                    params.push(("client_secret", secret.secret().to_string()));
+                }
+            }
+            ClientAuthMethod::BasicAuth => {
+                request = request.basic_auth(
+                    form_encode(inner.client_id.as_str()),
+                    inner
+                        .client_secret
+                        .as_ref()
+                        .map(|secret| form_encode(secret.secret())),
+                );
+            }
+        }
+
+        // Measured before sending so the computed expiry errs on the early side.
+        let requested_at = Instant::now();
+        let response = request.form(&params).send().await?;
+        let status = response.status();
+        let body = response.bytes().await?;
+
+        if !status.is_success() {
+            return Err(match serde_json::from_slice::<Oauth2Error>(&body) {
+                Ok(error) => TokenProviderError::OAuth(error),
+                Err(_) => TokenProviderError::InvalidResponse(format!(
+                    "token endpoint responded with status {status}"
+                )),
+            });
+        }
+
+        let response: TokenEndpointResponse = serde_json::from_slice(&body)
+            .map_err(|error| TokenProviderError::InvalidResponse(error.to_string()))?;
+
+        Ok(CachedToken {
+            access_token: response.access_token,
+            refresh_token: response.refresh_token,
+            scopes: response.scope,
+            expires_at: response
+                .expires_in
+                .map(|expires_in| requested_at + Duration::from_secs(expires_in)),
+        })
+    }
+}
+
+/// Encodes `value` with `application/x-www-form-urlencoded`, for the credentials in a Basic
+/// `Authorization` header.
+fn form_encode(value: &str) -> String {
+    form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+/// Builder for [`TokenProvider`].
+pub struct TokenProviderBuilder {
+    http: Option<reqwest::Client>,
+    token_url: String,
+    client_id: ClientId,
+    client_secret: Option<ClientSecret>,
+    auth_method: ClientAuthMethod,
+    grant: TokenGrant,
+    expiry_margin: Duration,
+}
+
+impl TokenProviderBuilder {
+    #[inline(always)]
+    pub fn new(token_url: impl Into<String>, client_id: ClientId) -> Self {
+        Self {
+            http: None,
+            token_url: token_url.into(),
+            client_id,
+            client_secret: None,
+            auth_method: ClientAuthMethod::default(),
+            grant: TokenGrant::client_credentials(),
+            expiry_margin: DEFAULT_EXPIRY_MARGIN,
+        }
+    }
+
+    #[inline(always)]
+    pub fn client_secret(mut self, client_secret: ClientSecret) -> Self {
+        self.client_secret = Some(client_secret);
+        self
+    }
+
+    #[inline(always)]
+    pub fn auth_method(mut self, auth_method: ClientAuthMethod) -> Self {
+        self.auth_method = auth_method;
+        self
+    }
+
+    /// Sets the grant used to obtain tokens, defaults to the client credentials grant.
+    #[inline(always)]
+    pub fn grant(mut self, grant: TokenGrant) -> Self {
+        self.grant = grant;
+        self
+    }
+
+    /// Sets how long before expiry a cached token is replaced.
+    #[inline(always)]
+    pub fn expiry_margin(mut self, expiry_margin: Duration) -> Self {
+        self.expiry_margin = expiry_margin;
+        self
+    }
+
+    /// Sets the HTTP client used to talk to the token endpoint.
+    #[inline(always)]
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    #[inline(always)]
+    pub fn build(self) -> TokenProvider {
+        TokenProvider {
+            inner: Arc::new(Inner {
+                http: self.http.unwrap_or_default(),
+                token_url: self.token_url,
+                client_id: self.client_id,
+                client_secret: self.client_secret,
+                auth_method: self.auth_method,
+                grant: self.grant,
+                expiry_margin: self.expiry_margin,
+                cache: Mutex::new(None),
+            }),
+        }
+    }
+}
+
+// These tests run against a mock token endpoint. An end to end test against the token endpoint
+// of a running server needs its database, so it is left to a separate integration setup.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::JWT_BEARER_GRANT_TYPE;
+    use futures::future::join_all;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Serves a token endpoint on a random port that issues `token-1`, `token-2`, ... with
+    /// `expires_in` and a refresh token, and records the bodies of the requests it got.
+    async fn token_endpoint(expires_in: u64) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/token", listener.local_addr().unwrap());
+        let bodies = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let issued = Arc::new(AtomicUsize::new(0));
+
+        let requests = bodies.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let body = read_request_body(&mut stream).await;
+                requests.lock().unwrap().push(body);
+
+                let n = issued.fetch_add(1, Ordering::SeqCst) + 1;
+                let json = format!(
+                    r#"{{"access_token":"token-{n}","token_type":"bearer","expires_in":{expires_in},"refresh_token":"refresh-{n}"}}"#
+                );
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{json}",
+                    json.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (url, bodies)
+    }
+
+    async fn read_request_body(stream: &mut TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+
+        loop {
+            let read = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..read]);
+
+            if read == 0 {
+                return String::new();
+            }
+
+            let request = String::from_utf8_lossy(&request);
+            let Some((head, body)) = request.split_once("\r\n\r\n") else {
+                continue;
+            };
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().ok())?
+                })
+                .unwrap_or(0);
+
+            if body.len() >= length {
+                return body.to_string();
+            }
+        }
+    }
+
+    fn provider(url: String) -> TokenProvider {
+        TokenProvider::builder(url, ClientId::new("client"))
+            .client_secret(ClientSecret::new("secret"))
+            .build()
+    }
+
+    #[test]
+    fn tokens_are_fresh_until_the_margin() {
+        let token = CachedToken {
+            access_token: AccessToken::new("token".to_string()),
+            refresh_token: None,
+            scopes: None,
+            expires_at: Some(Instant::now() + Duration::from_secs(60)),
+        };
+
+        assert!(token.is_fresh(Duration::from_secs(30)));
+        assert!(!token.is_fresh(Duration::from_secs(90)));
+        assert!(
+            CachedToken {
+                expires_at: None,
+                ..token
+            }
+            .is_fresh(Duration::MAX / 2)
+        );
+    }
+
+    #[tokio::test]
+    async fn reuses_the_token_until_it_expires() {
+        let (url, bodies) = token_endpoint(3600).await;
+        let provider = provider(url);
+
+        let first = provider.access_token().await.unwrap();
+        let second = provider.access_token().await.unwrap();
+
+        assert_eq!(first.secret(), "token-1");
+        assert_eq!(second.secret(), "token-1");
+        assert_eq!(bodies.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_callers_share_a_single_fetch() {
+        let (url, bodies) = token_endpoint(3600).await;
+        let provider = provider(url);
+
+        let tokens = join_all((0..16).map(|_| provider.token())).await;
+
+        for token in tokens {
+            assert_eq!(token.unwrap().access_token.secret(), "token-1");
+        }
+        assert_eq!(bodies.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn refreshes_tokens_within_the_margin() {
+        // Expires before the default margin, so it is never fresh.
+        let (url, bodies) = token_endpoint(10).await;
+        let provider = provider(url);
+
+        let first = provider.access_token().await.unwrap();
+        let second = provider.access_token().await.unwrap();
+
+        assert_eq!(first.secret(), "token-1");
+        assert_eq!(second.secret(), "token-2");
+
+        let bodies = bodies.lock().unwrap();
+        assert!(bodies[0].contains("grant_type=client_credentials"));
+        assert!(bodies[1].contains("grant_type=refresh_token"));
+        assert!(bodies[1].contains("refresh_token=refresh-1"));
+    }
+
+    #[tokio::test]
+    async fn fetches_a_new_token_after_invalidating() {
+        let (url, _) = token_endpoint(3600).await;
+        let provider = provider(url);
+
+        provider.access_token().await.unwrap();
+        provider.invalidate().await;
+
+        assert_eq!(provider.access_token().await.unwrap().secret(), "token-2");
+    }
+
+    #[tokio::test]
+    async fn sends_the_jwt_bearer_assertion() {
+        let (url, bodies) = token_endpoint(3600).await;
+        let provider = TokenProvider::builder(url, ClientId::new("client"))
+            .grant(TokenGrant::jwt_bearer("header.claims.signature"))
+            .build();
+
+        assert_eq!(provider.access_token().await.unwrap().secret(), "token-1");
+
+        let bodies = bodies.lock().unwrap();
+        let params = form_urlencoded::parse(bodies[0].as_bytes()).collect::<Vec<_>>();
+        assert!(params.contains(&("grant_type".into(), JWT_BEARER_GRANT_TYPE.into())));
+        assert!(params.contains(&("assertion".into(), "header.claims.signature".into())));
+    }
+
+    #[test]
+    fn form_encodes_basic_auth_credentials() {
+        assert_eq!(form_encode("my client"), "my+client");
+        assert_eq!(form_encode("s3cr3t:/+%"), "s3cr3t%3A%2F%2B%25");
+    }
+}
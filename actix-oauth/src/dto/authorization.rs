@@ -1,4 +1,4 @@
-use crate::types::{ClientId, RedirectUri, Scopes};
+use crate::types::{ClientId, CodeChallengeMethod, RedirectUri, Scopes};
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
@@ -37,4 +37,11 @@ pub struct AuthorizationRequest {
     /// Optional state to prevent CSRF attacks
     #[schema(example = "random_state_value")]
     pub state: Option<String>,
+
+    /// PKCE code challenge derived from the code verifier (RFC 7636)
+    #[schema(example = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM")]
+    pub code_challenge: Option<String>,
+
+    /// Method the code challenge was derived with, `plain` when left out
+    pub code_challenge_method: Option<CodeChallengeMethod>,
 }
@@ -1,14 +1,16 @@
 pub mod create;
+pub mod redirect_uris;
+pub mod secrets;
 pub mod update;
 
-pub use {create::*, update::*};
+pub use {create::*, redirect_uris::*, secrets::*, update::*};
 
 use crate::impl_responder;
-use crate::types::{ClientId, ClientSecret, GrantType, RedirectUri, Scopes};
+use crate::types::{ClientId, ClientSecret, ClientType, GrantType, RedirectUri, Scopes};
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use utoipa::{ToResponse, ToSchema};
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 /// Represents a OAuth2 client returned by the Server.
 #[derive(
@@ -17,11 +19,29 @@ use validator::Validate;
 pub struct OAuthClientDTO {
     /// The ID that should be used when authenticating.
     pub client_id: ClientId,
+    /// Name of the client shown to users when they authorize it
+    pub name: String,
+    /// Short description of the client
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// URL of the clients logo
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo_uri: Option<String>,
+    /// Email address to contact the people responsible for the client
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<String>,
+    /// Identifier of the user that owns the client
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Whether the client can keep a secret
+    pub client_type: ClientType,
     /// The secret that should be used when authenticating.
-    pub client_secret: ClientSecret,
-    /// The predefined redirect uri that is supported by the client
-    #[validate(url)]
-    pub redirect_uri: RedirectUri,
+    ///
+    /// Only included when the client is created, the server does not store the secret itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<ClientSecret>,
+    /// The redirect URIs registered for the client, requests have to use one of these exactly
+    pub redirect_uris: Vec<RedirectUri>,
     /// What grant types that the client supports
     pub grant_types: Vec<GrantType>,
     /// Scopes the client is allowed to use
@@ -32,6 +52,19 @@ pub struct OAuthClientDTO {
 
 impl_responder!(OAuthClientDTO);
 
+/// Public clients cannot keep a secret, so they may not use grants that authenticate with one.
+pub(crate) fn validate_client_grants(
+    client_type: ClientType,
+    grant_types: &[GrantType],
+) -> Result<(), ValidationError> {
+    if !client_type.has_secret() && grant_types.contains(&GrantType::ClientCredentials) {
+        return Err(ValidationError::new("public_client_grant")
+            .with_message("Public clients cannot use the client credentials grant".into()));
+    }
+
+    Ok(())
+}
+
 /*#[derive(ToResponse)]
 #[response(examples(
         ("Successful" = (value = json!([{
@@ -0,0 +1,22 @@
+use crate::types::RedirectUri;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+/// DTO for replacing the redirect URIs of a OAuth2 Client
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, ToSchema, Validate)]
+pub struct OAuthRedirectUrisDTO {
+    /// The redirect URIs that should be registered for the client, at least one
+    #[validate(length(min = 1))]
+    pub redirect_uris: Vec<RedirectUri>,
+}
+
+/// DTO for adding or removing a single redirect URI of a OAuth2 Client
+#[derive(
+    Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, ToSchema, IntoParams, Validate,
+)]
+#[into_params(parameter_in = Query)]
+pub struct OAuthRedirectUriDTO {
+    /// The redirect URI to add or remove
+    pub redirect_uri: RedirectUri,
+}
@@ -0,0 +1,53 @@
+use crate::impl_responder;
+use crate::types::ClientSecret;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToResponse, ToSchema};
+use validator::Validate;
+
+/// Longest grace period in seconds that can be requested when rotating a secret, 30 days.
+pub const MAX_GRACE_PERIOD: u64 = 2_592_000;
+
+/// Represents a secret of a OAuth2 client returned by the Server.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, ToSchema, ToResponse)]
+pub struct OAuthClientSecretDTO {
+    /// Identifies the secret when revoking it.
+    pub id: i64,
+    /// The secret itself.
+    ///
+    /// Only included when the secret is issued, the server does not store the secret itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<ClientSecret>,
+    /// When the secret was issued
+    pub created_at: NaiveDateTime,
+    /// When the secret stops being valid, set once a newer secret has been issued
+    pub expires_at: Option<NaiveDateTime>,
+    /// When the secret was revoked
+    pub revoked_at: Option<NaiveDateTime>,
+    /// When the secret was last used to authenticate the client
+    pub last_used_at: Option<NaiveDateTime>,
+}
+
+impl_responder!(OAuthClientSecretDTO);
+
+/// Options for issuing a new secret for a OAuth2 client.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Hash,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    IntoParams,
+    Validate,
+)]
+#[into_params(parameter_in = Query)]
+pub struct OAuthRotateClientSecretDTO {
+    /// How many seconds the current secrets stay valid after the new one is issued, defaults
+    /// to the grace period configured on the server
+    #[validate(range(max = MAX_GRACE_PERIOD))]
+    pub grace_period: Option<u64>,
+}
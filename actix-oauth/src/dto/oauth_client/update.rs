@@ -1,16 +1,36 @@
-use crate::types::{GrantType, RedirectUri, Scopes};
+use super::validate_client_grants;
+use crate::types::{ClientType, GrantType, RedirectUri, Scopes};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 /// DTO for updating a OAuth2 Client
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, ToSchema, Validate)]
+#[validate(schema(function = "validate_update_client"))]
 pub struct OAuthUpdateClientDTO {
-    /// The predefined redirect uri that is supported by the client
+    /// Name of the client shown to users when they authorize it
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    /// Short description of the client
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    /// URL of the clients logo
     #[validate(url)]
-    pub redirect_uri: RedirectUri,
+    pub logo_uri: Option<String>,
+    /// Email address to contact the people responsible for the client
+    #[validate(email)]
+    pub contact: Option<String>,
+    /// Whether the client can keep a secret
+    pub client_type: ClientType,
+    /// The redirect URIs registered for the client, requests have to use one of these exactly
+    #[validate(length(min = 1))]
+    pub redirect_uris: Vec<RedirectUri>,
     /// What grant types that the client supports
     pub grant_types: Vec<GrantType>,
     /// Scopes the client is allowed to use
     pub scopes: Scopes,
 }
+
+fn validate_update_client(dto: &OAuthUpdateClientDTO) -> Result<(), ValidationError> {
+    validate_client_grants(dto.client_type, &dto.grant_types)
+}
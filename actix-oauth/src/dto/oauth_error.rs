@@ -13,6 +13,10 @@ pub struct Oauth2Error {
     pub(crate) error: String,
     /// A human-readable ASCII string providing additional information about the error.
     pub(crate) error_description: String,
+    /// Token to send with a one-time password in the `mfa_otp` grant, only set for
+    /// `mfa_required`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) mfa_token: Option<String>,
 }
 
 impl_responder!(Oauth2Error);
@@ -36,11 +40,13 @@ pub enum Oauth2ErrorResponses {
     }))]
     InvalidClient(Oauth2Error),
 
-    /// Error: `unauthorized_client`, HTTP Status: 403 Forbidden
-    #[response(status = 403, example = json!({
-        "error": "unauthorized_client",
-        "error_description": "The client is not authorized to request a token using this method."
-    }))]
+    /// Error: `unauthorized_client` or `mfa_required`, HTTP Status: 403 Forbidden
+    #[response(status = 403,
+        examples(
+            ("unauthorized_client" = (value = json!({"error": "unauthorized_client", "error_description": "The client is not authorized to request a token using this method."}))),
+            ("mfa_required" = (description = "The user has to pass a second factor, send a one-time password with the `mfa_token` through the `mfa_otp` grant.", value = json!({"error": "mfa_required", "error_description": "Multi-factor authentication is required, send a one-time password with the mfa_token.", "mfa_token": "..."}))),
+        ))
+    ]
     UnauthorizedClient(Oauth2Error),
 
     #[response(status = 500, example = json!({
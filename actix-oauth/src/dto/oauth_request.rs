@@ -1,5 +1,6 @@
 use crate::types::{
-    AuthorizationCode, ClientId, ClientSecret, Password, RedirectUri, RefreshToken, Username,
+    AuthorizationCode, ClientId, ClientSecret, CodeVerifier, MfaToken, Password, RedirectUri,
+    RefreshToken, Username,
 };
 use serde::{Deserialize, Serialize};
 #[allow(unused_imports)]
@@ -30,8 +31,12 @@ pub enum OauthRequest {
         redirect_uri: RedirectUri,
         /// The OAuth clients id.
         client_id: ClientId,
-        /// The OAuth clients secret.
-        client_secret: ClientSecret,
+        /// The OAuth clients secret, left out by public clients.
+        #[serde(default)]
+        client_secret: Option<ClientSecret>,
+        /// PKCE code verifier, required when the authorization request had a code challenge.
+        #[serde(default)]
+        code_verifier: Option<CodeVerifier>,
     },
     /// Client credentials grant request.
     ClientCredentials {
@@ -53,6 +58,14 @@ pub enum OauthRequest {
         /// The refresh token to exchange.
         refresh_token: RefreshToken,
     },
+    /// Second step of a grant that failed with `mfa_required`.
+    MfaOtp {
+        /// The `mfa_token` of the `mfa_required` error.
+        mfa_token: MfaToken,
+        /// One-time password from the authenticator app, or a recovery code.
+        #[schema(example = "123456")]
+        otp: String,
+    },
 }
 
 impl IntoParams for OauthRequest {
@@ -72,6 +85,7 @@ impl IntoParams for OauthRequest {
                         "authorization_code",
                         "client_credentials",
                         "refresh_token",
+                        "mfa_otp",
                     ]))
                     .build(),
             ))
@@ -106,7 +120,18 @@ impl IntoParams for OauthRequest {
         params.extend(ClientSecret::into_params(|| Some(parameter_in.clone())));
         params.extend(ClientId::into_params(|| Some(parameter_in.clone())));
         params.extend(ClientSecret::into_params(|| Some(parameter_in.clone())));
+        params.extend(CodeVerifier::into_params(|| Some(parameter_in.clone())));
         params.extend(RefreshToken::into_params(|| Some(parameter_in.clone())));
+        params.extend(MfaToken::into_params(|| Some(parameter_in.clone())));
+        params.push(
+            ParameterBuilder::new()
+                .name("otp")
+                .parameter_in(parameter_in.clone())
+                .required(Required::True)
+                .schema(Some(ObjectBuilder::new().schema_type(Type::String).build()))
+                .description(Some("One-time password or recovery code for the MFA grant"))
+                .build(),
+        );
 
         parameters.extend(params);
 
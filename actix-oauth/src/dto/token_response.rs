@@ -11,7 +11,11 @@ pub struct TokenResponse {
     /// The access token to use when authenticating requests.
     pub access_token: AccessToken,
     /// Token to exchange for a new access token after its expired.
-    pub refresh_token: RefreshToken,
+    ///
+    /// Not issued for grants where the client can simply request a new token, such as the
+    /// client credentials grant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<RefreshToken>,
     /// How to use the token in requests.
     pub token_type: TokenType,
     /// how many seconds until the access token expires.
@@ -52,12 +56,21 @@ impl Default for TokenResponse {
 }
 
 impl TokenResponse {
+    /// Creates a response with a random access and refresh token.
     pub fn new() -> Self {
         Self {
             access_token: AccessToken::new(utils::random_string(50)),
-            refresh_token: RefreshToken::new(utils::random_string(50)),
+            refresh_token: Some(RefreshToken::new(utils::random_string(50))),
             token_type: TokenType::default(),
             expires_in: 3600,
         }
     }
+
+    /// Creates a response with a random access token and no refresh token.
+    pub fn without_refresh_token() -> Self {
+        Self {
+            refresh_token: None,
+            ..Self::new()
+        }
+    }
 }
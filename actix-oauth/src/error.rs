@@ -4,6 +4,7 @@
 //! (RFC 6749, Section 5.2), with appropriate HTTP status codes and JSON responses.
 
 use crate::dto::oauth_error::Oauth2Error;
+use crate::types::{MfaToken, RedirectUri};
 use actix_web::body::BoxBody;
 use actix_web::http::{StatusCode, header};
 use actix_web::{HttpResponse, ResponseError};
@@ -53,10 +54,23 @@ pub enum Oauth2ErrorType {
     /// The authenticated client is not authorized to use this authorization grant type.
     #[error("unauthorized_client")]
     UnauthorizedClient,
+    /// The resource owner or authorization server denied the request.
+    #[error("access_denied")]
+    AccessDenied,
+    /// The authorization server does not support obtaining an authorization code or token
+    /// using this method.
+    #[error("unsupported_response_type")]
+    UnsupportedResponseType,
     /// The authorization server encountered an unexpected condition that prevented it
     /// from fulfilling the request.
     #[error("server_error")]
     ServerError,
+    /// The credentials are valid, but the resource owner also has to pass a second factor.
+    ///
+    /// The token is sent back as `mfa_token` and exchanged together with a one-time password
+    /// through the `mfa_otp` grant.
+    #[error("mfa_required")]
+    MfaRequired(MfaToken),
     /// An internal error occurred that doesn't map to a standard OAuth2 error.
     #[error("internal_error")]
     InternalError(String),
@@ -89,10 +103,48 @@ impl Oauth2ErrorType {
             Oauth2ErrorType::UnauthorizedClient => {
                 "The client is not authorized to request a token using this method.".to_string()
             }
+            Oauth2ErrorType::AccessDenied => {
+                "The resource owner or authorization server denied the request.".to_string()
+            }
+            Oauth2ErrorType::UnsupportedResponseType => {
+                "The authorization server does not support obtaining a response using this method.".to_string()
+            }
+            Oauth2ErrorType::MfaRequired(_) => {
+                "Multi-factor authentication is required, send a one-time password with the mfa_token.".to_string()
+            }
             Oauth2ErrorType::ServerError => "An internal server error has occurred".to_string(),
             Oauth2ErrorType::InternalError(s) => s.to_string()
         }
     }
+
+    /// Builds the response for an error at the authorization endpoint.
+    ///
+    /// Once the client and its redirect URI have been validated, errors are reported by
+    /// redirecting back to the client with the error in the query, as specified in
+    /// RFC 6749, Section 4.1.2.1.
+    ///
+    /// # Parameters
+    ///
+    /// * `redirect_uri` - The validated redirect URI of the authorization request
+    /// * `state` - The `state` of the authorization request, which has to be sent back
+    ///
+    /// # Returns
+    ///
+    /// A `302 Found` response redirecting to the client.
+    pub fn redirect(&self, redirect_uri: &RedirectUri, state: Option<&str>) -> HttpResponse {
+        let mut params = vec![
+            ("error", self.to_string()),
+            ("error_description", self.get_description()),
+        ];
+
+        if let Some(state) = state {
+            params.push(("state", state.to_string()));
+        }
+
+        HttpResponse::Found()
+            .insert_header((header::LOCATION, redirect_uri.with_query(params)))
+            .finish()
+    }
 }
 
 impl ResponseError for Oauth2ErrorType {
@@ -115,6 +167,9 @@ impl ResponseError for Oauth2ErrorType {
             Oauth2ErrorType::InvalidScope => StatusCode::BAD_REQUEST,
             Oauth2ErrorType::InvalidClient => StatusCode::UNAUTHORIZED,
             Oauth2ErrorType::UnauthorizedClient => StatusCode::FORBIDDEN,
+            Oauth2ErrorType::AccessDenied => StatusCode::FORBIDDEN,
+            Oauth2ErrorType::UnsupportedResponseType => StatusCode::BAD_REQUEST,
+            Oauth2ErrorType::MfaRequired(_) => StatusCode::FORBIDDEN,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -124,7 +179,8 @@ impl ResponseError for Oauth2ErrorType {
     /// The response includes:
     /// - Appropriate HTTP status code
     /// - JSON content type header
-    /// - JSON body with error and error_description fields
+    /// - JSON body with error and error_description fields, and mfa_token for
+    ///   [`Oauth2ErrorType::MfaRequired`]
     ///
     /// This method also logs the error at the error level.
     ///
@@ -135,9 +191,17 @@ impl ResponseError for Oauth2ErrorType {
         let oauth_error = Oauth2Error {
             error: self.to_string(),
             error_description: self.get_description(),
+            mfa_token: match self {
+                Oauth2ErrorType::MfaRequired(token) => Some(token.secret().to_string()),
+                _ => None,
+            },
         };
 
-        error!("Error occurred when handling OAuth request: {oauth_error:?}");
+        // Logged without the MFA token, it stands in for the first factor until it expires.
+        error!(
+            "Error occurred when handling OAuth request: {}: {}",
+            oauth_error.error, oauth_error.error_description
+        );
 
         let json = serde_json::to_string(&oauth_error).unwrap_or_else(|_| "{\"error\":\"server_error\",\"error_description\":\"An internal server error occurred.\"}".to_string());
 
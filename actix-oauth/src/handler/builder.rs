@@ -1,11 +1,12 @@
 use super::OAuth2Handler;
 use crate::handler::default::{
     NotImplementedAuthCodeHandler, NotImplementedAuthorizationHandler,
-    NotImplementedClientCredentialsHandler, NotImplementedPasswordHandler,
-    NotImplementedRefreshTokenHandler,
+    NotImplementedClientCredentialsHandler, NotImplementedMfaOtpHandler,
+    NotImplementedPasswordHandler, NotImplementedRefreshTokenHandler,
 };
 
 use crate::traits::*;
+use crate::types::ScopeRegistry;
 
 pub struct OAuth2HandlerBuilder<
     PH = NotImplementedPasswordHandler,
@@ -13,18 +14,22 @@ pub struct OAuth2HandlerBuilder<
     CH = NotImplementedClientCredentialsHandler,
     RH = NotImplementedRefreshTokenHandler,
     AuthH = NotImplementedAuthorizationHandler,
+    MH = NotImplementedMfaOtpHandler,
 > where
     PH: PasswordHandler,
     AH: AuthCodeHandler,
     CH: ClientCredentialsHandler,
     RH: RefreshTokenHandler,
     AuthH: AuthorizationHandler,
+    MH: MfaOtpHandler,
 {
     password_grant_handler: PH,
     authorization_code_grant_handler: AH,
     client_credentials_grant_handler: CH,
     refresh_token_handler: RH,
     authorization_handler: AuthH,
+    mfa_otp_handler: MH,
+    scopes: ScopeRegistry,
 }
 
 impl OAuth2HandlerBuilder {
@@ -34,19 +39,20 @@ impl OAuth2HandlerBuilder {
     }
 }
 
-impl<PH, AH, CH, RH, AuthH> OAuth2HandlerBuilder<PH, AH, CH, RH, AuthH>
+impl<PH, AH, CH, RH, AuthH, MH> OAuth2HandlerBuilder<PH, AH, CH, RH, AuthH, MH>
 where
     PH: PasswordHandler,
     AH: AuthCodeHandler,
     CH: ClientCredentialsHandler,
     RH: RefreshTokenHandler,
     AuthH: AuthorizationHandler,
+    MH: MfaOtpHandler,
 {
     #[inline(always)]
     pub fn password_handler<NewPH>(
         self,
         handler: NewPH,
-    ) -> OAuth2HandlerBuilder<NewPH, AH, CH, RH, AuthH>
+    ) -> OAuth2HandlerBuilder<NewPH, AH, CH, RH, AuthH, MH>
     where
         NewPH: PasswordHandler,
     {
@@ -56,6 +62,8 @@ where
             client_credentials_grant_handler: self.client_credentials_grant_handler,
             refresh_token_handler: self.refresh_token_handler,
             authorization_handler: self.authorization_handler,
+            mfa_otp_handler: self.mfa_otp_handler,
+            scopes: self.scopes,
         }
     }
 
@@ -63,7 +71,7 @@ where
     pub fn authorization_code_handler<NewAH>(
         self,
         handler: NewAH,
-    ) -> OAuth2HandlerBuilder<PH, NewAH, CH, RH, AuthH>
+    ) -> OAuth2HandlerBuilder<PH, NewAH, CH, RH, AuthH, MH>
     where
         NewAH: AuthCodeHandler,
     {
@@ -73,6 +81,8 @@ where
             client_credentials_grant_handler: self.client_credentials_grant_handler,
             refresh_token_handler: self.refresh_token_handler,
             authorization_handler: self.authorization_handler,
+            mfa_otp_handler: self.mfa_otp_handler,
+            scopes: self.scopes,
         }
     }
 
@@ -80,7 +90,7 @@ where
     pub fn client_credentials_handler<NewCH>(
         self,
         handler: NewCH,
-    ) -> OAuth2HandlerBuilder<PH, AH, NewCH, RH, AuthH>
+    ) -> OAuth2HandlerBuilder<PH, AH, NewCH, RH, AuthH, MH>
     where
         NewCH: ClientCredentialsHandler,
     {
@@ -90,6 +100,8 @@ where
             client_credentials_grant_handler: handler,
             refresh_token_handler: self.refresh_token_handler,
             authorization_handler: self.authorization_handler,
+            mfa_otp_handler: self.mfa_otp_handler,
+            scopes: self.scopes,
         }
     }
 
@@ -97,7 +109,7 @@ where
     pub fn refresh_handler<NewRH>(
         self,
         handler: NewRH,
-    ) -> OAuth2HandlerBuilder<PH, AH, CH, NewRH, AuthH>
+    ) -> OAuth2HandlerBuilder<PH, AH, CH, NewRH, AuthH, MH>
     where
         NewRH: RefreshTokenHandler,
     {
@@ -107,6 +119,8 @@ where
             client_credentials_grant_handler: self.client_credentials_grant_handler,
             refresh_token_handler: handler,
             authorization_handler: self.authorization_handler,
+            mfa_otp_handler: self.mfa_otp_handler,
+            scopes: self.scopes,
         }
     }
 
@@ -114,7 +128,7 @@ where
     pub fn authorization_handler<NewAuthH>(
         self,
         handler: NewAuthH,
-    ) -> OAuth2HandlerBuilder<PH, AH, CH, RH, NewAuthH>
+    ) -> OAuth2HandlerBuilder<PH, AH, CH, RH, NewAuthH, MH>
     where
         NewAuthH: AuthorizationHandler,
     {
@@ -124,17 +138,49 @@ where
             client_credentials_grant_handler: self.client_credentials_grant_handler,
             refresh_token_handler: self.refresh_token_handler,
             authorization_handler: handler,
+            mfa_otp_handler: self.mfa_otp_handler,
+            scopes: self.scopes,
         }
     }
 
+    /// Sets the handler for the `mfa_otp` grant, which completes grants that failed with
+    /// [`Oauth2ErrorType::MfaRequired`](crate::error::Oauth2ErrorType::MfaRequired).
     #[inline(always)]
-    pub fn build(self) -> OAuth2Handler<PH, AH, CH, RH, AuthH> {
+    pub fn mfa_otp_handler<NewMH>(
+        self,
+        handler: NewMH,
+    ) -> OAuth2HandlerBuilder<PH, AH, CH, RH, AuthH, NewMH>
+    where
+        NewMH: MfaOtpHandler,
+    {
+        OAuth2HandlerBuilder {
+            password_grant_handler: self.password_grant_handler,
+            authorization_code_grant_handler: self.authorization_code_grant_handler,
+            client_credentials_grant_handler: self.client_credentials_grant_handler,
+            refresh_token_handler: self.refresh_token_handler,
+            authorization_handler: self.authorization_handler,
+            mfa_otp_handler: handler,
+            scopes: self.scopes,
+        }
+    }
+
+    /// Sets the scopes the server knows about.
+    #[inline(always)]
+    pub fn scopes(mut self, scopes: ScopeRegistry) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
+    #[inline(always)]
+    pub fn build(self) -> OAuth2Handler<PH, AH, CH, RH, AuthH, MH> {
         OAuth2Handler {
             password_grant_handler: self.password_grant_handler,
             authorization_code_grant_handler: self.authorization_code_grant_handler,
             client_credentials_grant_handler: self.client_credentials_grant_handler,
             refresh_token_handler: self.refresh_token_handler,
             authorization_handler: self.authorization_handler,
+            mfa_otp_handler: self.mfa_otp_handler,
+            scopes: self.scopes,
         }
     }
 }
@@ -148,6 +194,8 @@ impl Default for OAuth2HandlerBuilder {
             client_credentials_grant_handler: NotImplementedClientCredentialsHandler,
             refresh_token_handler: NotImplementedRefreshTokenHandler,
             authorization_handler: NotImplementedAuthorizationHandler,
+            mfa_otp_handler: NotImplementedMfaOtpHandler,
+            scopes: ScopeRegistry::default(),
         }
     }
 }
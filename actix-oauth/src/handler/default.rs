@@ -1,28 +1,36 @@
 use crate::dto::AuthorizationRequest;
-use crate::error::Oauth2ErrorType;
 use crate::handler::{AuthorizationReturn, HandlerReturn};
 use crate::oauth2_handler;
+use crate::traits::{
+    AuthCodeHandler, AuthorizationHandler, ClientCredentialsHandler, MfaOtpHandler,
+    PasswordHandler, RefreshTokenHandler,
+};
 use crate::types::{
-    AuthorizationCode, ClientId, ClientSecret, Password, RedirectUri, RefreshToken, Username,
+    AuthorizationCode, ClientId, ClientSecret, CodeVerifier, MfaToken, Password, RedirectUri,
+    RefreshToken, Username,
 };
 use actix_web::HttpRequest;
 
 oauth2_handler! {
-    pub fn NotImplementedPasswordHandler(_ => (HttpRequest, Username, Password)) -> HandlerReturn
+    impl PasswordHandler for pub NotImplementedPasswordHandler(_req: HttpRequest, _username: Username, _password: Password) -> HandlerReturn
+}
+
+oauth2_handler! {
+    impl AuthCodeHandler for pub NotImplementedAuthCodeHandler(_req: HttpRequest, _code: AuthorizationCode, _redirect_uri: RedirectUri, _client_id: ClientId, _client_secret: Option<ClientSecret>, _code_verifier: Option<CodeVerifier>) -> HandlerReturn
 }
 
 oauth2_handler! {
-    pub fn NotImplementedAuthCodeHandler(_ => (HttpRequest, AuthorizationCode, RedirectUri, ClientId, ClientSecret)) -> HandlerReturn
+    impl ClientCredentialsHandler for pub NotImplementedClientCredentialsHandler(_req: HttpRequest, _client_id: ClientId, _client_secret: ClientSecret) -> HandlerReturn
 }
 
 oauth2_handler! {
-    pub fn NotImplementedClientCredentialsHandler(_ => (HttpRequest, ClientId, ClientSecret)) -> HandlerReturn
+    impl RefreshTokenHandler for pub NotImplementedRefreshTokenHandler(_req: HttpRequest, _client_id: Option<ClientId>, _client_secret: Option<ClientSecret>, _refresh_token: RefreshToken) -> HandlerReturn
 }
 
 oauth2_handler! {
-    pub fn NotImplementedRefreshTokenHandler(_ => (HttpRequest, Option<ClientId>, Option<ClientSecret>, RefreshToken)) -> HandlerReturn
+    impl MfaOtpHandler for pub NotImplementedMfaOtpHandler(_req: HttpRequest, _mfa_token: MfaToken, _otp: String) -> HandlerReturn
 }
 
 oauth2_handler! {
-    pub fn NotImplementedAuthorizationHandler(_ => (HttpRequest, AuthorizationRequest)) -> AuthorizationReturn
+    impl AuthorizationHandler for pub NotImplementedAuthorizationHandler(_req: HttpRequest, _auth_req: AuthorizationRequest) -> AuthorizationReturn
 }
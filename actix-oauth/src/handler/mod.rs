@@ -6,6 +6,7 @@
 //! - Authorization code grant
 //! - Client credentials grant
 //! - Refresh token grant
+//! - MFA one-time password grant, the second step of grants that require MFA
 //!
 //! # Usage
 //!
@@ -27,16 +28,20 @@
 
 mod builder;
 pub mod default;
-pub(crate) mod docs;
 pub use builder::OAuth2HandlerBuilder;
 use default::*;
 
 use crate::dto::OauthRequest;
 use crate::dto::token_response::TokenResponse;
 use crate::error::Oauth2ErrorType;
+use crate::openapi::OAuth2SecurityScheme;
 use crate::traits::*;
+use crate::types::ScopeRegistry;
 use actix_web::dev::{AppService, HttpServiceFactory};
 use actix_web::{HttpRequest, HttpResponse};
+use utoipa::openapi::security::{
+    AuthorizationCode, ClientCredentials, Flow, Password, Scopes as OpenApiScopes,
+};
 
 /// Result type for token endpoint operations
 pub type HandlerReturn = Result<TokenResponse, Oauth2ErrorType>;
@@ -57,6 +62,7 @@ pub type AuthorizationReturn = Result<HttpResponse, Oauth2ErrorType>;
 /// * `CH` - Client credentials grant handler, must implement [`ClientCredentialsHandler`] trait
 /// * `RH` - Refresh token grant handler, must implement [`RefreshTokenHandler`] trait
 /// * `AuthH` - Authorization endpoint handler, must implement [`AuthorizationHandler`] trait
+/// * `MH` - MFA one-time password grant handler, must implement [`MfaOtpHandler`] trait
 ///
 /// # Examples
 ///
@@ -78,18 +84,22 @@ pub struct OAuth2Handler<
     CH = NotImplementedClientCredentialsHandler,
     RH = NotImplementedRefreshTokenHandler,
     AuthH = NotImplementedAuthorizationHandler,
+    MH = NotImplementedMfaOtpHandler,
 > where
     PH: PasswordHandler,
     AH: AuthCodeHandler,
     CH: ClientCredentialsHandler,
     RH: RefreshTokenHandler,
     AuthH: AuthorizationHandler,
+    MH: MfaOtpHandler,
 {
     password_grant_handler: PH,
     authorization_code_grant_handler: AH,
     client_credentials_grant_handler: CH,
     refresh_token_handler: RH,
     authorization_handler: AuthH,
+    mfa_otp_handler: MH,
+    scopes: ScopeRegistry,
 }
 
 impl Default for OAuth2Handler {
@@ -108,13 +118,14 @@ impl Default for OAuth2Handler {
     }
 }
 
-impl<PH, AH, CH, RH, AuthH> OAuth2Manager for OAuth2Handler<PH, AH, CH, RH, AuthH>
+impl<PH, AH, CH, RH, AuthH, MH> OAuth2Manager for OAuth2Handler<PH, AH, CH, RH, AuthH, MH>
 where
     PH: PasswordHandler,
     AH: AuthCodeHandler,
     CH: ClientCredentialsHandler,
     RH: RefreshTokenHandler,
     AuthH: AuthorizationHandler,
+    MH: MfaOtpHandler,
 {
     /// Processes token requests according to the OAuth2 specification.
     ///
@@ -139,7 +150,7 @@ where
         match oauth_req {
             OauthRequest::Password { username, password } => {
                 self.password_grant_handler
-                    .async_call((req, username, password))
+                    .handle(req, username, password)
                     .await
             }
             OauthRequest::AuthorizationCode {
@@ -147,9 +158,17 @@ where
                 redirect_uri,
                 client_id,
                 client_secret,
+                code_verifier,
             } => {
                 self.authorization_code_grant_handler
-                    .async_call((req, code, redirect_uri, client_id, client_secret))
+                    .handle(
+                        req,
+                        code,
+                        redirect_uri,
+                        client_id,
+                        client_secret,
+                        code_verifier,
+                    )
                     .await
             }
             OauthRequest::ClientCredentials {
@@ -157,7 +176,7 @@ where
                 client_secret,
             } => {
                 self.client_credentials_grant_handler
-                    .async_call((req, client_id, client_secret))
+                    .handle(req, client_id, client_secret)
                     .await
             }
             OauthRequest::RefreshToken {
@@ -166,9 +185,12 @@ where
                 refresh_token,
             } => {
                 self.refresh_token_handler
-                    .async_call((req, client_id, client_secret, refresh_token))
+                    .handle(req, client_id, client_secret, refresh_token)
                     .await
             }
+            OauthRequest::MfaOtp { mfa_token, otp } => {
+                self.mfa_otp_handler.handle(req, mfa_token, otp).await
+            }
         }
     }
 
@@ -181,15 +203,75 @@ where
     fn authorization_handler(&self) -> impl AuthorizationHandler {
         self.authorization_handler.clone()
     }
+
+    /// Builds the OpenAPI security scheme from the configured handlers.
+    ///
+    /// The refresh URL is only documented when a refresh token handler is configured, and
+    /// the authorization code flow requires both the code exchange and the authorization
+    /// endpoint handler. The token endpoint is documented when any grant is configured, the
+    /// authorization endpoint when its handler is.
+    fn security_scheme(&self, base_path: &str) -> OAuth2SecurityScheme {
+        let base_path = base_path.trim_end_matches('/');
+        let token_url = format!("{base_path}{TOKEN_ENDPOINT}");
+        let authorization_url = format!("{base_path}{AUTHORIZATION_ENDPOINT}");
+        let refresh_url = RH::IMPLEMENTED.then(|| token_url.clone());
+        let scopes = OpenApiScopes::from(&self.scopes);
+
+        let mut flows = Vec::new();
+
+        if PH::IMPLEMENTED {
+            flows.push(Flow::Password(match &refresh_url {
+                Some(refresh_url) => {
+                    Password::with_refresh_url(&token_url, scopes.clone(), refresh_url)
+                }
+                None => Password::new(&token_url, scopes.clone()),
+            }));
+        }
+
+        if AH::IMPLEMENTED && AuthH::IMPLEMENTED {
+            flows.push(Flow::AuthorizationCode(match &refresh_url {
+                Some(refresh_url) => AuthorizationCode::with_refresh_url(
+                    &authorization_url,
+                    &token_url,
+                    scopes.clone(),
+                    refresh_url,
+                ),
+                None => AuthorizationCode::new(&authorization_url, &token_url, scopes.clone()),
+            }));
+        }
+
+        if CH::IMPLEMENTED {
+            flows.push(Flow::ClientCredentials(ClientCredentials::new(
+                &token_url, scopes,
+            )));
+        }
+
+        let mut scheme = OAuth2SecurityScheme::new(flows);
+
+        if PH::IMPLEMENTED
+            || AH::IMPLEMENTED
+            || CH::IMPLEMENTED
+            || RH::IMPLEMENTED
+            || MH::IMPLEMENTED
+        {
+            scheme = scheme.token_path(token_url);
+        }
+        if AuthH::IMPLEMENTED {
+            scheme = scheme.authorization_path(authorization_url);
+        }
+
+        scheme
+    }
 }
 
-impl<PH, AH, CH, RH, AuthH> HttpServiceFactory for OAuth2Handler<PH, AH, CH, RH, AuthH>
+impl<PH, AH, CH, RH, AuthH, MH> HttpServiceFactory for OAuth2Handler<PH, AH, CH, RH, AuthH, MH>
 where
     PH: PasswordHandler,
     AH: AuthCodeHandler,
     CH: ClientCredentialsHandler,
     RH: RefreshTokenHandler,
     AuthH: AuthorizationHandler,
+    MH: MfaOtpHandler,
 {
     /// Registers the OAuth2 handler with the Actix web application.
     ///
@@ -1,12 +1,12 @@
-#![feature(unboxed_closures)]
-#![feature(async_fn_traits)]
-
 use std::collections::BTreeMap;
 use utoipa::{Modify, OpenApi};
 
+#[cfg(feature = "client")]
+pub mod client;
 pub mod dto;
 pub mod error;
 pub mod handler;
+pub mod openapi;
 pub mod traits;
 pub mod types;
 mod utils;
@@ -34,7 +34,6 @@ impl Modify for NormalizePath {
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(handler::docs::token, handler::docs::authorize),
     components(
         schemas(
             RefreshToken,
@@ -53,7 +52,12 @@ impl Modify for NormalizePath {
             ResponseType,
             OAuthClientDTO,
             OAuthCreateClientDTO,
-            OAuthUpdateClientDTO
+            OAuthUpdateClientDTO,
+            OAuthRedirectUrisDTO,
+            OAuthRedirectUriDTO,
+            OAuthClientSecretDTO,
+            OAuthRotateClientSecretDTO,
+            ClientType
         ),
         responses(TokenResponse)
     ),
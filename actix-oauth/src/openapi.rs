@@ -0,0 +1,204 @@
+//! OpenAPI documentation of the OAuth2 grants a server supports.
+//!
+//! The [`OAuth2SecurityScheme`] is created from a configured handler through
+//! [`OAuth2Manager::security_scheme`](crate::traits::OAuth2Manager::security_scheme) and adds an
+//! `oauth2` security scheme to the OpenAPI document. Tools such as Swagger UI and Scalar use
+//! it to let the reader authorize against the running server. The token and authorization
+//! endpoints the handler serves are documented next to it.
+
+use crate::dto::{AuthorizationRequest, Oauth2ErrorResponses, OauthRequest, TokenResponse};
+use utoipa::openapi::security::{Flow, OAuth2, SecurityScheme};
+use utoipa::openapi::{ComponentsBuilder, OpenApi};
+use utoipa::{Modify, Path};
+
+/// OpenAPI `oauth2` security scheme describing the configured grant flows.
+///
+/// Implements [`Modify`] so it can be passed to `#[openapi(modifiers(...))]` or applied
+/// to an existing [`OpenApi`] document. The scheme is only added if flows are configured, and
+/// the endpoints only if their paths are set.
+///
+/// # Example
+///
+/// ```
+/// use actix_oauth::handler::OAuth2Handler;
+/// use actix_oauth::traits::OAuth2Manager;
+/// use utoipa::Modify;
+/// use utoipa::openapi::OpenApiBuilder;
+///
+/// let mut openapi = OpenApiBuilder::new().build();
+///
+/// OAuth2Handler::default()
+///     .security_scheme("/api")
+///     .modify(&mut openapi);
+/// ```
+#[derive(Debug, Clone)]
+pub struct OAuth2SecurityScheme {
+    name: String,
+    description: Option<String>,
+    flows: Vec<Flow>,
+    token_path: Option<String>,
+    authorization_path: Option<String>,
+}
+
+impl OAuth2SecurityScheme {
+    /// Name the scheme is registered under unless changed with [`OAuth2SecurityScheme::name`].
+    pub const DEFAULT_NAME: &'static str = "oauth2";
+
+    pub fn new(flows: impl IntoIterator<Item = Flow>) -> Self {
+        Self {
+            name: Self::DEFAULT_NAME.to_string(),
+            description: None,
+            flows: flows.into_iter().collect(),
+            token_path: None,
+            authorization_path: None,
+        }
+    }
+
+    /// Sets the name the scheme is registered under in the OpenAPI components.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Documents the token endpoint at `path`.
+    pub fn token_path(mut self, path: impl Into<String>) -> Self {
+        self.token_path = Some(path.into());
+        self
+    }
+
+    /// Documents the authorization endpoint at `path`.
+    pub fn authorization_path(mut self, path: impl Into<String>) -> Self {
+        self.authorization_path = Some(path.into());
+        self
+    }
+
+    /// Returns `true` if no grant flows are configured.
+    pub fn is_empty(&self) -> bool {
+        self.flows.is_empty()
+    }
+
+    pub fn flows(&self) -> &[Flow] {
+        &self.flows
+    }
+}
+
+impl Modify for OAuth2SecurityScheme {
+    fn modify(&self, openapi: &mut OpenApi) {
+        if let Some(path) = &self.token_path {
+            openapi.paths.add_path_operation(
+                path,
+                <__path_token as Path>::methods(),
+                <__path_token as Path>::operation(),
+            );
+        }
+        if let Some(path) = &self.authorization_path {
+            openapi.paths.add_path_operation(
+                path,
+                <__path_authorize as Path>::methods(),
+                <__path_authorize as Path>::operation(),
+            );
+        }
+
+        if self.is_empty() {
+            return;
+        }
+
+        let flows = self.flows.iter().cloned();
+        let scheme = SecurityScheme::OAuth2(match &self.description {
+            Some(description) => OAuth2::with_description(flows, description.clone()),
+            None => OAuth2::new(flows),
+        });
+
+        match &mut openapi.components {
+            Some(components) => components.add_security_scheme(self.name.clone(), scheme),
+            None => {
+                openapi.components = Some(
+                    ComponentsBuilder::new()
+                        .security_scheme(self.name.clone(), scheme)
+                        .build(),
+                )
+            }
+        }
+    }
+}
+
+// The operations of the endpoints, their paths are replaced with the ones set on the scheme.
+
+/// Exchange credentials for an access token.
+///
+/// Supports Form data, Json or query params.
+#[utoipa::path(
+    post,
+    path = "/oauth/token",
+    tags = ["OAuth"],
+    request_body(
+        description = "The OAuth2 grants, only the grants documented in the `oauth2` security scheme are served. *Note that it can be sent as a query param as well but its not recommended*",
+        content(
+            (OauthRequest = "application/json"),
+            (OauthRequest = "application/x-www-form-urlencoded")
+        )
+    ),
+    responses(
+        Oauth2ErrorResponses,
+        (status = 200, description = "Successfully got a access token", body = TokenResponse)
+    )
+)]
+#[allow(dead_code)]
+fn token() {}
+
+/// Start an authorization request.
+///
+/// Signs the user in and asks for their consent, then redirects back to the client with a
+/// short-lived code that can be exchanged for a token.
+#[utoipa::path(
+    method(get, post),
+    path = "/oauth/authorize",
+    tags = ["OAuth"],
+    params(AuthorizationRequest),
+    responses(
+        Oauth2ErrorResponses,
+        (status = 200, description = "The login or consent page"),
+        (status = 302, description = "Redirects to the client with the code in the query params")
+    )
+)]
+#[allow(dead_code)]
+fn authorize() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utoipa::openapi::OpenApiBuilder;
+
+    #[test]
+    fn documents_only_the_configured_endpoints() {
+        let mut openapi = OpenApiBuilder::new().build();
+
+        OAuth2SecurityScheme::new([])
+            .token_path("/api/oauth/token")
+            .modify(&mut openapi);
+
+        let token = &openapi.paths.paths["/api/oauth/token"];
+        assert!(token.post.is_some());
+        assert!(token.get.is_none());
+        assert!(!openapi.paths.paths.contains_key("/api/oauth/authorize"));
+        assert!(openapi.components.is_none());
+    }
+
+    #[test]
+    fn documents_both_methods_of_the_authorization_endpoint() {
+        let mut openapi = OpenApiBuilder::new().build();
+
+        OAuth2SecurityScheme::new([])
+            .authorization_path("/api/oauth/authorize")
+            .modify(&mut openapi);
+
+        let authorize = &openapi.paths.paths["/api/oauth/authorize"];
+        assert!(authorize.get.is_some());
+        assert!(authorize.post.is_some());
+    }
+}
@@ -1,18 +1,19 @@
 //! Authorization Code grant type handler for OAuth2.
 //!
 //! This module provides the [`AuthCodeHandler`] trait for implementing the
-//! Authorization Code grant type as specified in RFC 6749, Section 4.1.
+//! Authorization Code grant type as specified in RFC 6749, Section 4.1, with PKCE as
+//! specified in RFC 7636.
 
 use crate::handler::HandlerReturn;
-use crate::types::{AuthorizationCode, ClientId, ClientSecret, RedirectUri};
+use crate::types::{AuthorizationCode, ClientId, ClientSecret, CodeVerifier, RedirectUri};
 use actix_web::HttpRequest;
 
 /// Handler for the OAuth2 Authorization Code grant type.
 ///
 /// This trait is implemented for types that can process OAuth2 Authorization Code
-/// grant requests according to RFC 6749, Section 4.1. This grant type is used for
-/// applications such as server-side web applications where the client secret can
-/// be kept confidential.
+/// grant requests according to RFC 6749, Section 4.1. Confidential clients authenticate with
+/// their secret, public clients such as native and browser applications cannot keep a secret
+/// and prove they started the authorization request with a PKCE code verifier instead.
 ///
 /// # Parameters
 ///
@@ -20,24 +21,19 @@ use actix_web::HttpRequest;
 /// * [`AuthorizationCode`] - The authorization code received from the authorization server
 /// * [`RedirectUri`] - The redirect URI that was used in the authorization request
 /// * [`ClientId`] - The client identifier
-/// * [`ClientSecret`] - The client secret for authentication
+/// * `Option<`[`ClientSecret`]`>` - The client secret for authentication, left out by public clients
+/// * `Option<`[`CodeVerifier`]`>` - The PKCE code verifier, if the authorization request had a code challenge
 ///
 /// # Returns
 ///
 /// * [`HandlerReturn`] - A Result containing either a [TokenResponse](crate::TokenResponse) or an [Oauth2ErrorType](crate::error::Oauth2ErrorType)
 ///
-/// # Implementation
-///
-/// This trait is automatically implemented for any type that satisfies the required
-/// function signature. You don't need to manually implement the trait, just provide
-/// a function with the correct signature.
-///
 /// # Example
 ///
 /// ```
 /// use actix_oauth::traits::AuthCodeHandler;
 /// use actix_oauth::handler::HandlerReturn;
-/// use actix_oauth::types::{AuthorizationCode, ClientId, ClientSecret, RedirectUri};
+/// use actix_oauth::types::{AuthorizationCode, ClientId, ClientSecret, CodeVerifier, RedirectUri};
 /// use actix_oauth::dto::token_response::TokenResponse;
 /// use actix_oauth::error::Oauth2ErrorType;
 /// use actix_web::HttpRequest;
@@ -47,12 +43,14 @@ use actix_web::HttpRequest;
 ///     code: AuthorizationCode,
 ///     redirect_uri: RedirectUri,
 ///     client_id: ClientId,
-///     client_secret: ClientSecret,
+///     client_secret: Option<ClientSecret>,
+///     code_verifier: Option<CodeVerifier>,
 /// ) -> HandlerReturn {
 ///     // 1. Validate the authorization code
 ///     // 2. Verify the redirect URI matches the one used for the authorization request
 ///     // 3. Authenticate the client (verify client_id and client_secret)
-///     // 4. Generate access token, refresh token, etc.
+///     // 4. Verify the code verifier against the code challenge of the authorization request
+///     // 5. Generate access token, refresh token, etc.
 ///
 ///     if code.secret().is_empty() {
 ///         return Err(Oauth2ErrorType::InvalidGrant);
@@ -72,26 +70,55 @@ use actix_web::HttpRequest;
     message = "`{Self}` must be able to process authorization code requests",
     label = "this type doesn't implement the required function signature for handling authorization code grants"
 )]
-pub trait AuthCodeHandler:
-    AsyncFn(HttpRequest, AuthorizationCode, RedirectUri, ClientId, ClientSecret) -> HandlerReturn
-    + Send
-    + Sync
-    + Clone
-    + 'static
-{
+pub trait AuthCodeHandler: Send + Sync + Clone + 'static {
+    /// Whether the handler serves the authorization code grant.
+    const IMPLEMENTED: bool = true;
+
+    /// Exchanges an authorization code for a token.
+    fn handle(
+        &self,
+        req: HttpRequest,
+        code: AuthorizationCode,
+        redirect_uri: RedirectUri,
+        client_id: ClientId,
+        client_secret: Option<ClientSecret>,
+        code_verifier: Option<CodeVerifier>,
+    ) -> impl Future<Output = HandlerReturn>;
 }
 
-impl<T> AuthCodeHandler for T where
-    T: AsyncFn(
+impl<F, Fut> AuthCodeHandler for F
+where
+    F: Fn(
             HttpRequest,
             AuthorizationCode,
             RedirectUri,
             ClientId,
-            ClientSecret,
-        ) -> HandlerReturn
+            Option<ClientSecret>,
+            Option<CodeVerifier>,
+        ) -> Fut
         + Send
         + Sync
         + Clone
-        + 'static
+        + 'static,
+    Fut: Future<Output = HandlerReturn>,
 {
+    #[inline]
+    fn handle(
+        &self,
+        req: HttpRequest,
+        code: AuthorizationCode,
+        redirect_uri: RedirectUri,
+        client_id: ClientId,
+        client_secret: Option<ClientSecret>,
+        code_verifier: Option<CodeVerifier>,
+    ) -> impl Future<Output = HandlerReturn> {
+        (self)(
+            req,
+            code,
+            redirect_uri,
+            client_id,
+            client_secret,
+            code_verifier,
+        )
+    }
 }
@@ -23,12 +23,6 @@ use actix_web::HttpRequest;
 ///
 /// * [`AuthorizationReturn`] - A Result containing either a [HttpResponse](actix_web::HttpResponse) or an [Oauth2ErrorType](crate::error::Oauth2ErrorType)
 ///
-/// # Implementation
-///
-/// This trait is automatically implemented for any type that satisfies the required
-/// function signature. You don't need to manually implement the trait, just provide
-/// a function with the correct signature.
-///
 /// # Example
 ///
 /// ```
@@ -69,16 +63,29 @@ use actix_web::HttpRequest;
     message = "`{Self}` must be able to process authorization endpoint requests",
     label = "this type doesn't implement the required function signature for handling authorization requests"
 )]
-pub trait AuthorizationHandler:
-    AsyncFn(HttpRequest, AuthorizationRequest) -> AuthorizationReturn + Send + Sync + Clone + 'static
-{
+pub trait AuthorizationHandler: Send + Sync + Clone + 'static {
+    /// Whether the handler serves the authorization endpoint.
+    const IMPLEMENTED: bool = true;
+
+    /// Processes a request made to the authorization endpoint.
+    fn handle(
+        &self,
+        req: HttpRequest,
+        auth_req: AuthorizationRequest,
+    ) -> impl Future<Output = AuthorizationReturn>;
 }
 
-impl<T> AuthorizationHandler for T where
-    T: AsyncFn(HttpRequest, AuthorizationRequest) -> AuthorizationReturn
-        + Send
-        + Sync
-        + Clone
-        + 'static
+impl<F, Fut> AuthorizationHandler for F
+where
+    F: Fn(HttpRequest, AuthorizationRequest) -> Fut + Send + Sync + Clone + 'static,
+    Fut: Future<Output = AuthorizationReturn>,
 {
+    #[inline]
+    fn handle(
+        &self,
+        req: HttpRequest,
+        auth_req: AuthorizationRequest,
+    ) -> impl Future<Output = AuthorizationReturn> {
+        (self)(req, auth_req)
+    }
 }
@@ -23,12 +23,6 @@ use actix_web::HttpRequest;
 ///
 /// * [`HandlerReturn`] - A Result containing either a [TokenResponse](crate::TokenResponse) or an [Oauth2ErrorType](crate::error::Oauth2ErrorType)
 ///
-/// # Implementation
-///
-/// This trait is automatically implemented for any type that satisfies the required
-/// function signature. You don't need to manually implement the trait, just provide
-/// a function with the correct signature.
-///
 /// # Example
 ///
 /// ```
@@ -70,16 +64,31 @@ use actix_web::HttpRequest;
     message = "`{Self}` must be able to process client credentials requests",
     label = "this type doesn't implement the required function signature for handling client credentials grants"
 )]
-pub trait ClientCredentialsHandler:
-    AsyncFn(HttpRequest, ClientId, ClientSecret) -> HandlerReturn + Send + Sync + Clone + 'static
-{
+pub trait ClientCredentialsHandler: Send + Sync + Clone + 'static {
+    /// Whether the handler serves the client credentials grant.
+    const IMPLEMENTED: bool = true;
+
+    /// Processes a client credentials grant request.
+    fn handle(
+        &self,
+        req: HttpRequest,
+        client_id: ClientId,
+        client_secret: ClientSecret,
+    ) -> impl Future<Output = HandlerReturn>;
 }
 
-impl<T> ClientCredentialsHandler for T where
-    T: AsyncFn(HttpRequest, ClientId, ClientSecret) -> HandlerReturn
-        + Send
-        + Sync
-        + Clone
-        + 'static
+impl<F, Fut> ClientCredentialsHandler for F
+where
+    F: Fn(HttpRequest, ClientId, ClientSecret) -> Fut + Send + Sync + Clone + 'static,
+    Fut: Future<Output = HandlerReturn>,
 {
+    #[inline]
+    fn handle(
+        &self,
+        req: HttpRequest,
+        client_id: ClientId,
+        client_secret: ClientSecret,
+    ) -> impl Future<Output = HandlerReturn> {
+        (self)(req, client_id, client_secret)
+    }
 }
@@ -4,13 +4,21 @@
 //! for registering OAuth2 endpoints with an Actix web application.
 
 use crate::dto::{AuthorizationRequest, OauthRequest};
+use crate::error::Oauth2ErrorType;
 use crate::handler::HandlerReturn;
+use crate::openapi::OAuth2SecurityScheme;
 use crate::traits::authorization_handler::AuthorizationHandler;
 use actix_web::dev::{AppService, HttpServiceFactory};
-use actix_web::web::post;
+use actix_web::web::{get, post};
 use actix_web::{HttpRequest, web};
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
 
+/// Path of the token endpoint, relative to where the service is mounted.
+pub const TOKEN_ENDPOINT: &str = "/oauth/token";
+
+/// Path of the authorization endpoint, relative to where the service is mounted.
+pub const AUTHORIZATION_ENDPOINT: &str = "/oauth/authorize";
+
 /// Service wrapper for OAuth2Manager implementations.
 ///
 /// This struct wraps an [`OAuth2Manager`] implementation and provides the Actix
@@ -71,7 +79,11 @@ pub trait OAuth2Manager: Clone + 'static {
     /// # Returns
     ///
     /// * `HandlerReturn` - A Result containing either a TokenResponse or an OAuth2 error
-    async fn token_handler(&self, req: HttpRequest, oauth_req: OauthRequest) -> HandlerReturn;
+    fn token_handler(
+        &self,
+        req: HttpRequest,
+        oauth_req: OauthRequest,
+    ) -> impl Future<Output = HandlerReturn>;
     /// Returns the authorization handler.
     ///
     /// This method should return a handler for authorization requests that
@@ -81,6 +93,21 @@ pub trait OAuth2Manager: Clone + 'static {
     ///
     /// * `impl AuthorizationHandler` - The authorization handler implementation
     fn authorization_handler(&self) -> impl AuthorizationHandler;
+    /// Describes the supported grants as an OpenAPI `oauth2` security scheme.
+    ///
+    /// Only grants with an implemented handler are included, and the scopes are taken
+    /// from the configured scope registry. The token and authorization endpoints are documented
+    /// along with the scheme when they serve anything.
+    ///
+    /// # Parameters
+    ///
+    /// * `base_path` - The path the service is mounted under, used to build the
+    ///   [`TOKEN_ENDPOINT`] and [`AUTHORIZATION_ENDPOINT`] URLs
+    ///
+    /// # Returns
+    ///
+    /// * `OAuth2SecurityScheme` - A [`Modify`](utoipa::Modify) implementation adding the scheme
+    fn security_scheme(&self, base_path: &str) -> OAuth2SecurityScheme;
 }
 
 impl<T: OAuth2Manager> HttpServiceFactory for OAuth2ManagerService<T> {
@@ -88,9 +115,11 @@ impl<T: OAuth2Manager> HttpServiceFactory for OAuth2ManagerService<T> {
     ///
     /// This method sets up the following routes:
     /// - POST /oauth/token - Token endpoint for all grant types
-    /// - POST /oauth/authorize - Authorization endpoint
+    /// - GET and POST /oauth/authorize - Authorization endpoint, RFC 6749, Section 3.1 requires
+    ///   `GET` so user agents can be redirected to it
     ///
-    /// The token endpoint accepts form, JSON, or query parameters.
+    /// The token endpoint accepts form, JSON, or query parameters. Requests that cannot be
+    /// parsed are rejected with [`Oauth2ErrorType::InvalidRequest`].
     ///
     /// # Parameters
     ///
@@ -125,18 +154,43 @@ impl<T: OAuth2Manager> HttpServiceFactory for OAuth2ManagerService<T> {
                   web::Query(authorization_request): web::Query<AuthorizationRequest>| {
                 let auth_handler = auth_handler.clone();
 
-                async move { auth_handler.async_call((req, authorization_request)).await }
+                async move { auth_handler.handle(req, authorization_request).await }
             }
         };
 
-        let scope = web::scope("/oauth")
-            .route("/token", post().to(token_handler))
-            .route("/authorize", post().to(authorization_handler));
-
-        HttpServiceFactory::register(scope, config);
+        HttpServiceFactory::register(
+            web::resource(TOKEN_ENDPOINT)
+                .app_data(invalid_request_form_config())
+                .app_data(invalid_request_json_config())
+                .app_data(invalid_request_query_config())
+                .route(post().to(token_handler)),
+            config,
+        );
+        HttpServiceFactory::register(
+            web::resource(AUTHORIZATION_ENDPOINT)
+                .app_data(invalid_request_query_config())
+                .route(get().to(authorization_handler.clone()))
+                .route(post().to(authorization_handler)),
+            config,
+        );
     }
 }
 
+// Requests that fail to parse, for example because of a malformed redirect URI, are answered
+// with `invalid_request` as RFC 6749, Section 5.2 requires instead of actix's plain text errors.
+
+fn invalid_request_form_config() -> web::FormConfig {
+    web::FormConfig::default().error_handler(|_, _| Oauth2ErrorType::InvalidRequest.into())
+}
+
+fn invalid_request_json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(|_, _| Oauth2ErrorType::InvalidRequest.into())
+}
+
+fn invalid_request_query_config() -> web::QueryConfig {
+    web::QueryConfig::default().error_handler(|_, _| Oauth2ErrorType::InvalidRequest.into())
+}
+
 /// Extension trait for OAuth2Manager implementations.
 ///
 /// This trait provides convenience methods for working with OAuth2Manager
@@ -0,0 +1,84 @@
+//! MFA one-time password grant handler for OAuth2.
+//!
+//! This module provides the [`MfaOtpHandler`] trait for completing grants that failed with
+//! [`Oauth2ErrorType::MfaRequired`](crate::error::Oauth2ErrorType::MfaRequired).
+
+use crate::handler::HandlerReturn;
+use crate::types::MfaToken;
+use actix_web::HttpRequest;
+
+/// Handler for the second step of grants that require multi-factor authentication.
+///
+/// When a handler for another grant has verified the first factor but the resource owner also
+/// has to pass a second one, it fails with
+/// [`Oauth2ErrorType::MfaRequired`](crate::error::Oauth2ErrorType::MfaRequired) carrying an
+/// [`MfaToken`]. The client then sends that token with a one-time password using the
+/// `mfa_otp` grant type, which is handled by this trait.
+///
+/// # Parameters
+///
+/// * [`HttpRequest`] - The incoming HTTP request containing headers and context
+/// * [`MfaToken`] - The token from the `mfa_required` error
+/// * [`String`] - The one-time password the resource owner entered
+///
+/// # Returns
+///
+/// * [`HandlerReturn`] - A Result containing either a [TokenResponse](crate::TokenResponse) or an [Oauth2ErrorType](crate::error::Oauth2ErrorType)
+///
+/// # Example
+///
+/// ```
+/// use actix_oauth::traits::MfaOtpHandler;
+/// use actix_oauth::handler::HandlerReturn;
+/// use actix_oauth::types::MfaToken;
+/// use actix_oauth::error::Oauth2ErrorType;
+/// use actix_web::HttpRequest;
+///
+/// async fn handle_mfa_otp(_req: HttpRequest, mfa_token: MfaToken, otp: String) -> HandlerReturn {
+///     // 1. Find the resource owner the token was issued for and check the one-time password
+///     if !validate_otp(mfa_token.secret(), &otp) {
+///         return Err(Oauth2ErrorType::InvalidGrant);
+///     }
+///
+///     // 2. Generate the tokens like the grant that required the second factor would have
+///     Err(Oauth2ErrorType::ServerError)
+/// }
+///
+/// fn validate_otp(_mfa_token: &str, _otp: &str) -> bool {
+///     // Your implementation to verify the token and the one-time password
+///     true
+/// }
+/// ```
+#[diagnostic::on_unimplemented(
+    note = "Consider creating a custom handler that processes MFA one-time password requests",
+    message = "`{Self}` must be able to process MFA one-time password requests",
+    label = "this type doesn't implement the required function signature for handling MFA grants"
+)]
+pub trait MfaOtpHandler: Send + Sync + Clone + 'static {
+    /// Whether the handler serves the MFA one-time password grant.
+    const IMPLEMENTED: bool = true;
+
+    /// Exchanges an MFA token and a one-time password for a token.
+    fn handle(
+        &self,
+        req: HttpRequest,
+        mfa_token: MfaToken,
+        otp: String,
+    ) -> impl Future<Output = HandlerReturn>;
+}
+
+impl<F, Fut> MfaOtpHandler for F
+where
+    F: Fn(HttpRequest, MfaToken, String) -> Fut + Send + Sync + Clone + 'static,
+    Fut: Future<Output = HandlerReturn>,
+{
+    #[inline]
+    fn handle(
+        &self,
+        req: HttpRequest,
+        mfa_token: MfaToken,
+        otp: String,
+    ) -> impl Future<Output = HandlerReturn> {
+        (self)(req, mfa_token, otp)
+    }
+}
@@ -15,13 +15,33 @@
 //! * [`AuthCodeHandler`] - Handles authorization code grant
 //! * [`ClientCredentialsHandler`] - Handles client credentials grant
 //! * [`RefreshTokenHandler`] - Handles refresh token requests
+//! * [`MfaOtpHandler`] - Handles the second step of grants that require MFA
 //! * [`AuthorizationHandler`] - Handles authorization endpoint requests
-#![allow(dead_code, async_fn_in_trait)]
+//!
+//! # Implementing handlers
+//!
+//! Every handler trait is implemented for functions and closures that take the parameters of
+//! its `handle` method and return a future of its result, so an ordinary `async fn` with the
+//! right signature can be used directly. Types that need to carry state implement `handle`
+//! themselves instead.
+//!
+//! Each trait also has an `IMPLEMENTED` constant. Handlers that reject every request, such as
+//! the ones in [`crate::handler::default`], set it to `false` so what they would serve is left
+//! out of the generated OpenAPI documentation.
+//!
+//! # Futures
+//!
+//! The handlers return `impl Future` rather than being `async fn`s, so the futures they return
+//! are part of the public signature. The futures are not required to be [`Send`]: they get the
+//! [`HttpRequest`](actix_web::HttpRequest), which is not `Send`, and actix-web polls them on the
+//! worker thread that received the request.
+#![allow(dead_code)]
 
 mod auth_code_handler;
 mod authorization_handler;
 mod client_credentials_handler;
 mod manager;
+mod mfa_otp_handler;
 mod password_handler;
 mod refresh_token_handler;
 
@@ -29,5 +49,6 @@ pub use auth_code_handler::*;
 pub use authorization_handler::*;
 pub use client_credentials_handler::*;
 pub use manager::*;
+pub use mfa_otp_handler::*;
 pub use password_handler::*;
 pub use refresh_token_handler::*;
@@ -29,12 +29,6 @@ use actix_web::HttpRequest;
 ///
 /// * [`HandlerReturn`] - A Result containing either a [TokenResponse](crate::TokenResponse) or an [Oauth2ErrorType](crate::error::Oauth2ErrorType)
 ///
-/// # Implementation
-///
-/// This trait is automatically implemented for any type that satisfies the required
-/// function signature. You don't need to manually implement the trait, just provide
-/// a function with the correct signature.
-///
 /// # Example
 ///
 /// ```
@@ -77,12 +71,31 @@ use actix_web::HttpRequest;
     message = "`{Self}` must be able to process resource owner password credentials requests",
     label = "this type doesn't implement the required function signature for handling password grants"
 )]
-pub trait PasswordHandler:
-    AsyncFn(HttpRequest, Username, Password) -> HandlerReturn + Send + Sync + Clone + 'static
-{
+pub trait PasswordHandler: Send + Sync + Clone + 'static {
+    /// Whether the handler serves the password grant.
+    const IMPLEMENTED: bool = true;
+
+    /// Processes a resource owner password credentials grant request.
+    fn handle(
+        &self,
+        req: HttpRequest,
+        username: Username,
+        password: Password,
+    ) -> impl Future<Output = HandlerReturn>;
 }
 
-impl<T> PasswordHandler for T where
-    T: AsyncFn(HttpRequest, Username, Password) -> HandlerReturn + Send + Sync + Clone + 'static
+impl<F, Fut> PasswordHandler for F
+where
+    F: Fn(HttpRequest, Username, Password) -> Fut + Send + Sync + Clone + 'static,
+    Fut: Future<Output = HandlerReturn>,
 {
+    #[inline]
+    fn handle(
+        &self,
+        req: HttpRequest,
+        username: Username,
+        password: Password,
+    ) -> impl Future<Output = HandlerReturn> {
+        (self)(req, username, password)
+    }
 }
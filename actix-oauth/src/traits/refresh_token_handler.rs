@@ -25,12 +25,6 @@ use actix_web::HttpRequest;
 ///
 /// * [`HandlerReturn`] - A Result containing either a [TokenResponse](crate::TokenResponse) or an [Oauth2ErrorType](crate::error::Oauth2ErrorType)
 ///
-/// # Implementation
-///
-/// This trait is automatically implemented for any type that satisfies the required
-/// function signature. You don't need to manually implement the trait, just provide
-/// a function with the correct signature.
-///
 /// # Example
 ///
 /// ```
@@ -86,20 +80,37 @@ use actix_web::HttpRequest;
     message = "`{Self}` must be able to process refresh token requests",
     label = "this type doesn't implement the required function signature for handling refresh token grants"
 )]
-pub trait RefreshTokenHandler:
-    AsyncFn(HttpRequest, Option<ClientId>, Option<ClientSecret>, RefreshToken) -> HandlerReturn
-    + Send
-    + Sync
-    + Clone
-    + 'static
-{
+pub trait RefreshTokenHandler: Send + Sync + Clone + 'static {
+    /// Whether the handler serves the refresh token grant.
+    const IMPLEMENTED: bool = true;
+
+    /// Exchanges a refresh token for a new token.
+    fn handle(
+        &self,
+        req: HttpRequest,
+        client_id: Option<ClientId>,
+        client_secret: Option<ClientSecret>,
+        refresh_token: RefreshToken,
+    ) -> impl Future<Output = HandlerReturn>;
 }
 
-impl<T> RefreshTokenHandler for T where
-    T: AsyncFn(HttpRequest, Option<ClientId>, Option<ClientSecret>, RefreshToken) -> HandlerReturn
+impl<F, Fut> RefreshTokenHandler for F
+where
+    F: Fn(HttpRequest, Option<ClientId>, Option<ClientSecret>, RefreshToken) -> Fut
         + Send
         + Sync
         + Clone
-        + 'static
+        + 'static,
+    Fut: Future<Output = HandlerReturn>,
 {
+    #[inline]
+    fn handle(
+        &self,
+        req: HttpRequest,
+        client_id: Option<ClientId>,
+        client_secret: Option<ClientSecret>,
+        refresh_token: RefreshToken,
+    ) -> impl Future<Output = HandlerReturn> {
+        (self)(req, client_id, client_secret, refresh_token)
+    }
 }
@@ -1,3 +1,4 @@
+use crate::utils::random_string;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 use tosic_utils::wrap_external_type;
@@ -17,6 +18,16 @@ impl Debug for AuthorizationCode {
     }
 }
 
+impl AuthorizationCode {
+    pub fn new(code: impl Into<String>) -> Self {
+        Self(oauth2::AuthorizationCode::new(code.into()))
+    }
+
+    pub fn new_random() -> Self {
+        Self::new(random_string(64))
+    }
+}
+
 impl PartialSchema for AuthorizationCode {
     fn schema() -> RefOr<Schema> {
         openapi::schema::ObjectBuilder::new()
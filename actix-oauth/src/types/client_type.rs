@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Client type as defined in RFC 6749, Section 2.1.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Hash,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+    sqlx::Type,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "client_type", rename_all = "snake_case")]
+pub enum ClientType {
+    /// A client that can keep its credentials confidential, such as a server side application.
+    #[default]
+    Confidential,
+    /// A client that cannot keep credentials confidential, such as a native or browser based
+    /// application. Public clients are not issued a secret and cannot use grants that
+    /// authenticate the client with one.
+    Public,
+}
+
+impl ClientType {
+    /// Whether the client authenticates with a client secret.
+    #[inline]
+    pub fn has_secret(&self) -> bool {
+        matches!(self, Self::Confidential)
+    }
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use tosic_utils::wrap_external_type;
+use utoipa::openapi::path::{Parameter, ParameterBuilder, ParameterIn};
+use utoipa::openapi::{RefOr, Required, Schema};
+use utoipa::{IntoParams, PartialSchema, ToSchema, openapi};
+
+wrap_external_type! {
+    /// Token handed out with an `mfa_required` error, exchanged together with a one-time
+    /// password for a token.
+    #[derive(Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+    pub struct MfaToken(String);
+}
+
+impl Debug for MfaToken {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "MfaToken([redacted])")
+    }
+}
+
+impl MfaToken {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialSchema for MfaToken {
+    fn schema() -> RefOr<Schema> {
+        openapi::schema::ObjectBuilder::new()
+            .schema_type(openapi::schema::Type::String)
+            .title("MFA Token".into())
+            .description(Some(
+                "Token from an `mfa_required` error, proves the first factor was already checked",
+            ))
+            .into()
+    }
+}
+
+impl ToSchema for MfaToken {}
+
+impl IntoParams for MfaToken {
+    fn into_params(parameter_in_provider: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        let parameter_in = parameter_in_provider().unwrap_or_default();
+
+        let param = ParameterBuilder::new()
+            .name("mfa_token")
+            .parameter_in(parameter_in)
+            .required(Required::True)
+            .schema(Some(Self::schema()))
+            .description(Some("Token from the `mfa_required` error"))
+            .build();
+
+        vec![param]
+    }
+}
+
+impl AsRef<str> for MfaToken {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
@@ -2,11 +2,15 @@ pub mod access_token;
 pub mod authorization_code;
 pub mod client_id;
 pub mod client_secret;
+pub mod client_type;
 pub mod grant_type;
+pub mod mfa_token;
 pub mod password;
+pub mod pkce;
 pub mod redirect_uri;
 pub mod refresh_token;
 pub mod scope;
+pub mod scope_registry;
 pub mod scopes;
 pub mod username;
 
@@ -14,10 +18,14 @@ pub use access_token::*;
 pub use authorization_code::*;
 pub use client_id::*;
 pub use client_secret::*;
+pub use client_type::*;
 pub use grant_type::*;
+pub use mfa_token::*;
 pub use password::*;
+pub use pkce::*;
 pub use redirect_uri::*;
 pub use refresh_token::*;
 pub use scope::*;
+pub use scope_registry::*;
 pub use scopes::*;
 pub use username::*;
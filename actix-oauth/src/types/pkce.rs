@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use tosic_utils::wrap_external_type;
+use utoipa::openapi::path::{Parameter, ParameterBuilder, ParameterIn};
+use utoipa::openapi::{RefOr, Required, Schema};
+use utoipa::{IntoParams, PartialSchema, ToSchema, openapi};
+
+/// Method a PKCE code challenge was derived from the code verifier with (RFC 7636, Section 4.2).
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+pub enum CodeChallengeMethod {
+    /// The challenge is the base64url encoded SHA-256 digest of the verifier.
+    S256,
+    /// The challenge is the verifier itself, the default when no method is sent.
+    #[serde(rename = "plain")]
+    Plain,
+}
+
+wrap_external_type! {
+    /// Secret a client proves it started the authorization request with when it exchanges the
+    /// authorization code (RFC 7636).
+    #[derive(Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct CodeVerifier(String);
+}
+
+impl Debug for CodeVerifier {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "CodeVerifier([redacted])")
+    }
+}
+
+impl CodeVerifier {
+    pub fn new(verifier: impl Into<String>) -> Self {
+        Self(verifier.into())
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the verifier has the length and characters RFC 7636, Section 4.1 requires.
+    pub fn is_valid(&self) -> bool {
+        is_valid_pkce_value(&self.0)
+    }
+
+    /// Whether the verifier matches an `S256` code challenge.
+    ///
+    /// Malformed verifiers never match.
+    pub fn matches_s256(&self, challenge: &str) -> bool {
+        if !self.is_valid() {
+            return false;
+        }
+
+        let verifier = oauth2::PkceCodeVerifier::new(self.0.clone());
+        let expected = oauth2::PkceCodeChallenge::from_code_verifier_sha256(&verifier);
+
+        // Both are public once the code is exchanged, but there is no reason to leak timing.
+        constant_time_eq(expected.as_str().as_bytes(), challenge.as_bytes())
+    }
+}
+
+/// Whether `value` is a valid code verifier or `S256` code challenge: 43 to 128 characters
+/// that are letters, digits, `-`, `.`, `_` or `~`.
+pub fn is_valid_pkce_value(value: &str) -> bool {
+    (43..=128).contains(&value.len())
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~'))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+impl PartialSchema for CodeVerifier {
+    fn schema() -> RefOr<Schema> {
+        openapi::schema::ObjectBuilder::new()
+            .schema_type(openapi::schema::Type::String)
+            .title("Code Verifier".into())
+            .description(Some(
+                "PKCE code verifier the code challenge of the authorization request was derived from",
+            ))
+            .into()
+    }
+}
+
+impl ToSchema for CodeVerifier {}
+
+impl IntoParams for CodeVerifier {
+    fn into_params(parameter_in_provider: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        let parameter_in = parameter_in_provider().unwrap_or_default();
+
+        let param = ParameterBuilder::new()
+            .name("code_verifier")
+            .parameter_in(parameter_in)
+            .required(Required::False)
+            .schema(Some(Self::schema()))
+            .description(Some(
+                "PKCE code verifier, required when the authorization request had a code challenge",
+            ))
+            .build();
+
+        vec![param]
+    }
+}
+
+impl AsRef<str> for CodeVerifier {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
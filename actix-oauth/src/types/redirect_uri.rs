@@ -1,4 +1,6 @@
-use serde::{Deserialize, Serialize};
+use crate::error::Oauth2ErrorType;
+use oauth2::url::{Host, Url};
+use serde::{Deserialize, Deserializer, Serialize, de};
 use std::fmt::{Debug, Formatter};
 use tosic_utils::wrap_external_type;
 use utoipa::openapi::path::{Parameter, ParameterBuilder, ParameterIn};
@@ -6,13 +8,82 @@ use utoipa::openapi::{KnownFormat, RefOr, Required, Schema, SchemaFormat};
 use utoipa::{IntoParams, PartialSchema, ToSchema, openapi};
 
 wrap_external_type! {
-    #[derive(Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+    #[derive(Clone, Hash, Eq, PartialEq, Serialize)]
     pub struct RedirectUri(oauth2::RedirectUrl);
 }
 
 impl RedirectUri {
-    pub fn new(url: impl Into<String>) -> Self {
-        Self(oauth2::RedirectUrl::new(url.into()).expect("Failed to parse URL"))
+    /// Parses a redirect URI.
+    ///
+    /// Returns [`Oauth2ErrorType::InvalidRequest`] if the URI is not an absolute URL or has a
+    /// fragment, which RFC 6749, Section 3.1.2 does not allow.
+    pub fn new(url: impl Into<String>) -> Result<Self, Oauth2ErrorType> {
+        let url =
+            oauth2::RedirectUrl::new(url.into()).map_err(|_| Oauth2ErrorType::InvalidRequest)?;
+
+        if url.url().fragment().is_some() {
+            return Err(Oauth2ErrorType::InvalidRequest);
+        }
+
+        Ok(Self(url))
+    }
+
+    /// Whether a redirect URI sent in a request matches this registered URI.
+    ///
+    /// URIs have to match exactly, except for loopback IP redirect URIs (`http://127.0.0.1` and
+    /// `http://[::1]`) where any port is accepted, as native apps pick a free port at runtime
+    /// (RFC 8252, Section 7.3).
+    pub fn matches(&self, requested: &RedirectUri) -> bool {
+        if self.0.as_str() == requested.0.as_str() {
+            return true;
+        }
+
+        let (registered, requested) = (self.0.url(), requested.0.url());
+
+        is_loopback(registered)
+            && registered.scheme() == requested.scheme()
+            && registered.host() == requested.host()
+            && registered.path() == requested.path()
+            && registered.query() == requested.query()
+            && registered.username() == requested.username()
+            && registered.password() == requested.password()
+    }
+
+    /// Appends `params` to the query of the URI, keeping the query it already has.
+    pub fn with_query<K, V>(&self, params: impl IntoIterator<Item = (K, V)>) -> String
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut url = self.0.url().clone();
+        url.query_pairs_mut().extend_pairs(params);
+
+        url.to_string()
+    }
+
+    /// Whether `requested` matches any of the `registered` URIs, see [`RedirectUri::matches`].
+    pub fn is_registered(registered: &[RedirectUri], requested: &RedirectUri) -> bool {
+        registered.iter().any(|uri| uri.matches(requested))
+    }
+}
+
+fn is_loopback(url: &Url) -> bool {
+    url.scheme() == "http"
+        && match url.host() {
+            Some(Host::Ipv4(ip)) => ip.is_loopback(),
+            Some(Host::Ipv6(ip)) => ip.is_loopback(),
+            _ => false,
+        }
+}
+
+impl<'de> Deserialize<'de> for RedirectUri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let url = String::deserialize(deserializer)?;
+
+        Self::new(url).map_err(|_| de::Error::custom("invalid redirect URI"))
     }
 }
 
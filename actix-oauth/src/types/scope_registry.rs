@@ -0,0 +1,76 @@
+use crate::types::Scopes;
+use std::collections::BTreeMap;
+use utoipa::openapi::security::Scopes as OpenApiScopes;
+
+/// All scopes known to the server together with a human-readable description of each.
+///
+/// The registry is used when documenting the OAuth2 flows and can be used by handlers to
+/// reject requests for scopes the server does not know about.
+///
+/// # Example
+///
+/// ```
+/// use actix_oauth::types::{ScopeRegistry, Scopes};
+///
+/// let registry = ScopeRegistry::new()
+///     .with_scope("read", "Read access")
+///     .with_scope("write", "Write access");
+///
+/// assert!(registry.covers(&Scopes::from_iter(["read"])));
+/// assert!(!registry.covers(&Scopes::from_iter(["admin"])));
+/// ```
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct ScopeRegistry(BTreeMap<String, String>);
+
+impl ScopeRegistry {
+    pub fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    /// Adds a scope to the registry, replacing the description if it is already registered.
+    pub fn with_scope(mut self, scope: impl Into<String>, description: impl Into<String>) -> Self {
+        self.0.insert(scope.into(), description.into());
+        self
+    }
+
+    /// Returns `true` if the scope is registered.
+    pub fn contains(&self, scope: impl AsRef<str>) -> bool {
+        self.0.contains_key(scope.as_ref())
+    }
+
+    /// Returns the description of a registered scope.
+    pub fn description(&self, scope: impl AsRef<str>) -> Option<&str> {
+        self.0.get(scope.as_ref()).map(String::as_str)
+    }
+
+    /// Returns `true` if every scope in `scopes` is registered.
+    pub fn covers(&self, scopes: &Scopes) -> bool {
+        scopes.iter().all(|scope| self.contains(scope))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(scope, description)| (scope.as_str(), description.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, S)> for ScopeRegistry {
+    fn from_iter<T: IntoIterator<Item = (S, S)>>(iter: T) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(scope, description)| (scope.into(), description.into()))
+                .collect(),
+        )
+    }
+}
+
+impl From<&ScopeRegistry> for OpenApiScopes {
+    fn from(value: &ScopeRegistry) -> Self {
+        value.iter().collect()
+    }
+}
@@ -6,46 +6,44 @@ pub fn random_string(length: usize) -> String {
     Alphanumeric.sample_string(&mut thread_rng(), length)
 }
 
+/// Defines a unit struct implementing one of the handler traits from [`crate::traits`].
+///
+/// When no block is given the handler responds with
+/// [`UnsupportedGrantType`](crate::error::Oauth2ErrorType::UnsupportedGrantType) and is
+/// marked as not implemented, which keeps the grant out of the generated documentation.
+///
+/// # Example
+///
+/// ```
+/// use actix_oauth::handler::HandlerReturn;
+/// use actix_oauth::oauth2_handler;
+/// use actix_oauth::traits::PasswordHandler;
+/// use actix_oauth::types::{Password, Username};
+/// use actix_web::HttpRequest;
+///
+/// oauth2_handler! {
+///     impl PasswordHandler for pub DenyAllPasswordHandler(_req: HttpRequest, _username: Username, _password: Password) -> HandlerReturn
+/// }
+/// ```
 #[macro_export]
 macro_rules! oauth2_handler {
-    ($(#[$ty_meta:meta])* $vis:vis fn $name:ident($parameter:pat_param => ($($param:ty),*)) -> $return_type:ty) => {
-        $crate::oauth2_handler! {$(#[$ty_meta])* $vis fn $name($parameter => ($($param),*)) -> $return_type {std::future::ready(Err(Oauth2ErrorType::UnsupportedGrantType))}}
-    };
-
-    ($(#[$ty_meta:meta])* $vis:vis fn $name:ident($parameter:pat_param => ($($param:ty),*)) -> $return_type:ty $block:block) => {
-        #[derive(Clone)]
+    (@impl $implemented:literal; $(#[$ty_meta:meta])* impl $handler:ident for $vis:vis $name:ident($($param:ident: $param_ty:ty),*) -> $return_type:ty $block:block) => {
+        $(#[$ty_meta])*
+        #[derive(Debug, Default, Clone, Copy)]
         $vis struct $name;
 
-        #[allow(unused_parens)]
-        impl AsyncFnOnce<($($param),*)> for $name {
-            type CallOnceFuture = std::future::Ready<$return_type>;
-            type Output = $return_type;
+        impl $handler for $name {
+            const IMPLEMENTED: bool = $implemented;
 
-            extern "rust-call" fn async_call_once(
-                self,
-                $parameter: ($($param),*),
-            ) -> Self::CallOnceFuture $block
+            async fn handle(&self, $($param: $param_ty),*) -> $return_type $block
         }
+    };
 
-        #[allow(unused_parens)]
-        impl AsyncFnMut<($($param),*)> for $name {
-            type CallRefFuture<'a>
-                = std::future::Ready<$return_type>
-            where
-                Self: 'a;
-
-            extern "rust-call" fn async_call_mut(
-                &mut self,
-                $parameter: ($($param),*),
-            ) -> Self::CallRefFuture<'_> $block
-        }
+    ($(#[$ty_meta:meta])* impl $handler:ident for $vis:vis $name:ident($($param:ident: $param_ty:ty),*) -> $return_type:ty) => {
+        $crate::oauth2_handler! {@impl false; $(#[$ty_meta])* impl $handler for $vis $name($($param: $param_ty),*) -> $return_type {Err($crate::error::Oauth2ErrorType::UnsupportedGrantType)}}
+    };
 
-        #[allow(unused_parens)]
-        impl AsyncFn<($($param),*)> for $name {
-            extern "rust-call" fn async_call(
-                &self,
-                $parameter: ($($param),*),
-            ) -> Self::CallRefFuture<'_> $block
-        }
+    ($(#[$ty_meta:meta])* impl $handler:ident for $vis:vis $name:ident($($param:ident: $param_ty:ty),*) -> $return_type:ty $block:block) => {
+        $crate::oauth2_handler! {@impl true; $(#[$ty_meta])* impl $handler for $vis $name($($param: $param_ty),*) -> $return_type $block}
     };
 }
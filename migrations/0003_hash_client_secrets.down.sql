@@ -0,0 +1,2 @@
+-- Hashed secrets cannot be recovered, clients created or migrated after the up migration need new secrets.
+ALTER TABLE oauth_client RENAME COLUMN client_secret_hash TO client_secret;
@@ -0,0 +1,3 @@
+-- Client secrets are now stored as Argon2 PHC strings. Rows created before this migration still
+-- hold the plaintext secret, the server hashes those on startup and whenever one is used.
+ALTER TABLE oauth_client RENAME COLUMN client_secret TO client_secret_hash;
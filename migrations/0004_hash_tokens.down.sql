@@ -0,0 +1,6 @@
+-- The digests cannot be reversed, existing tokens and codes are dropped as they can no longer be used.
+DELETE FROM oauth_token;
+ALTER TABLE oauth_token RENAME COLUMN token_hash TO token;
+
+DELETE FROM oauth_auth_code;
+ALTER TABLE oauth_auth_code RENAME COLUMN code_hash TO code;
@@ -0,0 +1,6 @@
+-- Tokens and authorization codes are stored as the hex encoded SHA-256 digest of their value.
+ALTER TABLE oauth_token RENAME COLUMN token TO token_hash;
+UPDATE oauth_token SET token_hash = encode(sha256(convert_to(token_hash, 'UTF8')), 'hex');
+
+ALTER TABLE oauth_auth_code RENAME COLUMN code TO code_hash;
+UPDATE oauth_auth_code SET code_hash = encode(sha256(convert_to(code_hash, 'UTF8')), 'hex');
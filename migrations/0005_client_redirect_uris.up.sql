@@ -0,0 +1,5 @@
+-- Clients can register several redirect URIs, requests have to use one of them.
+ALTER TABLE oauth_client ADD COLUMN redirect_uris TEXT[] NOT NULL DEFAULT '{}';
+UPDATE oauth_client SET redirect_uris = ARRAY[redirect_uri];
+ALTER TABLE oauth_client ALTER COLUMN redirect_uris DROP DEFAULT;
+ALTER TABLE oauth_client DROP COLUMN redirect_uri;
@@ -0,0 +1,14 @@
+DROP INDEX IF EXISTS idx_oauth_client_owner_ext_id;
+
+DELETE FROM oauth_client WHERE client_secret_hash IS NULL;
+ALTER TABLE oauth_client ALTER COLUMN client_secret_hash SET NOT NULL;
+
+ALTER TABLE oauth_client
+    DROP COLUMN name,
+    DROP COLUMN description,
+    DROP COLUMN logo_uri,
+    DROP COLUMN contact,
+    DROP COLUMN owner_ext_id,
+    DROP COLUMN client_type;
+
+DROP TYPE IF EXISTS client_type;
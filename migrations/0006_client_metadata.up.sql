@@ -0,0 +1,18 @@
+CREATE TYPE client_type AS ENUM('confidential', 'public');
+
+-- Clients created before this migration have no owner.
+ALTER TABLE oauth_client
+    ADD COLUMN name TEXT NOT NULL DEFAULT '',
+    ADD COLUMN description TEXT,
+    ADD COLUMN logo_uri TEXT,
+    ADD COLUMN contact TEXT,
+    ADD COLUMN owner_ext_id UUID REFERENCES users(ext_id) ON DELETE CASCADE,
+    ADD COLUMN client_type client_type NOT NULL DEFAULT 'confidential';
+
+UPDATE oauth_client SET name = client_id;
+ALTER TABLE oauth_client ALTER COLUMN name DROP DEFAULT;
+
+-- Public clients are not issued a secret.
+ALTER TABLE oauth_client ALTER COLUMN client_secret_hash DROP NOT NULL;
+
+CREATE INDEX idx_oauth_client_owner_ext_id ON oauth_client(owner_ext_id);
@@ -0,0 +1,15 @@
+-- Only the newest valid secret of each client is kept.
+ALTER TABLE oauth_client ADD COLUMN client_secret_hash TEXT;
+
+UPDATE oauth_client client
+SET client_secret_hash = (
+    SELECT secret.secret_hash
+    FROM oauth_client_secret secret
+    WHERE secret.client_id = client.client_id
+      AND secret.revoked_at IS NULL
+      AND (secret.expires_at IS NULL OR secret.expires_at > CURRENT_TIMESTAMP)
+    ORDER BY secret.created_at DESC
+    LIMIT 1
+);
+
+DROP TABLE IF EXISTS oauth_client_secret;
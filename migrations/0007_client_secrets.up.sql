@@ -0,0 +1,20 @@
+-- A client can have several valid secrets at once so secrets can be rotated without downtime.
+CREATE TABLE oauth_client_secret (
+    id BIGSERIAL PRIMARY KEY,
+    client_id TEXT NOT NULL REFERENCES oauth_client(client_id) ON DELETE CASCADE,
+    secret_hash TEXT NOT NULL,
+    -- Set when a newer secret is issued, the secret stays valid until then.
+    expires_at TIMESTAMP,
+    revoked_at TIMESTAMP,
+    last_used_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_oauth_client_secret_client_id ON oauth_client_secret(client_id);
+
+INSERT INTO oauth_client_secret (client_id, secret_hash, created_at)
+SELECT client_id, client_secret_hash, created_at
+FROM oauth_client
+WHERE client_secret_hash IS NOT NULL;
+
+ALTER TABLE oauth_client DROP COLUMN client_secret_hash;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_oauth_token_client_id;
+
+DROP TABLE IF EXISTS user_consent;
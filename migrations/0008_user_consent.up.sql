@@ -0,0 +1,12 @@
+-- Scopes a user approved for a client, the consent prompt is skipped for scopes approved before.
+CREATE TABLE user_consent (
+    id BIGSERIAL PRIMARY KEY,
+    user_ext_id UUID NOT NULL REFERENCES users(ext_id) ON DELETE CASCADE,
+    client_id TEXT NOT NULL REFERENCES oauth_client(client_id) ON DELETE CASCADE,
+    scopes TEXT[] NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_ext_id, client_id)
+);
+
+CREATE INDEX idx_oauth_token_client_id ON oauth_token(client_id);
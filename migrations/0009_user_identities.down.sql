@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS oidc_login_state;
+
+DROP TABLE IF EXISTS user_identities;
@@ -0,0 +1,35 @@
+-- Identities of users at upstream OpenID Connect providers, a user can have several.
+CREATE TABLE user_identities (
+    id BIGSERIAL PRIMARY KEY,
+    user_ext_id UUID NOT NULL REFERENCES users(ext_id) ON DELETE CASCADE,
+    -- Id of the provider in the server configuration.
+    provider TEXT NOT NULL,
+    -- Subject the provider identifies the user by, stable for the user at that provider.
+    subject TEXT NOT NULL,
+    email TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_login_at TIMESTAMP,
+    UNIQUE (provider, subject)
+);
+
+CREATE INDEX idx_user_identities_user_ext_id ON user_identities(user_ext_id);
+
+-- Logins that were sent to an upstream provider and have not come back yet.
+CREATE TABLE oidc_login_state (
+    id BIGSERIAL PRIMARY KEY,
+    -- SHA-256 digest of the `state` parameter sent to the provider.
+    state_hash TEXT NOT NULL UNIQUE,
+    -- SHA-256 digest of the nonce in the cookie of the browser that started the login.
+    binding_hash TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    nonce TEXT NOT NULL,
+    pkce_verifier TEXT NOT NULL,
+    -- Set when a signed in user links an identity to their account.
+    user_ext_id UUID REFERENCES users(ext_id) ON DELETE CASCADE,
+    -- Authorization request to continue once the user is signed in.
+    return_to TEXT,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_oidc_login_state_expires_at ON oidc_login_state(expires_at);
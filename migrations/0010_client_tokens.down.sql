@@ -0,0 +1,5 @@
+ALTER TABLE oauth_token DROP CONSTRAINT IF EXISTS oauth_token_owner;
+
+DELETE FROM oauth_token WHERE user_ext_id IS NULL;
+
+ALTER TABLE oauth_token ALTER COLUMN user_ext_id SET NOT NULL;
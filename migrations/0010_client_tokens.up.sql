@@ -0,0 +1,5 @@
+-- Tokens from the client credentials grant belong to a client and have no user.
+ALTER TABLE oauth_token ALTER COLUMN user_ext_id DROP NOT NULL;
+
+ALTER TABLE oauth_token
+    ADD CONSTRAINT oauth_token_owner CHECK (user_ext_id IS NOT NULL OR client_id IS NOT NULL);
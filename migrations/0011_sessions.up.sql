@@ -0,0 +1,18 @@
+-- Browser sessions, authenticated by a cookie holding the raw session token.
+CREATE TABLE session (
+    id BIGSERIAL PRIMARY KEY,
+    -- SHA-256 digest of the session token, the token itself is never stored.
+    session_hash TEXT NOT NULL UNIQUE,
+    -- SHA-256 digest of the CSRF token unsafe requests made with the session have to send.
+    csrf_hash TEXT NOT NULL,
+    user_ext_id UUID NOT NULL REFERENCES users(ext_id) ON DELETE CASCADE,
+    user_agent TEXT,
+    -- The absolute timeout, the session ends at this point even when it is in use.
+    expires_at TIMESTAMP NOT NULL,
+    -- The session ends when it has not been used for the idle timeout.
+    last_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_session_user_ext_id ON session(user_ext_id);
+CREATE INDEX idx_session_expires_at ON session(expires_at);
@@ -0,0 +1,2 @@
+-- When the user proved they own their email, reset when the email changes.
+ALTER TABLE users ADD COLUMN email_verified_at TIMESTAMP;
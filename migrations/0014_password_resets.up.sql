@@ -0,0 +1,12 @@
+-- Password reset tokens, each can be used once before it expires.
+CREATE TABLE password_reset (
+    id BIGSERIAL PRIMARY KEY,
+    -- SHA-256 digest of the reset token, the token itself is never stored.
+    token_hash TEXT NOT NULL UNIQUE,
+    user_ext_id UUID NOT NULL REFERENCES users(ext_id) ON DELETE CASCADE,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_password_reset_user_ext_id ON password_reset(user_ext_id);
+CREATE INDEX idx_password_reset_expires_at ON password_reset(expires_at);
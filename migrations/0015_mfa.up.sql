@@ -0,0 +1,22 @@
+-- TOTP authenticators, at most one per user. Only confirmed ones are required at sign in.
+CREATE TABLE user_totp (
+    id BIGSERIAL PRIMARY KEY,
+    user_ext_id UUID NOT NULL UNIQUE REFERENCES users(ext_id) ON DELETE CASCADE,
+    -- The shared secret, encrypted with the server's encryption key.
+    secret_ciphertext BYTEA NOT NULL,
+    confirmed_at TIMESTAMP,
+    -- Last time step a code was accepted for, so a code cannot be used twice.
+    last_used_step BIGINT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Single use codes that stand in for a TOTP code when the authenticator is lost.
+CREATE TABLE mfa_recovery_code (
+    id BIGSERIAL PRIMARY KEY,
+    user_ext_id UUID NOT NULL REFERENCES users(ext_id) ON DELETE CASCADE,
+    -- SHA-256 digest of the code, the code itself is never stored.
+    code_hash TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_mfa_recovery_code_user_ext_id ON mfa_recovery_code(user_ext_id);
@@ -0,0 +1,24 @@
+-- Failed sign-ins per username, address and MFA user, used to slow down and lock out guessing.
+-- Password reset requests per email and address are counted here as well.
+CREATE TABLE login_attempts (
+    id BIGSERIAL PRIMARY KEY,
+    -- What `key` is: `username`, `ip`, `mfa`, `reset_email` or `reset_ip`.
+    kind TEXT NOT NULL,
+    key TEXT NOT NULL,
+    -- Failures since the counter was last reset, it resets once `last_failure_at` is older
+    -- than the failure window or the key is locked.
+    failures INTEGER NOT NULL DEFAULT 0,
+    last_failure_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_until TIMESTAMP,
+    UNIQUE (kind, key)
+);
+
+CREATE INDEX idx_login_attempts_last_failure_at ON login_attempts(last_failure_at);
+
+INSERT INTO permissions (name, description) VALUES
+    ('users:unlock', 'Unlock users that were locked out after failed sign-ins');
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT roles.id, permissions.id
+FROM roles CROSS JOIN permissions
+WHERE roles.name = 'admin' AND permissions.name = 'users:unlock';
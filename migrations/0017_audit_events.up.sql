@@ -0,0 +1,48 @@
+-- Security relevant events, such as sign-ins, token issuance and permission changes.
+--
+-- Rows are only ever appended. Each row carries the SHA-256 hash of its contents and the hash
+-- of the row before it, so changing or removing a row breaks the chain after it.
+CREATE TABLE audit_event (
+    id BIGSERIAL PRIMARY KEY,
+    action TEXT NOT NULL,
+    outcome TEXT NOT NULL CHECK (outcome IN ('success', 'failure')),
+    -- No foreign keys, events have to outlive the users and clients they are about.
+    actor_user_ext_id UUID,
+    actor_client_id TEXT,
+    target_type TEXT,
+    target_id TEXT,
+    ip TEXT,
+    user_agent TEXT,
+    -- JSON object with details of the event.
+    details TEXT,
+    created_at TIMESTAMP NOT NULL,
+    prev_hash TEXT NOT NULL,
+    hash TEXT NOT NULL UNIQUE
+);
+
+CREATE INDEX idx_audit_event_created_at ON audit_event(created_at, id);
+CREATE INDEX idx_audit_event_action ON audit_event(action);
+CREATE INDEX idx_audit_event_actor_user_ext_id ON audit_event(actor_user_ext_id);
+CREATE INDEX idx_audit_event_target ON audit_event(target_type, target_id);
+
+CREATE FUNCTION audit_event_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_event is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_event_no_update_or_delete
+    BEFORE UPDATE OR DELETE ON audit_event
+    FOR EACH ROW EXECUTE FUNCTION audit_event_append_only();
+
+CREATE TRIGGER audit_event_no_truncate
+    BEFORE TRUNCATE ON audit_event
+    FOR EACH STATEMENT EXECUTE FUNCTION audit_event_append_only();
+
+INSERT INTO permissions (name, description) VALUES
+    ('audit:read', 'Query and export the audit log');
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT roles.id, permissions.id
+FROM roles CROSS JOIN permissions
+WHERE roles.name = 'admin' AND permissions.name = 'audit:read';
@@ -0,0 +1 @@
+ALTER TABLE oauth_auth_code DROP COLUMN IF EXISTS code_challenge;
@@ -0,0 +1,3 @@
+-- PKCE code challenge of the authorization request a code was issued for (RFC 7636), always
+-- derived with `S256`. Codes issued without one can be exchanged without a code verifier.
+ALTER TABLE oauth_auth_code ADD COLUMN code_challenge TEXT;
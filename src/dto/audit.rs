@@ -0,0 +1,73 @@
+use crate::dto;
+use crate::models::audit_event::{AuditEvent, AuditOutcome};
+use crate::traits::IntoDTO;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::{ToResponse, ToSchema};
+use uuid::Uuid;
+
+dto! {
+    /// A security relevant event from the audit log.
+    #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+    pub(crate) struct AuditEventDTO => AuditEvent {
+        pub(crate) id: i64,
+        /// What happened, grouped by a prefix such as `auth.` or `user.`.
+        #[schema(example = "auth.login")]
+        pub(crate) action: String,
+        pub(crate) outcome: AuditOutcome,
+        /// The user that did it, if a user did it.
+        pub(crate) actor_user: Option<Uuid>,
+        /// The client that did it, on its own or for the user.
+        pub(crate) actor_client: Option<String>,
+        /// What kind of thing it was done to.
+        #[schema(example = "user")]
+        pub(crate) target_type: Option<String>,
+        pub(crate) target_id: Option<String>,
+        pub(crate) ip: Option<String>,
+        pub(crate) user_agent: Option<String>,
+        /// Details that depend on the action.
+        #[schema(value_type = Option<Object>)]
+        pub(crate) details: Option<serde_json::Value>,
+        pub(crate) created_at: NaiveDateTime,
+        /// Hash of the event before this one.
+        pub(crate) prev_hash: String,
+        /// Hash of this event, chained to `prev_hash`.
+        pub(crate) hash: String
+    }
+
+    fn from_model(model: AuditEvent) -> Self {
+        Self {
+            id: model.id.expect("Expected 'id' to be populated"),
+            action: model.action,
+            outcome: match model.outcome.as_str() {
+                "failure" => AuditOutcome::Failure,
+                _ => AuditOutcome::Success,
+            },
+            actor_user: model.actor_user_ext_id,
+            actor_client: model.actor_client_id,
+            target_type: model.target_type,
+            target_id: model.target_id,
+            ip: model.ip,
+            user_agent: model.user_agent,
+            details: model
+                .details
+                .and_then(|details| serde_json::from_str(&details).ok()),
+            created_at: model.created_at,
+            prev_hash: model.prev_hash,
+            hash: model.hash,
+        }
+    }
+}
+
+dto! {
+    /// Result of checking that no event in the audit log was changed or removed.
+    #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+    pub(crate) struct AuditChainDTO {
+        /// Whether every event matches its hash and follows the event before it.
+        pub(crate) valid: bool,
+        /// Number of events checked, checking stops at the first invalid event.
+        pub(crate) checked: i64,
+        /// The first event that was changed or does not follow the event before it.
+        pub(crate) first_invalid_id: Option<i64>,
+    }
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Form the login page of the authorization endpoint posts.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct AuthorizeLoginDTO {
+    #[schema(example = "john-doe")]
+    pub(crate) username: String,
+    pub(crate) password: String,
+    /// The authorization request to continue once signed in.
+    #[schema(example = "/api/v1/oauth/authorize?response_type=code&client_id=...")]
+    pub(crate) return_to: String,
+}
+
+/// Form the MFA page of the authorization endpoint posts.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct AuthorizeMfaDTO {
+    /// Code from the authenticator app, or a recovery code.
+    #[schema(example = "123456")]
+    pub(crate) code: String,
+    /// Token the login page handed out once the password checked out.
+    pub(crate) mfa_token: String,
+    /// The authorization request to continue once signed in.
+    #[schema(example = "/api/v1/oauth/authorize?response_type=code&client_id=...")]
+    pub(crate) return_to: String,
+}
+
+/// The answer of the user to a consent prompt.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ConsentAnswer {
+    Approve,
+    Deny,
+}
+
+/// Form the consent page of the authorization endpoint posts.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct AuthorizeConsentDTO {
+    pub(crate) consent: ConsentAnswer,
+    /// CSRF token of the session.
+    pub(crate) csrf_token: String,
+    /// The authorization request the prompt was shown for.
+    #[schema(example = "/api/v1/oauth/authorize?response_type=code&client_id=...")]
+    pub(crate) return_to: String,
+}
@@ -27,6 +27,10 @@ dto! {
         /// Status code in a human-readable format.
         #[serde(skip_serializing_if = "str::is_empty")]
         pub code: Cow<'a, str>,
+        /// Errors of each invalid field of the request, for validation errors.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[schema(value_type = Option<Object>, example = json!({"password": [{"code": "password_too_short", "message": "Must be at least 10 characters", "params": {"min": 10}}]}))]
+        pub fields: Option<serde_json::Value>,
         /// Stacktrace of the request after the error occurred.
         #[cfg(debug_assertions)]
         #[serde(skip_serializing_if = "str::is_empty")]
@@ -0,0 +1,54 @@
+use crate::dto;
+use crate::models::user_consent::UserConsent;
+use crate::traits::IntoDTO;
+use actix_oauth::types::{ClientId, Scopes};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::{ToResponse, ToSchema};
+
+dto! {
+    /// Access a user has granted a client.
+    #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+    pub(crate) struct GrantDTO => UserConsent {
+        pub(crate) client_id: ClientId,
+        /// The scopes the user has approved for the client.
+        pub(crate) scopes: Scopes,
+        /// When the user first approved the client.
+        pub(crate) created_at: NaiveDateTime,
+        /// When the user last approved additional scopes.
+        pub(crate) updated_at: NaiveDateTime
+    }
+
+    fn from_model(model: UserConsent) -> Self {
+        Self {
+            client_id: ClientId::new(model.client_id),
+            scopes: Scopes::from_iter(model.scopes),
+            created_at: model
+                .created_at
+                .expect("Expected 'created_at' to be populated"),
+            updated_at: model
+                .updated_at
+                .expect("Expected 'updated_at' to be populated"),
+        }
+    }
+}
+
+dto! {
+    /// Returned by the authorization endpoint when the user has to approve the requested scopes
+    /// and the request accepts JSON.
+    ///
+    /// Sending the authorization request again as a `POST` with `consent=approve` grants the
+    /// scopes, and `consent=deny` sends the user back to the client with `access_denied`. Users
+    /// signed in with a session answer through `/authorize/consent` instead.
+    #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+    pub(crate) struct ConsentPromptDTO {
+        pub(crate) client_id: ClientId,
+        /// Name of the client shown to the user.
+        pub(crate) name: String,
+        pub(crate) description: Option<String>,
+        pub(crate) logo_uri: Option<String>,
+        /// The requested scopes that have not been approved yet, with their descriptions.
+        pub(crate) scopes: BTreeMap<String, String>,
+    }
+}
@@ -0,0 +1,34 @@
+use crate::dto;
+use serde::{Deserialize, Serialize};
+use utoipa::{ToResponse, ToSchema};
+
+dto! {
+    /// Secret of a new authenticator, it has to be confirmed with a code before it is used.
+    #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+    pub(crate) struct TotpEnrollmentDTO {
+        /// The secret, base32 encoded for entering it by hand.
+        #[schema(example = "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP")]
+        pub(crate) secret: String,
+        /// `otpauth://` URI of the secret, to show as a QR code for authenticator apps to scan.
+        #[schema(example = "otpauth://totp/Ferric:john-doe?secret=JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP&issuer=Ferric&algorithm=SHA1&digits=6&period=30")]
+        pub(crate) provisioning_uri: String,
+    }
+}
+
+dto! {
+    /// Single use codes that stand in for a code from the authenticator.
+    ///
+    /// The codes are only shown once, they should be stored somewhere safe.
+    #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+    pub(crate) struct RecoveryCodesDTO {
+        #[schema(example = json!(["k3v9q-x2m7p", "a8d4r-w6n1c"]))]
+        pub(crate) recovery_codes: Vec<String>,
+    }
+}
+
+/// A code from the authenticator, or a recovery code.
+#[derive(Default, Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct MfaCodeDTO {
+    #[schema(example = "123456")]
+    pub(crate) code: String,
+}
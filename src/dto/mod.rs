@@ -5,8 +5,18 @@
 #![allow(unused_imports)]
 
 mod_def! {
+    pub mod audit;
+    pub mod authorize;
     pub mod error;
+    pub mod grant;
     pub mod llm;
+    pub mod mfa;
+    pub mod oidc;
+    pub mod page;
+    pub mod password;
+    pub mod role;
+    pub mod session;
+    pub mod token;
     pub mod user;
 }
 
@@ -0,0 +1,26 @@
+use crate::dto;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToResponse, ToSchema};
+
+dto! {
+    /// An upstream OpenID Connect provider users can sign in with.
+    #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+    pub(crate) struct OidcProviderDTO {
+        /// Identifies the provider in the login URL and the `provider` authorization parameter.
+        pub(crate) id: String,
+        /// Name of the provider shown to users.
+        pub(crate) name: String,
+    }
+}
+
+/// Parameters the provider redirects back to the callback with.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct OidcCallbackDTO {
+    /// The state sent to the provider when the login was started.
+    pub(crate) state: String,
+    /// Authorization code to exchange for the ID token, missing if the login failed.
+    pub(crate) code: Option<String>,
+    /// Error code of the provider if the login failed.
+    pub(crate) error: Option<String>,
+}
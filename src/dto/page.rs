@@ -0,0 +1,102 @@
+use crate::statics::BASE_URL;
+use crate::traits::FromModel;
+use actix_web::body::BoxBody;
+use actix_web::http::header::LINK;
+use actix_web::{HttpRequest, HttpResponse, Responder};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A page of a list that is paged with cursors.
+///
+/// `next` and `prev` are opaque cursors, passed as `after` and `before` they get the following
+/// and preceding page. The same pages are linked in the `Link` header of the response.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct Page<T> {
+    pub(crate) items: Vec<T>,
+    /// Number of items matching across all pages, only given by lists that count them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) total: Option<i64>,
+    /// Cursor of the next page, missing on the last page.
+    pub(crate) next: Option<String>,
+    /// Cursor of the previous page, missing on the first page.
+    pub(crate) prev: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Links `req` with the cursor `cursor` as the `param` query parameter.
+    fn link(req: &HttpRequest, param: &str, cursor: &str) -> String {
+        let mut query = form_urlencoded::Serializer::new(String::new());
+
+        query.extend_pairs(
+            form_urlencoded::parse(req.query_string().as_bytes())
+                .filter(|(key, _)| key != "after" && key != "before"),
+        );
+        query.append_pair(param, cursor);
+
+        format!("{}{}?{}", *BASE_URL, req.path(), query.finish())
+    }
+}
+
+impl<DTO, Model> FromModel<Page<Model>> for Page<DTO>
+where
+    DTO: FromModel<Model> + Serialize + DeserializeOwned,
+{
+    #[inline]
+    fn from_model(model: Page<Model>) -> Self {
+        Self {
+            items: model.items.into_iter().map(DTO::from_model).collect(),
+            total: model.total,
+            next: model.next,
+            prev: model.prev,
+        }
+    }
+}
+
+impl<T: Serialize> Responder for Page<T> {
+    type Body = BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let links = [
+            ("after", &self.next, "next"),
+            ("before", &self.prev, "prev"),
+        ]
+        .into_iter()
+        .filter_map(|(param, cursor, rel)| {
+            let cursor = cursor.as_deref()?;
+            Some(format!(
+                r#"<{}>; rel="{rel}""#,
+                Self::link(req, param, cursor)
+            ))
+        })
+        .collect::<Vec<_>>();
+
+        let mut response = HttpResponse::Ok();
+
+        if !links.is_empty() {
+            response.insert_header((LINK, links.join(", ")));
+        }
+
+        response.json(&self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total_is_only_given_when_counted() {
+        let mut page = Page {
+            items: vec![1, 2],
+            total: None,
+            next: None,
+            prev: None,
+        };
+
+        assert!(serde_json::to_value(&page).unwrap().get("total").is_none());
+
+        page.total = Some(12);
+        assert_eq!(serde_json::to_value(&page).unwrap()["total"], 12);
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Asks for a password reset token to be mailed to `email`.
+#[derive(
+    Default, Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, ToSchema, Validate,
+)]
+pub(crate) struct PasswordForgotDTO {
+    #[validate(email)]
+    pub(crate) email: String,
+}
+
+/// Sets a new password with a reset token.
+#[derive(Default, Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct PasswordResetDTO {
+    /// The token from the password reset mail.
+    pub(crate) token: String,
+    /// Has to follow the password policy.
+    pub(crate) new_password: String,
+}
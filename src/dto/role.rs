@@ -0,0 +1,43 @@
+use crate::dto;
+use crate::models::permission::Permission;
+use crate::models::role::Role;
+use crate::traits::IntoDTO;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::{ToResponse, ToSchema};
+
+dto! {
+    /// A role and the permissions it grants.
+    #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+    pub(crate) struct RoleDTO => Role {
+        #[schema(example = "admin")]
+        pub(crate) name: String,
+        pub(crate) description: Option<String>,
+        pub(crate) permissions: Vec<Permission>,
+        pub(crate) created_at: NaiveDateTime
+    }
+
+    fn from_model(model: Role) -> Self {
+        Self {
+            name: model.name,
+            description: model.description,
+            permissions: model
+                .permissions
+                .iter()
+                .filter_map(|name| Permission::from_name(name))
+                .collect(),
+            created_at: model
+                .created_at
+                .expect("Expected 'created_at' to be populated"),
+        }
+    }
+}
+
+dto! {
+    /// The roles assigned to a user.
+    #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+    pub(crate) struct UserRolesDTO {
+        #[schema(example = json!(["admin"]))]
+        pub(crate) roles: Vec<String>,
+    }
+}
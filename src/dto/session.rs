@@ -0,0 +1,33 @@
+use crate::dto;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::{ToResponse, ToSchema};
+
+/// Credentials to start a browser session with.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct SessionLoginDTO {
+    #[schema(example = "john-doe")]
+    pub(crate) username: String,
+    pub(crate) password: String,
+    /// Code from the authenticator app or a recovery code, required for users with an
+    /// authenticator.
+    #[serde(default)]
+    #[schema(example = "123456")]
+    pub(crate) otp: Option<String>,
+}
+
+dto! {
+    /// A browser session that was started.
+    ///
+    /// The session itself is in the HttpOnly `id` cookie. Requests made with it that are not
+    /// `GET`, `HEAD` or `OPTIONS` have to send `csrf_token` in the `X-CSRF-Token` header, it is
+    /// also set in the `csrf_token` cookie so scripts can read it.
+    #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+    pub(crate) struct SessionDTO {
+        pub(crate) csrf_token: String,
+        /// When the session ends, however much it is used.
+        pub(crate) expires_at: NaiveDateTime,
+        /// Seconds the session stays valid without being used.
+        pub(crate) idle_timeout_seconds: u64,
+    }
+}
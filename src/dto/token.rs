@@ -0,0 +1,33 @@
+use crate::dto;
+use crate::models::oauth_token::{OAuthToken, TokenType};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::{ToResponse, ToSchema};
+
+dto! {
+    /// A token issued for the user, without the token itself.
+    #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+    pub(crate) struct TokenDTO => OAuthToken {
+        pub(crate) id: i64,
+        pub(crate) token_type: TokenType,
+        pub(crate) scopes: Vec<String>,
+        /// Whether the token was issued to a client the user authorized, rather than to the
+        /// user directly.
+        pub(crate) delegated: bool,
+        pub(crate) expires_at: NaiveDateTime,
+        pub(crate) created_at: NaiveDateTime
+    }
+
+    fn from_model(model: OAuthToken) -> Self {
+        Self {
+            id: model.id.expect("Expected 'id' to be populated"),
+            token_type: model.token_type,
+            scopes: model.scopes,
+            delegated: model.client_id.is_some(),
+            expires_at: model.expires_at,
+            created_at: model
+                .created_at
+                .expect("Expected 'created_at' to be populated"),
+        }
+    }
+}
@@ -23,5 +23,6 @@ pub(crate) struct UserCreateDTO {
     pub(crate) username: String,
     #[validate(email)]
     pub(crate) email: String,
+    /// Has to follow the password policy.
     pub(crate) password: String,
 }
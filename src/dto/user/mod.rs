@@ -1,5 +1,7 @@
 mod_def! {
     pub(crate) mod create;
+    pub(crate) mod update;
+    pub(crate) mod verify;
 }
 
 use crate::dto;
@@ -37,6 +39,7 @@ dto! {
                     "id": "068cd24f-730f-451b-b4c7-e8fd81637701",
                     "username": "testuser",
                     "email": "test@test.com",
+                    "email_verified_at": "2025-01-06T16:10:12.120512",
                     "created_at": "2025-01-06T16:00:45.770588",
                     "updated_at": "2025-01-06T16:00:45.770588"
                 }), description = "Successfully found user"
@@ -47,6 +50,8 @@ dto! {
         pub(crate) username: String,
         #[validate(email)]
         pub(crate) email: String,
+        /// When the user verified their email, missing until they do.
+        pub(crate) email_verified_at: Option<NaiveDateTime>,
         pub(crate) created_at: Option<NaiveDateTime>,
         pub(crate) updated_at: Option<NaiveDateTime>
     }
@@ -56,22 +61,9 @@ dto! {
             id: model.ext_id.unwrap(),
             username: model.username,
             email: model.email,
+            email_verified_at: model.email_verified_at,
             created_at: model.created_at,
             updated_at: model.updated_at,
         }
     }
 }
-
-/// A list of `UserDTO` objects.
-#[derive(ToResponse)]
-#[response(examples(
-        ("Successful" = (value = json!([{
-                "id": "068cd24f-730f-451b-b4c7-e8fd81637701",
-                "username": "testuser",
-                "email": "test@test.com",
-                "created_at": "2025-01-06T16:00:45.770588",
-                "updated_at": "2025-01-06T16:00:45.770588"
-            }]), description = "Successfully found users"
-        ))
-))]
-pub(crate) struct UserDTOVecResponses(#[allow(dead_code)] Vec<UserDTO>);
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Changes to the profile of the authenticated user, fields that are left out are kept.
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Hash,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    Validate,
+)]
+pub(crate) struct UserUpdateDTO {
+    #[validate(length(min = 1, max = 100))]
+    pub(crate) username: Option<String>,
+    #[validate(email)]
+    pub(crate) email: Option<String>,
+    /// Required to change the email.
+    pub(crate) current_password: Option<String>,
+}
+
+/// Changes the password of the authenticated user.
+#[derive(Default, Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct PasswordChangeDTO {
+    pub(crate) current_password: String,
+    /// Has to follow the password policy.
+    pub(crate) new_password: String,
+}
+
+/// Confirms deleting the account of the authenticated user.
+#[derive(Default, Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct AccountDeleteDTO {
+    pub(crate) password: String,
+}
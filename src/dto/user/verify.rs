@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Verifies the email of a user with the token mailed to it.
+#[derive(Default, Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct EmailVerifyDTO {
+    pub(crate) token: String,
+}
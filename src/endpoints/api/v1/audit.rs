@@ -0,0 +1,164 @@
+use crate::dto::{AuditChainDTO, AuditEventDTO, Page};
+use crate::error::ApiError;
+use crate::middleware::AuthMiddleware;
+use crate::models::permission::Permission;
+use crate::repositories::SortDirection;
+use crate::repositories::audit_events::{AuditEventsRepository, AuditSearchParams};
+use crate::repositories::keyset::{MAX_PAGE_LIMIT, PageParams};
+use crate::traits::into_dto::IntoDTO;
+use crate::utils::api_scope;
+use actix_helper_utils::generate_endpoint;
+use actix_web::{HttpResponse, web};
+use bytes::Bytes;
+use futures::stream;
+
+api_scope! {
+    pub(super) audit = "/audit";
+
+    middleware: [auth: || async { Ok::<_, ApiError>(AuthMiddleware::default()) }];
+    permissions: [Permission::AuditRead];
+    paths: [get_events, export_events, verify_chain];
+
+    docs: {
+        schemas: [AuditEventDTO, AuditChainDTO, crate::models::audit_event::AuditOutcome];
+        responses: [AuditEventDTO, AuditChainDTO];
+    }
+}
+
+generate_endpoint! {
+    /// Searches the audit log.
+    ///
+    /// Events can be filtered by action, outcome, actor, target, address and time, and are
+    /// ordered by when they happened. The response is paged with the cursors in `next` and
+    /// `prev`, which are also linked in the `Link` header.
+    ///
+    /// Requires the `audit:read` permission.
+    fn get_events;
+    method: get;
+    path: "/events";
+    return_type: Page<AuditEventDTO>;
+    error: ApiError;
+    docs: {
+        tag: "Audit",
+        context_path: "/audit",
+        responses: {
+            (status = 200, description = "Successfully fetched events", body = Page<AuditEventDTO>),
+            (status = 400, description = "Invalid query parameters or cursor"),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "Missing the `audit:read` permission")
+        }
+    }
+    params: {
+        repo: web::Data<AuditEventsRepository>,
+        web::Query(params): web::Query<AuditSearchParams>,
+        web::Query(page): web::Query<PageParams>
+    }
+    {
+        Ok(repo.search(&params, &page).await?.into_dto())
+    }
+}
+
+generate_endpoint! {
+    /// Exports the events of the audit log as newline delimited JSON, one event per line.
+    ///
+    /// Takes the same filters as searching, but streams every matching event instead of a
+    /// page. Events are oldest first unless `order` says otherwise.
+    ///
+    /// Requires the `audit:read` permission.
+    fn export_events;
+    method: get;
+    path: "/events/export";
+    return_type: HttpResponse;
+    error: ApiError;
+    docs: {
+        tag: "Audit",
+        context_path: "/audit",
+        responses: {
+            (status = 200, description = "The matching events, one JSON object per line", content_type = "application/x-ndjson", body = AuditEventDTO),
+            (status = 400, description = "Invalid query parameters"),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "Missing the `audit:read` permission")
+        }
+    }
+    params: {
+        repo: web::Data<AuditEventsRepository>,
+        web::Query(params): web::Query<AuditSearchParams>
+    }
+    {
+        let params = AuditSearchParams {
+            order: Some(params.order.unwrap_or(SortDirection::Asc)),
+            ..params
+        };
+
+        let first = PageParams {
+            limit: Some(MAX_PAGE_LIMIT),
+            ..Default::default()
+        };
+
+        // Walks the pages one at a time, so the whole log is never held in memory.
+        let events = stream::try_unfold(Some(first), move |page| {
+            let repo = repo.clone();
+            let params = params.clone();
+
+            async move {
+                let Some(page) = page else {
+                    return Ok(None);
+                };
+
+                let events: Page<AuditEventDTO> = repo.search(&params, &page).await?.into_dto();
+
+                let mut lines = Vec::new();
+                for event in &events.items {
+                    serde_json::to_writer(&mut lines, event)?;
+                    lines.push(b'\n');
+                }
+
+                let next = events.next.map(|after| PageParams {
+                    after: Some(after),
+                    ..page
+                });
+
+                Ok::<_, ApiError>(Some((Bytes::from(lines), next)))
+            }
+        });
+
+        Ok(HttpResponse::Ok()
+            .content_type("application/x-ndjson")
+            .streaming(events))
+    }
+}
+
+generate_endpoint! {
+    /// Checks that no event in the audit log was changed and that none was removed from
+    /// between others.
+    ///
+    /// Goes through the whole log, which takes a while for large logs.
+    ///
+    /// Requires the `audit:read` permission.
+    fn verify_chain;
+    method: get;
+    path: "/verify";
+    return_type: AuditChainDTO;
+    error: ApiError;
+    docs: {
+        tag: "Audit",
+        context_path: "/audit",
+        responses: {
+            (status = 200, description = "Successfully checked the log", body = AuditChainDTO),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "Missing the `audit:read` permission")
+        }
+    }
+    params: {
+        repo: web::Data<AuditEventsRepository>
+    }
+    {
+        let status = repo.verify_chain().await?;
+
+        Ok(AuditChainDTO {
+            valid: status.first_invalid_id.is_none(),
+            checked: status.checked,
+            first_invalid_id: status.first_invalid_id,
+        })
+    }
+}
@@ -0,0 +1,60 @@
+use crate::dto::{AuthorizeConsentDTO, Error};
+use crate::error::ApiError;
+use crate::services::oauth::authorization_handler::answer_consent;
+use crate::services::oauth::pages::login_page;
+use crate::services::session::session_from_request;
+use actix_helper_utils::generate_endpoint;
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse, ResponseError, web};
+
+generate_endpoint! {
+    /// Answers the consent prompt from the consent page of the authorization endpoint.
+    ///
+    /// Only for users signed in with a session, the form has to carry the CSRF token of the
+    /// session. Continues the authorization request the same way the authorization endpoint does
+    /// once the prompt is answered, an expired session shows the login page.
+    fn consent;
+    method: post;
+    path: "/consent";
+    return_type: HttpResponse;
+    error: ApiError;
+    docs: {
+        tag: "Authorize",
+        context_path: "/authorize",
+        request_body: {
+            description = "Answer of the user and the authorization request it is for",
+            content(
+                (AuthorizeConsentDTO = "application/x-www-form-urlencoded")
+            )
+        }
+        responses: {
+            (status = 302, description = "Redirect back to the client with a code, or the error the request failed with"),
+            (status = 400, description = "`return_to` is not an authorization request", body = Error),
+            (status = 401, description = "The session expired, the login page is shown again"),
+            (status = 403, description = "Missing or invalid CSRF token", body = Error)
+        }
+    }
+    params: {
+        req: HttpRequest,
+        web::Form(dto): web::Form<AuthorizeConsentDTO>
+    };
+    {
+        super::check_return_to(&dto.return_to)?;
+
+        let Some(session) = session_from_request(&req).await? else {
+            return Ok(login_page(
+                StatusCode::UNAUTHORIZED,
+                &dto.return_to,
+                Some("Your session expired, please sign in again"),
+            ));
+        };
+
+        if !session.verify_csrf(&dto.csrf_token) {
+            return Err(ApiError::Forbidden("Missing or invalid CSRF token".into()));
+        }
+
+        Ok(answer_consent(&req, &dto.return_to, dto.consent)
+            .await
+            .unwrap_or_else(|err| err.error_response()))
+    }
+}
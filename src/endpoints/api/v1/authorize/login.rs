@@ -0,0 +1,72 @@
+use crate::dto::{AuthorizeLoginDTO, Error};
+use crate::error::ApiError;
+use crate::services::oauth::pages::{login_page, mfa_page};
+use crate::services::session::create_session;
+use crate::services::{lockout, mfa};
+use actix_helper_utils::generate_endpoint;
+use actix_web::http::StatusCode;
+use actix_web::http::header::LOCATION;
+use actix_web::{HttpRequest, HttpResponse, web};
+
+generate_endpoint! {
+    /// Signs a user in from the login page of the authorization endpoint.
+    ///
+    /// On success a session is started and the user is sent back to the authorization request,
+    /// otherwise the login page is shown again. Users with an authenticator are asked for a code
+    /// on the MFA page first.
+    fn login;
+    method: post;
+    path: "/login";
+    return_type: HttpResponse;
+    error: ApiError;
+    docs: {
+        tag: "Authorize",
+        context_path: "/authorize",
+        request_body: {
+            description = "Credentials of the user and the authorization request to continue",
+            content(
+                (AuthorizeLoginDTO = "application/x-www-form-urlencoded")
+            )
+        }
+        responses: {
+            (status = 200, description = "The password checked out, the MFA page asks for a code"),
+            (status = 303, description = "Signed in, continue the authorization request"),
+            (status = 400, description = "`return_to` is not an authorization request", body = Error),
+            (status = 401, description = "Invalid username or password, the login page is shown again")
+        }
+    }
+    params: {
+        req: HttpRequest,
+        web::Form(dto): web::Form<AuthorizeLoginDTO>
+    };
+    {
+        super::check_return_to(&dto.return_to)?;
+
+        let user = lockout::authenticate(&dto.username, &dto.password, &req).await?;
+
+        let Some(user_ext_id) = user.and_then(|user| user.ext_id) else {
+            return Ok(login_page(
+                StatusCode::UNAUTHORIZED,
+                &dto.return_to,
+                Some("Invalid username or password"),
+            ));
+        };
+
+        if mfa::is_enrolled(user_ext_id).await? {
+            return Ok(mfa_page(
+                StatusCode::OK,
+                &dto.return_to,
+                &mfa::mfa_token(user_ext_id),
+                None,
+            ));
+        }
+
+        let session = create_session(user_ext_id, &req).await?;
+
+        let mut response = HttpResponse::SeeOther();
+        response.insert_header((LOCATION, dto.return_to));
+        session.set_cookies(&mut response);
+
+        Ok(response.finish())
+    }
+}
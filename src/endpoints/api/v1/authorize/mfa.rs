@@ -0,0 +1,68 @@
+use crate::dto::{AuthorizeMfaDTO, Error};
+use crate::error::ApiError;
+use crate::services::oauth::pages::{login_page, mfa_page};
+use crate::services::session::create_session;
+use crate::services::{lockout, mfa};
+use actix_helper_utils::generate_endpoint;
+use actix_web::http::StatusCode;
+use actix_web::http::header::LOCATION;
+use actix_web::{HttpRequest, HttpResponse, web};
+
+generate_endpoint! {
+    /// Finishes signing in from the MFA page of the authorization endpoint.
+    ///
+    /// On success a session is started and the user is sent back to the authorization request.
+    /// A wrong code shows the MFA page again, an expired MFA token the login page.
+    fn mfa;
+    method: post;
+    path: "/mfa";
+    return_type: HttpResponse;
+    error: ApiError;
+    docs: {
+        tag: "Authorize",
+        context_path: "/authorize",
+        request_body: {
+            description = "Code of the user and the authorization request to continue",
+            content(
+                (AuthorizeMfaDTO = "application/x-www-form-urlencoded")
+            )
+        }
+        responses: {
+            (status = 303, description = "Signed in, continue the authorization request"),
+            (status = 400, description = "`return_to` is not an authorization request", body = Error),
+            (status = 401, description = "Invalid code or expired MFA token, the MFA or login page is shown again")
+        }
+    }
+    params: {
+        req: HttpRequest,
+        web::Form(dto): web::Form<AuthorizeMfaDTO>
+    };
+    {
+        super::check_return_to(&dto.return_to)?;
+
+        let Some(user_ext_id) = mfa::verify_mfa_token(&dto.mfa_token) else {
+            return Ok(login_page(
+                StatusCode::UNAUTHORIZED,
+                &dto.return_to,
+                Some("Signing in took too long, please sign in again"),
+            ));
+        };
+
+        if !lockout::verify_mfa(user_ext_id, &dto.code, &req).await? {
+            return Ok(mfa_page(
+                StatusCode::UNAUTHORIZED,
+                &dto.return_to,
+                &dto.mfa_token,
+                Some("Invalid code"),
+            ));
+        }
+
+        let session = create_session(user_ext_id, &req).await?;
+
+        let mut response = HttpResponse::SeeOther();
+        response.insert_header((LOCATION, dto.return_to));
+        session.set_cookies(&mut response);
+
+        Ok(response.finish())
+    }
+}
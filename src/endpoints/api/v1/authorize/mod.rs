@@ -0,0 +1,81 @@
+use crate::ApiResult;
+use crate::error::ApiError;
+use crate::services::oauth::OAUTH_BASE_PATH;
+use crate::utils::api_scope;
+use actix_oauth::traits::AUTHORIZATION_ENDPOINT;
+use actix_web::http::Uri;
+
+pub(crate) mod consent;
+pub(crate) mod login;
+pub(crate) mod mfa;
+
+api_scope! {
+    pub(super) authorize = "/authorize";
+
+    paths: [
+        login::login,
+        mfa::mfa,
+        consent::consent
+    ];
+
+    docs: {
+        schemas: [
+            crate::dto::AuthorizeLoginDTO,
+            crate::dto::AuthorizeMfaDTO,
+            crate::dto::AuthorizeConsentDTO,
+            crate::dto::ConsentAnswer
+        ];
+    }
+}
+
+/// Checks that `return_to` is a relative path to the authorization endpoint, so the forms only
+/// continue authorization requests and cannot be used to redirect users to other sites.
+fn check_return_to(return_to: &str) -> ApiResult<()> {
+    let invalid = || ApiError::BadRequest("'return_to' must be an authorization request".into());
+
+    // Browsers resolve `//host` and `/\host` against another host.
+    if !return_to.starts_with('/') || return_to.starts_with("//") || return_to.starts_with("/\\") {
+        return Err(invalid());
+    }
+
+    let uri: Uri = return_to.parse().map_err(|_| invalid())?;
+
+    if uri.scheme().is_some()
+        || uri.authority().is_some()
+        || uri.path() != format!("{OAUTH_BASE_PATH}{AUTHORIZATION_ENDPOINT}")
+        || uri.query().is_none()
+    {
+        return Err(invalid());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_authorization_requests() {
+        let return_to = format!("{OAUTH_BASE_PATH}{AUTHORIZATION_ENDPOINT}?client_id=app");
+
+        assert!(check_return_to(&return_to).is_ok());
+    }
+
+    #[test]
+    fn rejects_other_sites_and_paths() {
+        let path = format!("{OAUTH_BASE_PATH}{AUTHORIZATION_ENDPOINT}?client_id=app");
+
+        for return_to in [
+            format!("https://evil.example{path}"),
+            format!("//evil.example{path}"),
+            format!("/\\evil.example{path}"),
+            format!("/{path}"),
+            format!("{OAUTH_BASE_PATH}{AUTHORIZATION_ENDPOINT}/../../me?client_id=app"),
+            "/api/v1/me".to_string(),
+            format!("{OAUTH_BASE_PATH}{AUTHORIZATION_ENDPOINT}"),
+        ] {
+            assert!(check_return_to(&return_to).is_err(), "{return_to}");
+        }
+    }
+}
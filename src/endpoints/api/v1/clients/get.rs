@@ -1,33 +1,49 @@
+use crate::dto::Page;
 use crate::error::ApiError;
-use crate::models::oauth_client::OAuthClient;
+use crate::middleware::Principal;
+use crate::models::permission::Permission;
+use crate::repositories::keyset::PageParams;
 use crate::repositories::oauth_clients::OauthClientsRepository;
 use crate::traits::into_dto::IntoDTO;
 use actix_helper_utils::generate_endpoint;
-use actix_oauth::dto::OAuthClientDTOCollection;
+use actix_oauth::dto::OAuthClientDTO;
 use actix_web::web;
-use sqlx_utils::traits::Repository;
 
 generate_endpoint! {
-    /// Gets all registered OAuth clients.
+    /// Gets the OAuth clients owned by the authenticated user, users with the `clients:manage`
+    /// permission get all clients.
     ///
-    /// TODO: Add authentication to make sure we can only fetch clients we have access to.
+    /// The newest clients come first. The response is paged with the cursors in `next` and
+    /// `prev`, which are also linked in the `Link` header.
     fn get_clients;
     method: get;
     path: "";
-    return_type: OAuthClientDTOCollection;
+    return_type: Page<OAuthClientDTO>;
     error: ApiError;
     docs: {
         tag: "Client",
         context_path: "/clients",
         responses: {
-            (status = 200, description = "Successfully fetched all OAuth clients", body = OAuthClientDTOCollection)
+            (status = 200, description = "Successfully fetched the OAuth clients", body = Page<OAuthClientDTO>),
+            (status = 400, description = "Invalid query parameters or cursor"),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, not a user")
         }
     }
     params: {
-        repository: web::Data<OauthClientsRepository>
+        repository: web::Data<OauthClientsRepository>,
+        principal: web::ReqData<Principal>,
+        web::Query(page): web::Query<PageParams>
     }
     {
-        let clients: Vec<OAuthClient> = repository.get_all().await?;
-        Ok(clients.into_dto())
+        let user = principal.user()?;
+
+        let owner = if principal.permissions().contains(Permission::ClientsManage) {
+            None
+        } else {
+            Some(user.ext_id.ok_or(ApiError::InternalError)?)
+        };
+
+        Ok(repository.get_page(owner, &page).await?.into_dto())
     }
 }
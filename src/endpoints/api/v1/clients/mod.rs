@@ -1,15 +1,50 @@
+use crate::ApiResult;
+use crate::error::ApiError;
+use crate::middleware::{AuthMiddleware, Principal};
+use crate::models::oauth_client::OAuthClient;
+use crate::repositories::oauth_clients::OauthClientsRepository;
 use crate::utils::api_scope;
+use sqlx_utils::traits::Repository;
 
 pub(crate) mod get;
 pub(crate) mod post;
+pub(crate) mod redirect_uris;
+pub(crate) mod secrets;
+
+/// Gets a client `principal` may manage.
+///
+/// Returns `None` if the client does not exist or is owned by someone else, so the endpoints
+/// do not reveal which clients exist. Fails for clients acting on their own behalf.
+async fn get_accessible_client(
+    repository: &OauthClientsRepository,
+    principal: &Principal,
+    client_id: String,
+) -> ApiResult<Option<OAuthClient>> {
+    let user = principal.user()?;
+
+    Ok(repository
+        .get_by_id(client_id)
+        .await?
+        .filter(|client| client.is_accessible_by(user, principal.permissions())))
+}
 
 api_scope! {
     pub(super) clients = "/clients";
 
-    paths: [get::get_clients, post::register];
+    middleware: [auth: || async { Ok::<_, ApiError>(AuthMiddleware::default()) }];
+    paths: [
+        get::get_clients,
+        post::register,
+        redirect_uris::set_redirect_uris,
+        redirect_uris::add_redirect_uri,
+        redirect_uris::remove_redirect_uri,
+        secrets::rotate_secret,
+        secrets::get_secrets,
+        secrets::revoke_secret
+    ];
 
     docs: {
-        schemas: [actix_oauth::dto::OAuthCreateClientDTO, actix_oauth::dto::OAuthClientDTOCollection, actix_oauth::dto::OAuthClientDTO];
-        responses: [actix_oauth::dto::OAuthClientDTOCollection, actix_oauth::dto::OAuthClientDTO];
+        schemas: [actix_oauth::dto::OAuthCreateClientDTO, actix_oauth::dto::OAuthRedirectUrisDTO, actix_oauth::dto::OAuthRedirectUriDTO, actix_oauth::dto::OAuthClientDTOCollection, actix_oauth::dto::OAuthClientDTO, actix_oauth::dto::OAuthClientSecretDTOCollection, actix_oauth::dto::OAuthClientSecretDTO];
+        responses: [actix_oauth::dto::OAuthClientDTOCollection, actix_oauth::dto::OAuthClientDTO, actix_oauth::dto::OAuthClientSecretDTOCollection, actix_oauth::dto::OAuthClientSecretDTO];
     }
 }
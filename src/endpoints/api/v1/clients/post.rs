@@ -1,21 +1,25 @@
 use crate::dto::Error;
 use crate::error::ApiError;
+use crate::middleware::Principal;
 use crate::models::oauth_client::OAuthClient;
+use crate::repositories::oauth_client_secrets::OauthClientSecretsRepository;
 use crate::repositories::oauth_clients::OauthClientsRepository;
+use crate::services::audit::{AuditAction, AuditEntry};
 use crate::traits::IntoDTO;
 use actix_helper_utils::generate_endpoint;
 use actix_oauth::dto::{OAuthClientDTO, OAuthCreateClientDTO};
-use actix_web::web;
+use actix_web::{HttpRequest, web};
+use serde_json::json;
 use sqlx_utils::traits::Repository;
 use tracing::error;
 use validator::Validate;
 
 generate_endpoint! {
-    /// Register a new oauth2 client.
+    /// Register a new oauth2 client owned by the authenticated user.
     ///
     /// # Returns
     ///
-    /// * `OAuthClientDTO` - The created client.
+    /// * `OAuthClientDTO` - The created client, this is the only response that includes the client secret.
     fn register;
     method: post;
     path: "";
@@ -32,25 +36,59 @@ generate_endpoint! {
         }
         responses: {
             (status = 200, description = "Successfully created a new OAuth client", body = OAuthClientDTO),
+            (status = 400, description = "Invalid client details", body = Error),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, not a user"),
             (status = 500, description = "Internal Server Error", body = Error)
         }
     }
     params: {
+        req: HttpRequest,
         repository: web::Data<OauthClientsRepository>,
+        secrets: web::Data<OauthClientSecretsRepository>,
+        principal: web::ReqData<Principal>,
         web::Json(dto): web::Json<OAuthCreateClientDTO>
     };
     {
+        let user = principal.user()?;
+
         match dto.validate() {
             Ok(()) => {},
             Err(error) => return Err(error.into())
         }
 
-        let model = OAuthClient::new(dto);
+        let owner = user.ext_id.ok_or(ApiError::InternalError)?;
+        let (model, secret) = OAuthClient::new(dto, owner)?;
         repository.insert(&model).await?;
+
+        let secret = match secret {
+            Some((secret_model, secret)) => {
+                secrets.insert(&secret_model).await?;
+                Some(secret)
+            }
+            None => None,
+        };
+
         let client = repository.get_by_id(model.client_id).await?;
 
         match client {
-            Some(client) => Ok(client.into_dto()),
+            Some(client) => {
+                AuditEntry::new(AuditAction::ClientCreated)
+                    .request(&req)
+                    .target_client(&client.client_id)
+                    .details(json!({
+                        "name": client.name,
+                        "grant_types": client.grant_types,
+                        "scopes": client.scopes
+                    }))
+                    .record()
+                    .await;
+
+                let mut dto: OAuthClientDTO = client.into_dto();
+                dto.client_secret = secret;
+
+                Ok(dto)
+            }
             None => {
                 error!("Unknown issue while creating OAuth client");
                 Err(ApiError::InternalError)
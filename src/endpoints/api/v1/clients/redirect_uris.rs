@@ -0,0 +1,191 @@
+use super::get_accessible_client;
+use crate::ApiResult;
+use crate::dto::Error;
+use crate::error::ApiError;
+use crate::middleware::Principal;
+use crate::repositories::oauth_clients::OauthClientsRepository;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::traits::IntoDTO;
+use actix_helper_utils::generate_endpoint;
+use actix_oauth::dto::{OAuthClientDTO, OAuthRedirectUriDTO, OAuthRedirectUrisDTO};
+use actix_web::{HttpRequest, web};
+use serde_json::json;
+use validator::Validate;
+
+/// Applies `update` to the redirect URIs of a client and stores the result, unless `update`
+/// fails.
+///
+/// Returns `None` if the client does not exist or `principal` may not manage it.
+async fn update_redirect_uris(
+    req: &HttpRequest,
+    repository: &OauthClientsRepository,
+    principal: &Principal,
+    client_id: String,
+    update: impl FnOnce(&mut Vec<String>) -> ApiResult<()>,
+) -> ApiResult<Option<OAuthClientDTO>> {
+    let Some(mut client) = get_accessible_client(repository, principal, client_id).await? else {
+        return Ok(None);
+    };
+
+    update(&mut client.redirect_uris)?;
+
+    if !repository
+        .set_redirect_uris(&client.client_id, &client.redirect_uris)
+        .await?
+    {
+        return Ok(None);
+    }
+
+    AuditEntry::new(AuditAction::ClientUpdated)
+        .request(req)
+        .target_client(&client.client_id)
+        .details(json!({ "redirect_uris": client.redirect_uris }))
+        .record()
+        .await;
+
+    Ok(Some(client.into_dto()))
+}
+
+generate_endpoint! {
+    /// Replaces the redirect URIs registered for a client.
+    fn set_redirect_uris;
+    method: put;
+    path: "/{client_id}/redirect-uris";
+    return_type: Option<OAuthClientDTO>;
+    error: ApiError;
+    docs: {
+        tag: "Client",
+        context_path: "/clients",
+        request_body: {
+            description = "The redirect URIs the client should have",
+            content(
+                (OAuthRedirectUrisDTO)
+            )
+        }
+        responses: {
+            (status = 200, description = "Successfully updated the redirect URIs", body = OAuthClientDTO),
+            (status = 400, description = "No redirect URIs were given, or one of them is invalid", body = Error),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, not a user"),
+            (status = 404, description = "The client does not exist or is owned by someone else")
+        }
+    }
+    params: {
+        req: HttpRequest,
+        repository: web::Data<OauthClientsRepository>,
+        principal: web::ReqData<Principal>,
+        client_id: web::Path<String>,
+        web::Json(dto): web::Json<OAuthRedirectUrisDTO>
+    };
+    {
+        dto.validate()?;
+
+        let mut redirect_uris: Vec<String> = Vec::with_capacity(dto.redirect_uris.len());
+
+        for uri in dto.redirect_uris {
+            let uri = uri.to_string();
+
+            if !redirect_uris.contains(&uri) {
+                redirect_uris.push(uri);
+            }
+        }
+
+        update_redirect_uris(&req, &repository, &principal, client_id.into_inner(), |uris| {
+            *uris = redirect_uris;
+            Ok(())
+        })
+        .await
+    }
+}
+
+generate_endpoint! {
+    /// Registers an additional redirect URI for a client.
+    ///
+    /// Adding a URI that is already registered does nothing.
+    fn add_redirect_uri;
+    method: post;
+    path: "/{client_id}/redirect-uris";
+    return_type: Option<OAuthClientDTO>;
+    error: ApiError;
+    docs: {
+        tag: "Client",
+        context_path: "/clients",
+        request_body: {
+            description = "The redirect URI to add",
+            content(
+                (OAuthRedirectUriDTO)
+            )
+        }
+        responses: {
+            (status = 200, description = "Successfully added the redirect URI", body = OAuthClientDTO),
+            (status = 400, description = "The redirect URI is invalid", body = Error),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, not a user"),
+            (status = 404, description = "The client does not exist or is owned by someone else")
+        }
+    }
+    params: {
+        req: HttpRequest,
+        repository: web::Data<OauthClientsRepository>,
+        principal: web::ReqData<Principal>,
+        client_id: web::Path<String>,
+        web::Json(dto): web::Json<OAuthRedirectUriDTO>
+    };
+    {
+        let uri = dto.redirect_uri.to_string();
+
+        update_redirect_uris(&req, &repository, &principal, client_id.into_inner(), |uris| {
+            if !uris.contains(&uri) {
+                uris.push(uri);
+            }
+
+            Ok(())
+        })
+        .await
+    }
+}
+
+generate_endpoint! {
+    /// Removes a redirect URI from a client.
+    ///
+    /// The last redirect URI of a client cannot be removed, replace it instead.
+    fn remove_redirect_uri;
+    method: delete;
+    path: "/{client_id}/redirect-uris";
+    return_type: Option<OAuthClientDTO>;
+    error: ApiError;
+    docs: {
+        tag: "Client",
+        context_path: "/clients",
+        responses: {
+            (status = 200, description = "Successfully removed the redirect URI", body = OAuthClientDTO),
+            (status = 400, description = "The redirect URI is invalid, or the only one of the client", body = Error),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, not a user"),
+            (status = 404, description = "The client does not exist or is owned by someone else")
+        }
+    }
+    params: {
+        req: HttpRequest,
+        repository: web::Data<OauthClientsRepository>,
+        principal: web::ReqData<Principal>,
+        client_id: web::Path<String>,
+        web::Query(dto): web::Query<OAuthRedirectUriDTO>
+    };
+    {
+        let uri = dto.redirect_uri.to_string();
+
+        update_redirect_uris(&req, &repository, &principal, client_id.into_inner(), |uris| {
+            if uris.iter().all(|registered| *registered == uri) {
+                return Err(ApiError::BadRequest(
+                    "A client needs at least one redirect URI".into(),
+                ));
+            }
+
+            uris.retain(|registered| *registered != uri);
+
+            Ok(())
+        })
+        .await
+    }
+}
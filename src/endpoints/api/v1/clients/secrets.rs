@@ -0,0 +1,160 @@
+use super::get_accessible_client;
+use crate::dto::Error;
+use crate::error::ApiError;
+use crate::middleware::Principal;
+use crate::repositories::oauth_client_secrets::OauthClientSecretsRepository;
+use crate::repositories::oauth_clients::OauthClientsRepository;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::statics::CLIENT_SECRET_GRACE_PERIOD;
+use crate::traits::IntoDTO;
+use actix_helper_utils::generate_endpoint;
+use actix_oauth::dto::{
+    MAX_GRACE_PERIOD, OAuthClientSecretDTO, OAuthClientSecretDTOCollection,
+    OAuthRotateClientSecretDTO,
+};
+use actix_web::{HttpRequest, web};
+use chrono::TimeDelta;
+use serde_json::json;
+use validator::Validate;
+
+generate_endpoint! {
+    /// Issues a new secret for a confidential client.
+    ///
+    /// The secrets the client currently has stay valid for the grace period so the new secret can
+    /// be rolled out without downtime. This is the only response that includes the new secret.
+    fn rotate_secret;
+    method: post;
+    path: "/{client_id}/secrets";
+    return_type: Option<OAuthClientSecretDTO>;
+    error: ApiError;
+    docs: {
+        tag: "Client",
+        context_path: "/clients",
+        responses: {
+            (status = 200, description = "Successfully issued a new secret", body = OAuthClientSecretDTO),
+            (status = 400, description = "Invalid grace period or the client is public", body = Error),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, not a user"),
+            (status = 404, description = "The client does not exist or is owned by someone else")
+        }
+    }
+    params: {
+        req: HttpRequest,
+        repository: web::Data<OauthClientsRepository>,
+        secrets: web::Data<OauthClientSecretsRepository>,
+        principal: web::ReqData<Principal>,
+        client_id: web::Path<String>,
+        web::Query(dto): web::Query<OAuthRotateClientSecretDTO>
+    };
+    {
+        dto.validate()?;
+
+        let Some(client) = get_accessible_client(&repository, &principal, client_id.into_inner()).await? else {
+            return Ok(None);
+        };
+
+        if !client.client_type.has_secret() {
+            return Err(ApiError::BadRequest("Public clients do not have secrets".into()));
+        }
+
+        // The configured default is not covered by the DTO validation, so the bound is checked here.
+        let grace_period = Some(dto.grace_period.unwrap_or(*CLIENT_SECRET_GRACE_PERIOD))
+            .filter(|seconds| *seconds <= MAX_GRACE_PERIOD)
+            .and_then(|seconds| TimeDelta::try_seconds(seconds as i64))
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!("Grace period can be at most {MAX_GRACE_PERIOD} seconds"))
+            })?;
+
+        let (model, secret) = secrets.rotate(&client.client_id, grace_period).await?;
+
+        AuditEntry::new(AuditAction::ClientSecretCreated)
+            .request(&req)
+            .target_client(&client.client_id)
+            .details(json!({ "secret_id": model.id, "grace_period": grace_period.num_seconds() }))
+            .record()
+            .await;
+
+        let mut dto: OAuthClientSecretDTO = model.into_dto();
+        dto.client_secret = Some(secret);
+
+        Ok(Some(dto))
+    }
+}
+
+generate_endpoint! {
+    /// Lists the secrets of a client, including expired and revoked ones.
+    fn get_secrets;
+    method: get;
+    path: "/{client_id}/secrets";
+    return_type: Option<OAuthClientSecretDTOCollection>;
+    error: ApiError;
+    docs: {
+        tag: "Client",
+        context_path: "/clients",
+        responses: {
+            (status = 200, description = "Successfully fetched the secrets", body = OAuthClientSecretDTOCollection),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, not a user"),
+            (status = 404, description = "The client does not exist or is owned by someone else")
+        }
+    }
+    params: {
+        repository: web::Data<OauthClientsRepository>,
+        secrets: web::Data<OauthClientSecretsRepository>,
+        principal: web::ReqData<Principal>,
+        client_id: web::Path<String>
+    };
+    {
+        let Some(client) = get_accessible_client(&repository, &principal, client_id.into_inner()).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(secrets.get_by_client(&client.client_id).await?.into_dto()))
+    }
+}
+
+generate_endpoint! {
+    /// Revokes a secret of a client, it stops being valid immediately.
+    fn revoke_secret;
+    method: delete;
+    path: "/{client_id}/secrets/{secret_id}";
+    return_type: Option<OAuthClientSecretDTOCollection>;
+    error: ApiError;
+    docs: {
+        tag: "Client",
+        context_path: "/clients",
+        responses: {
+            (status = 200, description = "Successfully revoked the secret", body = OAuthClientSecretDTOCollection),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, not a user"),
+            (status = 404, description = "The client or secret does not exist, or the client is owned by someone else")
+        }
+    }
+    params: {
+        req: HttpRequest,
+        repository: web::Data<OauthClientsRepository>,
+        secrets: web::Data<OauthClientSecretsRepository>,
+        principal: web::ReqData<Principal>,
+        path: web::Path<(String, i64)>
+    };
+    {
+        let (client_id, secret_id) = path.into_inner();
+
+        let Some(client) = get_accessible_client(&repository, &principal, client_id).await? else {
+            return Ok(None);
+        };
+
+        if !secrets.revoke(&client.client_id, secret_id).await? {
+            return Ok(None);
+        }
+
+        AuditEntry::new(AuditAction::ClientSecretRevoked)
+            .request(&req)
+            .target_client(&client.client_id)
+            .details(json!({ "secret_id": secret_id }))
+            .record()
+            .await;
+
+        Ok(Some(secrets.get_by_client(&client.client_id).await?.into_dto()))
+    }
+}
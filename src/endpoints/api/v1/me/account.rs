@@ -0,0 +1,254 @@
+use crate::dto::{AccountDeleteDTO, Error, PasswordChangeDTO, UserDTO, UserUpdateDTO};
+use crate::error::ApiError;
+use crate::middleware::Principal;
+use crate::models::oauth_token::OAuthToken;
+use crate::models::session::Session;
+use crate::repositories::oauth_token::OauthTokenRepository;
+use crate::repositories::sessions::SessionsRepository;
+use crate::repositories::users::UsersRepository;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::services::password_policy::{self, PasswordOwner};
+use crate::services::session::clear_cookies;
+use crate::services::verification::try_send_verification_email;
+use crate::traits::IntoDTO;
+use actix_helper_utils::generate_endpoint;
+use actix_web::{HttpRequest, HttpResponse, web};
+use serde_json::json;
+use sqlx_utils::traits::Repository;
+use validator::Validate;
+
+generate_endpoint! {
+    /// Gets the profile of the authenticated user.
+    fn get_me;
+    method: get;
+    path: "";
+    return_type: UserDTO;
+    error: ApiError;
+    docs: {
+        tag: "Me",
+        context_path: "/me",
+        responses: {
+            (status = 200, description = "Successfully fetched the profile", body = UserDTO),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, not a user")
+        }
+    }
+    params: {
+        principal: web::ReqData<Principal>
+    };
+    {
+        let user = principal.user()?;
+
+        Ok(user.clone().into_dto())
+    }
+}
+
+generate_endpoint! {
+    /// Changes the username and email of the authenticated user.
+    ///
+    /// Fields that are left out are kept as they are. Changing the email requires the current
+    /// password, and the new email has to be verified again, a link to verify it is mailed to it.
+    fn update_me;
+    method: patch;
+    path: "";
+    return_type: UserDTO;
+    error: ApiError;
+    docs: {
+        tag: "Me",
+        context_path: "/me",
+        request_body: {
+            schema = UserUpdateDTO
+        }
+        responses: {
+            (status = 200, description = "Successfully updated the profile", body = UserDTO),
+            (status = 400, description = "Invalid details, or the username or email is already in use", body = Error),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The current password is missing or wrong, or the access token was issued to a client or obtained by a client for the user", body = Error)
+        }
+    }
+    params: {
+        req: HttpRequest,
+        repo: web::Data<UsersRepository>,
+        principal: web::ReqData<Principal>,
+        web::Json(dto): web::Json<UserUpdateDTO>
+    };
+    {
+        dto.validate()?;
+
+        let mut user = principal.account_user()?.clone();
+
+        let username_changed = dto
+            .username
+            .as_ref()
+            .is_some_and(|username| *username != user.username);
+        if let Some(username) = dto.username {
+            user.username = username;
+        }
+        let email_changed = dto.email.as_ref().is_some_and(|email| *email != user.email);
+        if email_changed {
+            // The email is where password resets are sent, changing it hands over the account.
+            let verified = dto
+                .current_password
+                .as_ref()
+                .is_some_and(|password| repo.verify_password(password, &user.password_hash));
+
+            if !verified {
+                AuditEntry::new(AuditAction::UserUpdated)
+                    .request(&req)
+                    .target_user(user.ext_id.ok_or(ApiError::InternalError)?)
+                    .details(json!({ "email_changed": true }))
+                    .failure()
+                    .record()
+                    .await;
+
+                return Err(ApiError::Forbidden("The current password is missing or wrong".into()));
+            }
+        }
+        if let Some(email) = dto.email {
+            user.email = email;
+        }
+        if email_changed {
+            user.email_verified_at = None;
+        }
+
+        repo.update_profile(&user).await?;
+
+        AuditEntry::new(AuditAction::UserUpdated)
+            .request(&req)
+            .target_user(user.ext_id.ok_or(ApiError::InternalError)?)
+            .details(json!({ "username_changed": username_changed, "email_changed": email_changed }))
+            .record()
+            .await;
+
+        if email_changed {
+            try_send_verification_email(&user).await;
+        }
+
+        let user = repo
+            .get_by_id(user.ext_id.ok_or(ApiError::InternalError)?)
+            .await?
+            .ok_or(ApiError::InternalError)?;
+
+        Ok(user.into_dto())
+    }
+}
+
+generate_endpoint! {
+    /// Changes the password of the authenticated user.
+    ///
+    /// Requires the current password, the new password has to follow the password policy. Every
+    /// other session and token of the user is revoked, the session or token the request is made
+    /// with stays valid.
+    fn change_password;
+    method: put;
+    path: "/password";
+    return_type: HttpResponse;
+    error: ApiError;
+    docs: {
+        tag: "Me",
+        context_path: "/me",
+        request_body: {
+            schema = PasswordChangeDTO
+        }
+        responses: {
+            (status = 204, description = "Changed the password"),
+            (status = 400, description = "The new password breaks the password policy", body = Error),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The current password is wrong, or the access token was issued to a client or obtained by a client for the user", body = Error)
+        }
+    }
+    params: {
+        req: HttpRequest,
+        users: web::Data<UsersRepository>,
+        tokens: web::Data<OauthTokenRepository>,
+        sessions: web::Data<SessionsRepository>,
+        principal: web::ReqData<Principal>,
+        session: Option<web::ReqData<Session>>,
+        token: Option<web::ReqData<OAuthToken>>,
+        web::Json(dto): web::Json<PasswordChangeDTO>
+    };
+    {
+        let user = principal.account_user()?;
+        let user_ext_id = user.ext_id.ok_or(ApiError::InternalError)?;
+
+        let entry = AuditEntry::new(AuditAction::PasswordChanged)
+            .request(&req)
+            .target_user(user_ext_id);
+
+        if !users.verify_password(&dto.current_password, &user.password_hash) {
+            entry.failure().record().await;
+            return Err(ApiError::Forbidden("The current password is wrong".into()));
+        }
+
+        password_policy::check(
+            "new_password",
+            &dto.new_password,
+            PasswordOwner { username: &user.username, email: &user.email },
+        )?;
+
+        users.change_password(user, &dto.new_password).await?;
+
+        entry.record().await;
+
+        tokens
+            .revoke_by_user(user_ext_id, token.and_then(|token| token.id))
+            .await?;
+        sessions
+            .delete_by_user(user_ext_id, session.and_then(|session| session.id))
+            .await?;
+
+        Ok(HttpResponse::NoContent().finish())
+    }
+}
+
+generate_endpoint! {
+    /// Deletes the account of the authenticated user.
+    ///
+    /// Requires the password. Everything owned by the user is deleted with it, including their
+    /// OAuth clients, grants, sessions and tokens.
+    fn delete_me;
+    method: delete;
+    path: "";
+    return_type: HttpResponse;
+    error: ApiError;
+    docs: {
+        tag: "Me",
+        context_path: "/me",
+        request_body: {
+            schema = AccountDeleteDTO
+        }
+        responses: {
+            (status = 204, description = "Deleted the account"),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The password is wrong, or the access token was issued to a client or obtained by a client for the user", body = Error)
+        }
+    }
+    params: {
+        req: HttpRequest,
+        repo: web::Data<UsersRepository>,
+        principal: web::ReqData<Principal>,
+        web::Json(dto): web::Json<AccountDeleteDTO>
+    };
+    {
+        let user = principal.account_user()?;
+        let user_ext_id = user.ext_id.ok_or(ApiError::InternalError)?;
+
+        let entry = AuditEntry::new(AuditAction::UserDeleted)
+            .request(&req)
+            .target_user(user_ext_id);
+
+        if !repo.verify_password(&dto.password, &user.password_hash) {
+            entry.failure().record().await;
+            return Err(ApiError::Forbidden("The password is wrong".into()));
+        }
+
+        repo.delete_by_id(user_ext_id).await?;
+
+        entry.record().await;
+
+        let mut response = HttpResponse::NoContent();
+        clear_cookies(&mut response);
+
+        Ok(response.finish())
+    }
+}
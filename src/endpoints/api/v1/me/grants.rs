@@ -0,0 +1,82 @@
+use crate::dto::GrantDTOCollection;
+use crate::error::ApiError;
+use crate::middleware::Principal;
+use crate::repositories::user_consent::UserConsentRepository;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::traits::IntoDTO;
+use actix_helper_utils::generate_endpoint;
+use actix_web::{HttpRequest, web};
+
+generate_endpoint! {
+    /// Lists the clients the authenticated user has granted access to, with the approved scopes.
+    fn get_grants;
+    method: get;
+    path: "/grants";
+    return_type: GrantDTOCollection;
+    error: ApiError;
+    docs: {
+        tag: "Me",
+        context_path: "/me",
+        responses: {
+            (status = 200, description = "Successfully fetched the grants", body = GrantDTOCollection),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, not a user")
+        }
+    }
+    params: {
+        repository: web::Data<UserConsentRepository>,
+        principal: web::ReqData<Principal>
+    };
+    {
+        let user = principal.user()?;
+
+        let owner = user.ext_id.ok_or(ApiError::InternalError)?;
+
+        Ok(repository.get_by_user(owner).await?.into_dto())
+    }
+}
+
+generate_endpoint! {
+    /// Revokes the access the authenticated user has granted a client.
+    ///
+    /// All tokens and authorization codes issued to the client for the user are revoked with
+    /// it, and the user is asked for consent again the next time the client requests access.
+    fn revoke_grant;
+    method: delete;
+    path: "/grants/{client_id}";
+    return_type: Option<GrantDTOCollection>;
+    error: ApiError;
+    docs: {
+        tag: "Me",
+        context_path: "/me",
+        responses: {
+            (status = 200, description = "Successfully revoked the grant, returns the remaining grants", body = GrantDTOCollection),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, or obtained by a client for the user"),
+            (status = 404, description = "The user has not granted the client access")
+        }
+    }
+    params: {
+        req: HttpRequest,
+        repository: web::Data<UserConsentRepository>,
+        principal: web::ReqData<Principal>,
+        client_id: web::Path<String>
+    };
+    {
+        let user = principal.account_user()?;
+
+        let owner = user.ext_id.ok_or(ApiError::InternalError)?;
+
+        if !repository.revoke(owner, &client_id).await? {
+            return Ok(None);
+        }
+
+        AuditEntry::new(AuditAction::GrantRevoked)
+            .request(&req)
+            .target_client(&client_id)
+            .record()
+            .await;
+
+        Ok(Some(repository.get_by_user(owner).await?.into_dto()))
+    }
+}
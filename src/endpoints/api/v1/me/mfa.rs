@@ -0,0 +1,183 @@
+use crate::dto::{Error, MfaCodeDTO, RecoveryCodesDTO, TotpEnrollmentDTO};
+use crate::error::ApiError;
+use crate::middleware::Principal;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::services::{lockout, mfa};
+use actix_helper_utils::generate_endpoint;
+use actix_web::{HttpRequest, HttpResponse, web};
+
+generate_endpoint! {
+    /// Sets up a new authenticator for the authenticated user.
+    ///
+    /// The secret is shown once, the authenticator is only required at sign in once it is
+    /// confirmed with a code. Setting up again before confirming replaces the secret.
+    fn enroll_totp;
+    method: post;
+    path: "/mfa/totp";
+    return_type: TotpEnrollmentDTO;
+    error: ApiError;
+    docs: {
+        tag: "Me",
+        context_path: "/me",
+        responses: {
+            (status = 200, description = "Created the secret of the authenticator", body = TotpEnrollmentDTO),
+            (status = 400, description = "An authenticator is already set up", body = Error),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, or obtained by a client for the user")
+        }
+    }
+    params: {
+        principal: web::ReqData<Principal>
+    };
+    {
+        let user = principal.account_user()?;
+        let enrollment = mfa::enroll(user).await?;
+
+        Ok(TotpEnrollmentDTO {
+            secret: enrollment.secret,
+            provisioning_uri: enrollment.provisioning_uri,
+        })
+    }
+}
+
+generate_endpoint! {
+    /// Confirms the new authenticator of the authenticated user with a code from it.
+    ///
+    /// From then on signing in takes a code as well. Returns the recovery codes, they are only
+    /// shown once.
+    fn confirm_totp;
+    method: post;
+    path: "/mfa/totp/confirm";
+    return_type: RecoveryCodesDTO;
+    error: ApiError;
+    docs: {
+        tag: "Me",
+        context_path: "/me",
+        request_body: {
+            schema = MfaCodeDTO
+        }
+        responses: {
+            (status = 200, description = "Confirmed the authenticator", body = RecoveryCodesDTO),
+            (status = 400, description = "The code is wrong, or there is no authenticator to confirm", body = Error),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, or obtained by a client for the user")
+        }
+    }
+    params: {
+        req: HttpRequest,
+        principal: web::ReqData<Principal>,
+        web::Json(dto): web::Json<MfaCodeDTO>
+    };
+    {
+        let user_ext_id = principal.account_user()?.ext_id.ok_or(ApiError::InternalError)?;
+
+        let recovery_codes = mfa::confirm(user_ext_id, &dto.code).await?.ok_or_else(|| {
+            ApiError::BadRequest(
+                "The code is wrong, or there is no authenticator to confirm".into(),
+            )
+        })?;
+
+        AuditEntry::new(AuditAction::MfaEnabled)
+            .request(&req)
+            .target_user(user_ext_id)
+            .record()
+            .await;
+
+        Ok(RecoveryCodesDTO { recovery_codes })
+    }
+}
+
+generate_endpoint! {
+    /// Replaces the recovery codes of the authenticated user.
+    ///
+    /// Requires a code from the authenticator or a recovery code, which counts towards the same
+    /// lockout as codes given at sign in. The old recovery codes stop working.
+    fn regenerate_recovery_codes;
+    method: post;
+    path: "/mfa/recovery-codes";
+    return_type: RecoveryCodesDTO;
+    error: ApiError;
+    docs: {
+        tag: "Me",
+        context_path: "/me",
+        request_body: {
+            schema = MfaCodeDTO
+        }
+        responses: {
+            (status = 200, description = "Created new recovery codes", body = RecoveryCodesDTO),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The code is wrong, or the access token was issued to a client or obtained by a client for the user", body = Error)
+        }
+    }
+    params: {
+        req: HttpRequest,
+        principal: web::ReqData<Principal>,
+        web::Json(dto): web::Json<MfaCodeDTO>
+    };
+    {
+        let user_ext_id = principal.account_user()?.ext_id.ok_or(ApiError::InternalError)?;
+
+        let entry = AuditEntry::new(AuditAction::RecoveryCodesRegenerated)
+            .request(&req)
+            .target_user(user_ext_id);
+
+        if !lockout::verify_mfa(user_ext_id, &dto.code, &req).await? {
+            entry.failure().record().await;
+            return Err(ApiError::Forbidden("The code is wrong".into()));
+        }
+
+        let recovery_codes = mfa::regenerate_recovery_codes(user_ext_id).await?;
+
+        entry.record().await;
+
+        Ok(RecoveryCodesDTO { recovery_codes })
+    }
+}
+
+generate_endpoint! {
+    /// Removes the authenticator of the authenticated user.
+    ///
+    /// Requires a code from the authenticator or a recovery code, which counts towards the same
+    /// lockout as codes given at sign in. Signing in no longer takes a code and the recovery
+    /// codes are deleted.
+    fn disable_totp;
+    method: delete;
+    path: "/mfa/totp";
+    return_type: HttpResponse;
+    error: ApiError;
+    docs: {
+        tag: "Me",
+        context_path: "/me",
+        request_body: {
+            schema = MfaCodeDTO
+        }
+        responses: {
+            (status = 204, description = "Removed the authenticator"),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The code is wrong, or the access token was issued to a client or obtained by a client for the user", body = Error)
+        }
+    }
+    params: {
+        req: HttpRequest,
+        principal: web::ReqData<Principal>,
+        web::Json(dto): web::Json<MfaCodeDTO>
+    };
+    {
+        let user_ext_id = principal.account_user()?.ext_id.ok_or(ApiError::InternalError)?;
+
+        let entry = AuditEntry::new(AuditAction::MfaDisabled)
+            .request(&req)
+            .target_user(user_ext_id);
+
+        if !lockout::verify_mfa(user_ext_id, &dto.code, &req).await? {
+            entry.failure().record().await;
+            return Err(ApiError::Forbidden("The code is wrong".into()));
+        }
+
+        mfa::disable(user_ext_id).await?;
+
+        entry.record().await;
+
+        Ok(HttpResponse::NoContent().finish())
+    }
+}
@@ -0,0 +1,43 @@
+use crate::error::ApiError;
+use crate::middleware::AuthMiddleware;
+use crate::utils::api_scope;
+
+pub(crate) mod account;
+pub(crate) mod grants;
+pub(crate) mod mfa;
+pub(crate) mod tokens;
+
+api_scope! {
+    pub(super) me = "/me";
+
+    middleware: [auth: || async { Ok::<_, ApiError>(AuthMiddleware::default()) }];
+    paths: [
+        account::get_me,
+        account::update_me,
+        account::change_password,
+        account::delete_me,
+        grants::get_grants,
+        grants::revoke_grant,
+        mfa::enroll_totp,
+        mfa::confirm_totp,
+        mfa::regenerate_recovery_codes,
+        mfa::disable_totp,
+        tokens::get_tokens
+    ];
+
+    docs: {
+        schemas: [
+            crate::dto::UserUpdateDTO,
+            crate::dto::PasswordChangeDTO,
+            crate::dto::AccountDeleteDTO,
+            crate::dto::GrantDTO,
+            crate::dto::GrantDTOCollection,
+            crate::dto::TokenDTO,
+            crate::dto::TotpEnrollmentDTO,
+            crate::dto::RecoveryCodesDTO,
+            crate::dto::MfaCodeDTO,
+            crate::models::oauth_token::TokenType
+        ];
+        responses: [crate::dto::GrantDTO, crate::dto::GrantDTOCollection];
+    }
+}
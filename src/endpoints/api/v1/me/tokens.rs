@@ -0,0 +1,42 @@
+use crate::dto::{Page, TokenDTO};
+use crate::error::ApiError;
+use crate::middleware::Principal;
+use crate::repositories::keyset::PageParams;
+use crate::repositories::oauth_token::OauthTokenRepository;
+use crate::traits::IntoDTO;
+use actix_helper_utils::generate_endpoint;
+use actix_web::web;
+
+generate_endpoint! {
+    /// Lists the valid tokens issued for the authenticated user.
+    ///
+    /// The newest tokens come first. The response is paged with the cursors in `next` and
+    /// `prev`, which are also linked in the `Link` header.
+    fn get_tokens;
+    method: get;
+    path: "/tokens";
+    return_type: Page<TokenDTO>;
+    error: ApiError;
+    docs: {
+        tag: "Me",
+        context_path: "/me",
+        responses: {
+            (status = 200, description = "Successfully fetched the tokens", body = Page<TokenDTO>),
+            (status = 400, description = "Invalid query parameters or cursor"),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "The access token was issued to a client, not a user")
+        }
+    }
+    params: {
+        repository: web::Data<OauthTokenRepository>,
+        principal: web::ReqData<Principal>,
+        web::Query(page): web::Query<PageParams>
+    };
+    {
+        let user = principal.user()?;
+
+        let owner = user.ext_id.ok_or(ApiError::InternalError)?;
+
+        Ok(repository.get_page_by_user(owner, &page).await?.into_dto())
+    }
+}
@@ -4,24 +4,45 @@ use crate::openapi::NormalizePath;
 use crate::services::oauth::oauth_handler;
 use crate::utils::api_scope;
 use actix_oauth::OauthAPI;
+use audit::audit_service;
+use authorize::authorize_service;
 use clients::clients_service;
+use me::me_service;
+use oidc::oidc_service;
+use password::password_service;
+use roles::roles_service;
+use session::session_service;
 use users::users_service;
 
 mod ai;
+mod audit;
+mod authorize;
 pub mod clients;
+mod me;
+mod oidc;
+mod password;
+mod roles;
+mod session;
 mod users;
 
 api_scope! {
     pub(crate) v1 = "/v1";
 
     version: V1;
-    services: [clients_service, oauth_handler, users_service, ai_service];
+    services: [audit_service, authorize_service, clients_service, me_service, oidc_service, oauth_handler, password_service, roles_service, session_service, users_service, ai_service];
 
     docs: {
         schemas: [Error];
         responses: [Error];
         nested: [
+            ("/", audit::AuditAPI),
+            ("/", authorize::AuthorizeAPI),
             ("/", clients::ClientsAPI),
+            ("/", me::MeAPI),
+            ("/", oidc::OidcAPI),
+            ("/", password::PasswordAPI),
+            ("/", roles::RolesAPI),
+            ("/", session::SessionAPI),
             ("/", users::UsersAPI),
             ("/", ai::AiAPI),
             ("/", OauthAPI),
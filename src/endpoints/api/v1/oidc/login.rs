@@ -0,0 +1,186 @@
+use crate::dto::{Error, OidcCallbackDTO, OidcProviderDTO, OidcProviderDTOCollection};
+use crate::error::ApiError;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::services::mfa;
+use crate::services::oauth::pages::mfa_page;
+use crate::services::oauth::{create_token_response, user_from_request};
+use crate::services::oidc::{LOGIN_COOKIE, OIDC_PROVIDERS, OidcProvider, clear_login_cookie};
+use crate::services::session::create_session;
+use actix_helper_utils::generate_endpoint;
+use actix_oauth::dto::TokenResponse;
+use actix_oauth::error::Oauth2ErrorType;
+use actix_oauth::types::MfaToken;
+use actix_web::http::StatusCode;
+use actix_web::http::header::LOCATION;
+use actix_web::{HttpRequest, HttpResponse, ResponseError, web};
+use serde_json::json;
+
+generate_endpoint! {
+    /// Lists the upstream OpenID Connect providers users can sign in with.
+    fn get_providers;
+    method: get;
+    path: "/providers";
+    return_type: OidcProviderDTOCollection;
+    error: ApiError;
+    docs: {
+        tag: "OIDC",
+        context_path: "/oidc",
+        responses: {
+            (status = 200, description = "Successfully fetched the providers", body = OidcProviderDTOCollection)
+        }
+    }
+    params: {
+        _req: HttpRequest
+    };
+    {
+        let providers: Vec<OidcProviderDTO> = OIDC_PROVIDERS
+            .iter()
+            .map(|provider| OidcProviderDTO {
+                id: provider.config.id.clone(),
+                name: provider.config.name.clone(),
+            })
+            .collect();
+
+        Ok(providers.into())
+    }
+}
+
+generate_endpoint! {
+    /// Signs in through an upstream OpenID Connect provider.
+    ///
+    /// Redirects to the provider, which redirects back to the callback. When the request has a
+    /// bearer token or session the identity at the provider is linked to the authenticated user
+    /// instead.
+    fn login;
+    method: get;
+    path: "/{provider}/login";
+    return_type: Option<HttpResponse>;
+    error: ApiError;
+    docs: {
+        tag: "OIDC",
+        context_path: "/oidc",
+        responses: {
+            (status = 302, description = "Redirect to the provider"),
+            (status = 404, description = "The provider does not exist"),
+            (status = 424, description = "The provider could not be reached", body = Error)
+        }
+    }
+    params: {
+        req: HttpRequest,
+        provider: web::Path<String>
+    };
+    {
+        let Some(provider) = OidcProvider::get(&provider) else {
+            return Ok(None);
+        };
+
+        let user = user_from_request(&req)
+            .await?
+            .and_then(|(user, _)| user.ext_id);
+        let login = provider.start_login(user, None).await?;
+
+        Ok(Some(login.redirect()))
+    }
+}
+
+generate_endpoint! {
+    /// Callback the upstream provider redirects back to after the user signed in.
+    ///
+    /// Logins started from the authorization endpoint start a session and continue the
+    /// authorization request, other logins respond with a token for the user. Users with an
+    /// authenticator are asked for a code on the MFA page first, or get an `mfa_required` error
+    /// with a token for the `mfa_otp` grant instead of a token.
+    fn callback;
+    method: get;
+    path: "/{provider}/callback";
+    return_type: Option<HttpResponse>;
+    error: ApiError;
+    docs: {
+        tag: "OIDC",
+        context_path: "/oidc",
+        responses: {
+            (status = 200, description = "Successfully signed in", body = TokenResponse),
+            (status = 200, description = "The MFA page asks for a code to continue the authorization request"),
+            (status = 303, description = "Signed in, continue the authorization request"),
+            (status = 400, description = "The login failed, expired or the identity cannot be used", body = Error),
+            (status = 403, description = "The user has an authenticator, a code has to be sent with the `mfa_otp` grant"),
+            (status = 404, description = "The provider does not exist"),
+            (status = 424, description = "The provider could not be reached or returned an invalid ID token", body = Error)
+        }
+    }
+    params: {
+        req: HttpRequest,
+        provider: web::Path<String>,
+        web::Query(dto): web::Query<OidcCallbackDTO>
+    };
+    {
+        let Some(provider) = OidcProvider::get(&provider) else {
+            return Ok(None);
+        };
+
+        if let Some(error) = dto.error {
+            return Err(ApiError::BadRequest(format!("The provider returned '{error}'")));
+        }
+
+        let code = dto
+            .code
+            .ok_or_else(|| ApiError::BadRequest("Missing authorization code".into()))?;
+
+        // Logins are bound to the browser that started them, see `LOGIN_COOKIE`.
+        let binding = req
+            .cookie(LOGIN_COOKIE)
+            .ok_or_else(|| ApiError::BadRequest("Unknown or expired login".into()))?;
+
+        let (user, return_to) = provider
+            .finish_login(code, &dto.state, binding.value())
+            .await?;
+        let user_ext_id = user.ext_id.ok_or(ApiError::InternalError)?;
+
+        AuditEntry::new(AuditAction::Login)
+            .request(&req)
+            .actor_user(Some(user_ext_id))
+            .details(json!({ "provider": provider.config.id }))
+            .record()
+            .await;
+
+        // The provider only stands in for the password, users with an authenticator still need a
+        // code, the same as after the password grant or the login page.
+        let mut response = if mfa::is_enrolled(user_ext_id).await? {
+            let mfa_token = mfa::mfa_token(user_ext_id);
+
+            match return_to {
+                Some(return_to) => mfa_page(StatusCode::OK, &return_to, &mfa_token, None),
+                None => Oauth2ErrorType::MfaRequired(MfaToken::new(mfa_token)).error_response(),
+            }
+        } else {
+            match return_to {
+                Some(return_to) => {
+                    let session = create_session(user_ext_id, &req).await?;
+
+                    let mut response = HttpResponse::SeeOther();
+                    response.insert_header((LOCATION, return_to));
+                    session.set_cookies(&mut response);
+
+                    response.finish()
+                }
+                None => {
+                    let token = create_token_response(user_ext_id, None, &[]).await?;
+
+                    AuditEntry::new(AuditAction::TokenIssued)
+                        .request(&req)
+                        .actor_user(Some(user_ext_id))
+                        .target_user(user_ext_id)
+                        .details(json!({ "grant_type": "oidc", "provider": provider.config.id }))
+                        .record()
+                        .await;
+
+                    HttpResponse::Ok().json(token)
+                }
+            }
+        };
+
+        clear_login_cookie(&mut response);
+
+        Ok(Some(response))
+    }
+}
@@ -0,0 +1,18 @@
+use crate::utils::api_scope;
+
+pub(crate) mod login;
+
+api_scope! {
+    pub(super) oidc = "/oidc";
+
+    paths: [
+        login::get_providers,
+        login::login,
+        login::callback
+    ];
+
+    docs: {
+        schemas: [crate::dto::OidcProviderDTO, crate::dto::OidcProviderDTOCollection, crate::dto::OidcCallbackDTO];
+        responses: [crate::dto::OidcProviderDTO, crate::dto::OidcProviderDTOCollection];
+    }
+}
@@ -0,0 +1,90 @@
+use crate::dto::{Error, PasswordForgotDTO, PasswordResetDTO};
+use crate::error::ApiError;
+use crate::services::lockout;
+use crate::services::password_reset::{request_reset_in_background, reset_password};
+use crate::utils::api_scope;
+use actix_helper_utils::generate_endpoint;
+use actix_web::{HttpRequest, HttpResponse, web};
+use validator::Validate;
+
+api_scope! {
+    pub(super) password = "/password";
+
+    paths: [forgot, reset];
+
+    docs: {
+        schemas: [PasswordForgotDTO, PasswordResetDTO];
+    }
+}
+
+generate_endpoint! {
+    /// Mails a password reset token to the user with the email.
+    ///
+    /// Always responds with `202`, whether a user has the email or not. Requests are limited per
+    /// email and per address, requests beyond the limits are accepted but no mail is sent.
+    fn forgot;
+    method: post;
+    path: "/forgot";
+    return_type: HttpResponse;
+    error: ApiError;
+    docs: {
+        tag: "Password",
+        context_path: "/password",
+        request_body: {
+            schema = PasswordForgotDTO
+        }
+        responses: {
+            (status = 202, description = "A reset token is mailed if a user has the email"),
+            (status = 400, description = "Invalid email", body = Error)
+        }
+    }
+    params: {
+        req: HttpRequest,
+        web::Json(dto): web::Json<PasswordForgotDTO>
+    };
+    {
+        dto.validate()?;
+
+        if lockout::allow_reset_request(&dto.email, &req).await? {
+            request_reset_in_background(dto.email);
+        }
+
+        Ok(HttpResponse::Accepted().finish())
+    }
+}
+
+generate_endpoint! {
+    /// Sets a new password with the token from the password reset mail.
+    ///
+    /// The token works once. Every session and token of the user is revoked, so they have to
+    /// sign in again everywhere.
+    fn reset;
+    method: post;
+    path: "/reset";
+    return_type: HttpResponse;
+    error: ApiError;
+    docs: {
+        tag: "Password",
+        context_path: "/password",
+        request_body: {
+            schema = PasswordResetDTO
+        }
+        responses: {
+            (status = 204, description = "Set the new password"),
+            (status = 400, description = "The token is invalid, expired or was already used, or the new password breaks the password policy", body = Error)
+        }
+    }
+    params: {
+        req: HttpRequest,
+        web::Json(dto): web::Json<PasswordResetDTO>
+    };
+    {
+        if !reset_password(&dto.token, &dto.new_password, &req).await? {
+            return Err(ApiError::BadRequest(
+                "The reset token is invalid or expired".into(),
+            ));
+        }
+
+        Ok(HttpResponse::NoContent().finish())
+    }
+}
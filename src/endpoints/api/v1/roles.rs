@@ -0,0 +1,50 @@
+use crate::dto::{RoleDTO, RoleDTOCollection};
+use crate::error::ApiError;
+use crate::middleware::AuthMiddleware;
+use crate::models::permission::Permission;
+use crate::repositories::roles::RolesRepository;
+use crate::traits::into_dto::IntoDTO;
+use crate::utils::api_scope;
+use actix_helper_utils::generate_endpoint;
+use actix_web::web;
+
+api_scope! {
+    pub(super) roles = "/roles";
+
+    middleware: [auth: || async { Ok::<_, ApiError>(AuthMiddleware::default()) }];
+    permissions: [Permission::RolesRead];
+    paths: [get_roles];
+
+    docs: {
+        schemas: [RoleDTO, Permission];
+        responses: [RoleDTO];
+    }
+}
+
+generate_endpoint! {
+    /// Gets all roles and the permissions they grant.
+    ///
+    /// Requires the `roles:read` permission.
+    fn get_roles;
+    method: get;
+    path: "";
+    return_type: RoleDTOCollection;
+    error: ApiError;
+    docs: {
+        tag: "role",
+        context_path: "/roles",
+        responses: {
+            (status = 200, description = "Successfully fetched roles", body = RoleDTOCollection),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "Missing the `roles:read` permission")
+        }
+    }
+    params: {
+        repo: web::Data<RolesRepository>
+    }
+    {
+        let roles = repo.get_all().await?.into_dto();
+
+        Ok(roles)
+    }
+}
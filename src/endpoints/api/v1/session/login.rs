@@ -0,0 +1,109 @@
+use crate::dto::{Error, SessionDTO, SessionLoginDTO};
+use crate::error::ApiError;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::services::session::{
+    SESSION_COOKIE, clear_cookies, create_session, end_session, session_from_request, verify_csrf,
+};
+use crate::services::{lockout, mfa};
+use actix_helper_utils::generate_endpoint;
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
+
+generate_endpoint! {
+    /// Starts a browser session.
+    ///
+    /// The session is set in the HttpOnly `id` cookie and authenticates requests like a bearer
+    /// token does. Unsafe requests made with it have to send the returned CSRF token in the
+    /// `X-CSRF-Token` header.
+    ///
+    /// Users with an authenticator also have to send a code from it in `otp`.
+    fn login;
+    method: post;
+    path: "/login";
+    return_type: HttpResponse;
+    error: ApiError;
+    docs: {
+        tag: "Session",
+        context_path: "/session",
+        request_body: {
+            schema = SessionLoginDTO
+        }
+        responses: {
+            (status = 200, description = "Signed in, the session cookies are set", body = SessionDTO),
+            (status = 401, description = "Invalid username, password or code", body = Error)
+        }
+    }
+    params: {
+        req: HttpRequest,
+        web::Json(dto): web::Json<SessionLoginDTO>
+    };
+    {
+        let user_ext_id = lockout::authenticate(&dto.username, &dto.password, &req)
+            .await?
+            .and_then(|user| user.ext_id)
+            .ok_or_else(|| ApiError::Unauthorized("Invalid username or password".into()))?;
+
+        if mfa::is_enrolled(user_ext_id).await? {
+            let Some(otp) = dto.otp.as_deref() else {
+                return Err(ApiError::Unauthorized(
+                    "A code from the authenticator is required".into(),
+                ));
+            };
+
+            if !lockout::verify_mfa(user_ext_id, otp, &req).await? {
+                return Err(ApiError::Unauthorized("Invalid code".into()));
+            }
+        }
+
+        let session = create_session(user_ext_id, &req).await?;
+
+        let mut response = HttpResponse::Ok();
+        session.set_cookies(&mut response);
+
+        Ok(response.json(session.to_dto()))
+    }
+}
+
+generate_endpoint! {
+    /// Ends the browser session of the request and removes its cookies.
+    fn logout;
+    method: post;
+    path: "/logout";
+    return_type: HttpResponse;
+    error: ApiError;
+    docs: {
+        tag: "Session",
+        context_path: "/session",
+        responses: {
+            (status = 204, description = "Signed out"),
+            (status = 401, description = "Missing or expired session", body = Error),
+            (status = 403, description = "Missing or invalid CSRF token", body = Error)
+        }
+    }
+    params: {
+        req: HttpRequest
+    };
+    {
+        let session = session_from_request(&req)
+            .await?
+            .ok_or_else(|| ApiError::Unauthorized("Missing or expired session".into()))?;
+
+        if !verify_csrf(&req, &session) {
+            return Err(ApiError::Forbidden("Missing or invalid CSRF token".into()));
+        }
+
+        if let Some(cookie) = req.cookie(SESSION_COOKIE) {
+            end_session(cookie.value()).await?;
+        }
+
+        AuditEntry::new(AuditAction::Logout)
+            .request(&req)
+            .actor_user(Some(session.user_ext_id))
+            .record()
+            .await;
+
+        let mut response = HttpResponse::NoContent();
+        clear_cookies(&mut response);
+
+        Ok(response.finish())
+    }
+}
@@ -0,0 +1,17 @@
+use crate::utils::api_scope;
+
+pub(crate) mod login;
+
+api_scope! {
+    pub(super) session = "/session";
+
+    paths: [
+        login::login,
+        login::logout
+    ];
+
+    docs: {
+        schemas: [crate::dto::SessionLoginDTO, crate::dto::SessionDTO];
+        responses: [crate::dto::SessionDTO];
+    }
+}
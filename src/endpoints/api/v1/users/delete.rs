@@ -1,15 +1,28 @@
 use crate::error::ApiError;
+use crate::middleware::AuthMiddleware;
+use crate::models::permission::Permission;
 use crate::prelude::*;
 use crate::repositories::users::UsersRepository;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::utils::api_scope;
 use actix_helper_utils::generate_endpoint;
-use actix_web::{HttpResponse, web};
+use actix_web::{HttpRequest, HttpResponse, web};
 use sqlx_utils::traits::Repository;
 use uuid::Uuid;
 
+api_scope! {
+    pub(super) users_delete = "";
+
+    guard: Delete;
+    middleware: [auth: || async { Ok::<_, ApiError>(AuthMiddleware::default()) }];
+    permissions: [Permission::UsersDelete];
+    paths: [delete_user];
+}
+
 generate_endpoint! {
     /// Delete a user given a external ID (UUID)
     ///
-    /// TODO: Currently there is no authentication, this will be changed in the future
+    /// Requires the `users:delete` permission.
     fn delete_user;
     method: delete;
     path: "/{id}";
@@ -19,15 +32,27 @@ generate_endpoint! {
         context_path: "/users",
         responses: {
             (status = 200, description = "Deleted the user"),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "Missing the `users:delete` permission"),
             (status = 500, description = "Error deleting user", body = Error)
         }
     }
     params: {
+        req: HttpRequest,
         repo: web::Data<UsersRepository>,
         id: web::Path<Uuid>
     }
     {
-        repo.delete_by_id(id.into_inner()).await?;
+        let id = id.into_inner();
+
+        repo.delete_by_id(id).await?;
+
+        AuditEntry::new(AuditAction::UserDeleted)
+            .request(&req)
+            .target_user(id)
+            .record()
+            .await;
+
         Ok(HttpResponse::Ok())
     }
 }
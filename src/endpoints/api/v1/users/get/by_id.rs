@@ -9,6 +9,8 @@ use uuid::Uuid;
 
 generate_endpoint! {
     /// Gets users by their external ID (UUID)
+    ///
+    /// Requires the `users:read` permission.
     fn get_user_by_id;
     method: get;
     path: "/{id}";
@@ -18,7 +20,9 @@ generate_endpoint! {
         tag: "user",
         context_path: "/users"
         responses: {
-            (status = 200, description = "Successfully fetched user", body = UserDTO)
+            (status = 200, description = "Successfully fetched user", body = UserDTO),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "Missing the `users:read` permission")
         }
     }
     params: {
@@ -1,13 +1,13 @@
-use crate::dto::{UserDTOCollection, UserDTOVecResponses};
+use crate::dto::{Page, UserDTO};
 use crate::error::ApiError;
 use crate::middleware::AuthMiddleware;
-use crate::repositories::oauth_token::OAUTH_TOKEN_REPOSITORY;
-use crate::repositories::users::{USERS_REPOSITORY, UsersRepository};
+use crate::models::permission::Permission;
+use crate::repositories::keyset::PageParams;
+use crate::repositories::users::{UserSearchParams, UsersRepository};
 use crate::traits::into_dto::IntoDTO;
 use crate::utils::api_scope;
 use actix_helper_utils::generate_endpoint;
 use actix_web::web;
-use sqlx_utils::traits::Repository;
 
 pub(crate) mod by_id;
 
@@ -15,35 +15,41 @@ api_scope! {
     pub(super) users_get = "";
 
     guard: Get;
-    middleware: [auth: || async {
-        let token_repo = *OAUTH_TOKEN_REPOSITORY;
-        let user_repo = *USERS_REPOSITORY;
-
-        Ok::<_, ApiError>(AuthMiddleware::new(token_repo, user_repo))
-    }];
+    middleware: [auth: || async { Ok::<_, ApiError>(AuthMiddleware::default()) }];
+    permissions: [Permission::UsersRead];
     paths: [get_users, by_id::get_user_by_id];
 }
 
 generate_endpoint! {
-    /// Gets all users that are registered.
+    /// Searches the registered users.
+    ///
+    /// Users can be filtered by username, email and when they were created, and are ordered by
+    /// the `sort` field. The response is paged with the cursors in `next` and `prev`, which are
+    /// also linked in the `Link` header, and `total` counts the users matching across all pages.
+    /// Cursors only work with the `sort` field they were made for.
+    ///
+    /// Requires the `users:read` permission.
     fn get_users;
     method: get;
     path: "";
-    return_type: UserDTOCollection;
+    return_type: Page<UserDTO>;
     error: ApiError;
     docs: {
         tag: "user",
         context_path: "/users",
         responses: {
-            (status = 200, description = "Successfully fetched users", body = UserDTOCollection)
+            (status = 200, description = "Successfully fetched users", body = Page<UserDTO>),
+            (status = 400, description = "Invalid query parameters or cursor"),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "Missing the `users:read` permission")
         }
     }
     params: {
-        repo: web::Data<UsersRepository>
+        repo: web::Data<UsersRepository>,
+        web::Query(params): web::Query<UserSearchParams>,
+        web::Query(page): web::Query<PageParams>
     }
     {
-        let users = repo.get_all().await?.into_dto();
-
-        Ok(users)
+        Ok(repo.search(&params, &page).await?.into_dto())
     }
 }
@@ -0,0 +1,64 @@
+use crate::error::ApiError;
+use crate::middleware::AuthMiddleware;
+use crate::models::permission::Permission;
+use crate::repositories::users::UsersRepository;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::services::lockout;
+use crate::utils::api_scope;
+use actix_helper_utils::generate_endpoint;
+use actix_web::{HttpRequest, HttpResponse, web};
+use serde_json::json;
+use sqlx_utils::traits::Repository;
+use uuid::Uuid;
+
+api_scope! {
+    pub(super) user_lockout = "/{id}/lockout";
+
+    middleware: [auth: || async { Ok::<_, ApiError>(AuthMiddleware::default()) }];
+    permissions: [Permission::UsersUnlock];
+    paths: [unlock_user];
+}
+
+generate_endpoint! {
+    /// Lifts the lockout of a user after failed sign-ins.
+    ///
+    /// Requires the `users:unlock` permission. Forgets the failed sign-ins with the username
+    /// and email of the user and their failed MFA codes. Addresses that were locked out stay
+    /// locked until the lockout ends.
+    fn unlock_user;
+    method: delete;
+    path: "";
+    return_type: Option<HttpResponse>;
+    error: ApiError;
+    docs: {
+        tag: "user",
+        context_path: "/users/{id}/lockout",
+        responses: {
+            (status = 204, description = "The user can sign in again"),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "Missing the `users:unlock` permission"),
+            (status = 404, description = "The user does not exist")
+        }
+    }
+    params: {
+        req: HttpRequest,
+        users: web::Data<UsersRepository>,
+        path: web::Path<Uuid>
+    };
+    {
+        let Some(user) = users.get_by_id(path.into_inner()).await? else {
+            return Ok(None);
+        };
+
+        let cleared = lockout::unlock_user(&user).await?;
+
+        AuditEntry::new(AuditAction::UserUnlocked)
+            .request(&req)
+            .target_user(user.ext_id.unwrap_or_default())
+            .details(json!({ "cleared": cleared }))
+            .record()
+            .await;
+
+        Ok(Some(HttpResponse::NoContent().finish()))
+    }
+}
@@ -1,20 +1,24 @@
-//! TODO: Most endpoints have no authentication in this module, this needs to be changed
-
+use crate::endpoints::api::v1::users::delete::users_delete_service;
 use crate::endpoints::api::v1::users::get::users_get_service;
+use crate::endpoints::api::v1::users::lockout::user_lockout_service;
+use crate::endpoints::api::v1::users::roles::user_roles_service;
 use crate::utils::api_scope;
 
 mod delete;
 mod get;
+mod lockout;
 mod post;
+mod roles;
+mod verify;
 
 api_scope! {
     pub(super) Users = "/users";
 
-    services: [users_get_service];
-    paths: [post::create_user, delete::delete_user];
+    services: [user_roles_service, user_lockout_service, users_get_service, users_delete_service];
+    paths: [post::create_user, verify::verify];
     docs: {
-        extra_paths: [get::get_users, get::by_id::get_user_by_id];
-        schemas: [crate::dto::UserDTO];
-        responses: [crate::dto::UserDTO, crate::dto::UserDTOVecResponses];
+        extra_paths: [get::get_users, get::by_id::get_user_by_id, delete::delete_user, roles::assign_role, roles::remove_role, lockout::unlock_user];
+        schemas: [crate::dto::UserDTO, crate::dto::EmailVerifyDTO, crate::dto::UserRolesDTO, crate::repositories::SortDirection, crate::repositories::users::UserSortField];
+        responses: [crate::dto::UserDTO, crate::dto::UserRolesDTO];
     }
 }
@@ -2,13 +2,19 @@ use crate::dto::user::create::UserCreateDTO;
 use crate::error::ApiError;
 use crate::prelude::*;
 use crate::repositories::users::UsersRepository;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::services::password_policy::{self, PasswordOwner};
+use crate::services::verification::try_send_verification_email;
 use actix_helper_utils::generate_endpoint;
 use actix_web::http::StatusCode;
-use actix_web::{HttpResponseBuilder, web};
-use validator::Validate;
+use actix_web::{HttpRequest, HttpResponseBuilder, web};
+use validator::{Validate, ValidationErrors};
 
 generate_endpoint! {
     /// Creates a new user.
+    ///
+    /// A link to verify the email is mailed to the user. The password has to follow the password
+    /// policy, every rule it breaks is returned as an error of the `password` field.
     fn create_user;
     method: post;
     path: "";
@@ -26,16 +32,35 @@ generate_endpoint! {
         }
     }
     params: {
+        req: HttpRequest,
         repo: web::Data<UsersRepository>,
         web::Json(dto): web::Json<UserCreateDTO>
     }
     {
-        match dto.validate() {
-            Ok(_) => {}
-            Err(error) => { return Err(error.into()); }
+        let mut errors = dto.validate().err().unwrap_or_else(ValidationErrors::new);
+
+        password_policy::validate(
+            &mut errors,
+            "password",
+            &dto.password,
+            PasswordOwner { username: &dto.username, email: &dto.email },
+        );
+
+        if !errors.is_empty() {
+            return Err(errors.into());
+        }
+
+        let user = repo.create_user(&dto.username, &dto.email, &dto.password).await?;
+
+        if let Some(ext_id) = user.ext_id {
+            AuditEntry::new(AuditAction::UserCreated)
+                .request(&req)
+                .target_user(ext_id)
+                .record()
+                .await;
         }
 
-        repo.create_user(&dto.username, &dto.email, &dto.password).await?;
+        try_send_verification_email(&user).await;
 
         Ok(HttpResponseBuilder::new(StatusCode::CREATED))
     }
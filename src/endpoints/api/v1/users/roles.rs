@@ -0,0 +1,121 @@
+use crate::dto::UserRolesDTO;
+use crate::error::ApiError;
+use crate::middleware::AuthMiddleware;
+use crate::models::permission::Permission;
+use crate::repositories::roles::RolesRepository;
+use crate::repositories::users::UsersRepository;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::utils::api_scope;
+use actix_helper_utils::generate_endpoint;
+use actix_web::{HttpRequest, web};
+use serde_json::json;
+use sqlx_utils::traits::Repository;
+use uuid::Uuid;
+
+api_scope! {
+    pub(super) user_roles = "/{id}/roles";
+
+    middleware: [auth: || async { Ok::<_, ApiError>(AuthMiddleware::default()) }];
+    permissions: [Permission::RolesAssign];
+    paths: [assign_role, remove_role];
+}
+
+generate_endpoint! {
+    /// Assigns a role to a user.
+    ///
+    /// Requires the `roles:assign` permission. Assigning a role the user already has does
+    /// nothing.
+    fn assign_role;
+    method: put;
+    path: "/{role}";
+    return_type: Option<UserRolesDTO>;
+    error: ApiError;
+    docs: {
+        tag: "user",
+        context_path: "/users/{id}/roles",
+        responses: {
+            (status = 200, description = "Assigned the role, returns the roles of the user", body = UserRolesDTO),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "Missing the `roles:assign` permission"),
+            (status = 404, description = "The user or role does not exist")
+        }
+    }
+    params: {
+        req: HttpRequest,
+        users: web::Data<UsersRepository>,
+        roles: web::Data<RolesRepository>,
+        path: web::Path<(Uuid, String)>
+    };
+    {
+        let (user, name) = path.into_inner();
+
+        if users.get_by_id(user).await?.is_none() {
+            return Ok(None);
+        }
+
+        let Some(role) = roles.get_id_by_name(&name).await? else {
+            return Ok(None);
+        };
+
+        roles.assign(user, role).await?;
+
+        AuditEntry::new(AuditAction::RoleAssigned)
+            .request(&req)
+            .target_user(user)
+            .details(json!({ "role": name }))
+            .record()
+            .await;
+
+        Ok(Some(UserRolesDTO {
+            roles: roles.get_names_by_user(user).await?,
+        }))
+    }
+}
+
+generate_endpoint! {
+    /// Removes a role from a user.
+    ///
+    /// Requires the `roles:assign` permission.
+    fn remove_role;
+    method: delete;
+    path: "/{role}";
+    return_type: Option<UserRolesDTO>;
+    error: ApiError;
+    docs: {
+        tag: "user",
+        context_path: "/users/{id}/roles",
+        responses: {
+            (status = 200, description = "Removed the role, returns the remaining roles of the user", body = UserRolesDTO),
+            (status = 401, description = "Missing or invalid access token"),
+            (status = 403, description = "Missing the `roles:assign` permission"),
+            (status = 404, description = "The role does not exist or the user does not have it")
+        }
+    }
+    params: {
+        req: HttpRequest,
+        roles: web::Data<RolesRepository>,
+        path: web::Path<(Uuid, String)>
+    };
+    {
+        let (user, name) = path.into_inner();
+
+        let Some(role) = roles.get_id_by_name(&name).await? else {
+            return Ok(None);
+        };
+
+        if !roles.unassign(user, role).await? {
+            return Ok(None);
+        }
+
+        AuditEntry::new(AuditAction::RoleRemoved)
+            .request(&req)
+            .target_user(user)
+            .details(json!({ "role": name }))
+            .record()
+            .await;
+
+        Ok(Some(UserRolesDTO {
+            roles: roles.get_names_by_user(user).await?,
+        }))
+    }
+}
@@ -0,0 +1,38 @@
+use crate::dto::EmailVerifyDTO;
+use crate::error::ApiError;
+use crate::prelude::*;
+use crate::services::verification::verify_email;
+use actix_helper_utils::generate_endpoint;
+use actix_web::{HttpResponse, web};
+
+generate_endpoint! {
+    /// Verifies the email of a user with the token from the verification mail.
+    fn verify;
+    method: post;
+    path: "/verify";
+    return_type: HttpResponse;
+    error: ApiError;
+    docs: {
+        tag: "user",
+        context_path: "/users",
+        request_body: {
+            schema = EmailVerifyDTO
+        }
+        responses: {
+            (status = 204, description = "Verified the email"),
+            (status = 400, description = "The token is invalid, expired or was already used", body = Error)
+        }
+    }
+    params: {
+        web::Json(dto): web::Json<EmailVerifyDTO>
+    }
+    {
+        if !verify_email(&dto.token).await? {
+            return Err(ApiError::BadRequest(
+                "The verification token is invalid or expired".into(),
+            ));
+        }
+
+        Ok(HttpResponse::NoContent().finish())
+    }
+}
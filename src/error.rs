@@ -19,6 +19,12 @@ use crate::dto::Error;
 pub enum ApiError {
     #[error("Error: {0}.")]
     Basic(String),
+    #[error("Bad request: {0}.")]
+    BadRequest(String),
+    #[error("Unauthorized: {0}.")]
+    Unauthorized(String),
+    #[error("Forbidden: {0}.")]
+    Forbidden(String),
     #[error(transparent)]
     Validation(#[from] ValidationErrors),
     #[cfg_attr(debug_assertions, error("Database error occurred: {0}."))]
@@ -31,6 +37,8 @@ pub enum ApiError {
     Argon2(#[from] argon2::password_hash::errors::Error),
     #[error(transparent)]
     Llm(#[from] tosic_llm::error::LlmError),
+    #[error("Failed to send mail: {0}.")]
+    Mail(String),
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
     #[error(transparent)]
@@ -74,7 +82,9 @@ pub enum ServerError {
 impl ResponseError for ApiError {
     fn status_code(&self) -> StatusCode {
         match self {
-            Self::Validation(..) => StatusCode::BAD_REQUEST,
+            Self::Validation(..) | Self::BadRequest(..) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(..) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(..) => StatusCode::FORBIDDEN,
             Self::FailedDependency { .. } => StatusCode::FAILED_DEPENDENCY,
             _ => {
                 error!("An error occurred: {self}");
@@ -97,6 +107,10 @@ impl ResponseError for ApiError {
             Error {
                 code: code.into(),
                 error: msg.into(),
+                fields: match self {
+                    Self::Validation(errors) => serde_json::to_value(errors).ok(),
+                    _ => None,
+                },
                 #[cfg(debug_assertions)]
                 stack_trace: backtrace.into(),
             }
@@ -144,6 +158,7 @@ pub fn default_error_handler<B: MessageBody + 'static>(
         Error {
             code: status.to_string().into(),
             error: error_message.into(),
+            fields: None,
             #[cfg(debug_assertions)]
             stack_trace: backtrace.into(),
         }
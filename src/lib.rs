@@ -1,4 +1,3 @@
-#![feature(associated_type_defaults)]
 #![allow(async_fn_in_trait)]
 
 use crate::error::{ApiError, ServerError};
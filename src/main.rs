@@ -1,5 +1,3 @@
-#![feature(fn_traits)]
-#![feature(associated_type_defaults)]
 #![allow(async_fn_in_trait)]
 
 use ferric_api::setup::setup;
@@ -1,6 +1,17 @@
+use crate::ApiResult;
+use crate::error::ApiError;
+use crate::models::oauth_client::OAuthClient;
 use crate::models::oauth_token::TokenType;
-use crate::repositories::oauth_token::{OauthTokenFilter, OauthTokenRepository};
-use crate::repositories::users::UsersRepository;
+use crate::models::permission::{Permission, Permissions};
+use crate::models::user::User;
+use crate::repositories::oauth_clients::{OAUTH_CLIENTS_REPOSITORY, OauthClientsRepository};
+use crate::repositories::oauth_token::{
+    OAUTH_TOKEN_REPOSITORY, OauthTokenFilter, OauthTokenRepository,
+};
+use crate::repositories::roles::{ROLES_REPOSITORY, RolesRepository};
+use crate::repositories::sessions::{SESSIONS_REPOSITORY, SessionsRepository};
+use crate::repositories::users::{USERS_REPOSITORY, UsersRepository};
+use crate::services::session::{SESSION_COOKIE, idle_timeout, verify_csrf};
 use crate::utils::middleware_macros::define_middleware;
 use actix_web::dev::ServiceRequest;
 use actix_web::http::StatusCode;
@@ -19,6 +30,14 @@ pub enum AuthError {
     InvalidTokenType,
     #[error("User not found")]
     UserNotFound,
+    #[error("Client not found")]
+    ClientNotFound,
+    #[error("Invalid or expired session")]
+    InvalidSession,
+    #[error("Missing or invalid CSRF token")]
+    InvalidCsrfToken,
+    #[error("Missing permission '{0}'")]
+    MissingPermission(Permission),
     #[error("Internal server error")]
     InternalError,
 }
@@ -30,6 +49,10 @@ impl ResponseError for AuthError {
             AuthError::InvalidToken => StatusCode::UNAUTHORIZED,
             AuthError::InvalidTokenType => StatusCode::UNAUTHORIZED,
             AuthError::UserNotFound => StatusCode::UNAUTHORIZED,
+            AuthError::ClientNotFound => StatusCode::UNAUTHORIZED,
+            AuthError::InvalidSession => StatusCode::UNAUTHORIZED,
+            AuthError::InvalidCsrfToken => StatusCode::FORBIDDEN,
+            AuthError::MissingPermission(_) => StatusCode::FORBIDDEN,
             AuthError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -42,11 +65,87 @@ impl ResponseError for AuthError {
     }
 }
 
+/// Who a request authenticated by [`AuthMiddleware`] is made by.
+///
+/// Handlers get it as `web::ReqData<Principal>`. Requests authenticated with a bearer token
+/// also carry the `OAuthToken`, and requests authenticated with the session cookie the
+/// `Session`.
+#[derive(Debug, Clone)]
+pub(crate) enum Principal {
+    /// A user, either directly or through a client the user has authorized.
+    ///
+    /// When a client acts for the user, `client_id` is the row id of the client and
+    /// `permissions` only has the permissions of the user's roles that the scopes of the token
+    /// cover.
+    User {
+        user: User,
+        permissions: Permissions,
+        client_id: Option<i64>,
+    },
+    /// A client acting on its own behalf with a token from the client credentials grant.
+    Client(OAuthClient),
+}
+
+/// Clients acting on their own behalf have no roles.
+static NO_PERMISSIONS: Permissions = Permissions::NONE;
+
+impl Principal {
+    /// The user the request is made by.
+    ///
+    /// Fails with [`ApiError::Forbidden`] for clients, for endpoints that only make sense for
+    /// users.
+    pub(crate) fn user(&self) -> ApiResult<&User> {
+        match self {
+            Self::User { user, .. } => Ok(user),
+            Self::Client(_) => Err(ApiError::Forbidden(
+                "This endpoint is only available to users".into(),
+            )),
+        }
+    }
+
+    /// The user the request is made by, when the user makes it themselves.
+    ///
+    /// Like [`Principal::user`], but also fails with [`ApiError::Forbidden`] for tokens a client
+    /// obtained for the user, for endpoints that manage the account itself.
+    pub(crate) fn account_user(&self) -> ApiResult<&User> {
+        match self {
+            Self::User {
+                client_id: Some(_), ..
+            } => Err(ApiError::Forbidden(
+                "This endpoint is not available to clients acting for a user".into(),
+            )),
+            _ => self.user(),
+        }
+    }
+
+    /// The permissions the request is made with.
+    pub(crate) fn permissions(&self) -> &Permissions {
+        match self {
+            Self::User { permissions, .. } => permissions,
+            Self::Client(_) => &NO_PERMISSIONS,
+        }
+    }
+
+    /// Fails with [`ApiError::Forbidden`] unless the request is made with `permission`.
+    pub(crate) fn require(&self, permission: Permission) -> ApiResult<()> {
+        if self.permissions().contains(permission) {
+            Ok(())
+        } else {
+            Err(ApiError::Forbidden(format!(
+                "Missing permission '{permission}'"
+            )))
+        }
+    }
+}
+
 define_middleware! {
     #[derive(Debug)]
     pub struct AuthMiddleware {
         token_repo: OauthTokenRepository,
         user_repo: UsersRepository,
+        client_repo: OauthClientsRepository,
+        session_repo: SessionsRepository,
+        role_repo: RolesRepository,
     },
 
     pub struct AuthMiddlewareService;
@@ -55,13 +154,45 @@ define_middleware! {
         {
             let headers = req.headers();
 
-            let auth_header = headers
-                .get(AUTHORIZATION)
-                .ok_or(AuthError::MissingAuth)?;
+            let Some(auth_header) = headers.get(AUTHORIZATION) else {
+                // Browsers authenticate with the session cookie instead of a bearer token.
+                let cookie = req.cookie(SESSION_COOKIE).ok_or(AuthError::MissingAuth)?;
+
+                let session = service.session_repo
+                    .touch(cookie.value(), idle_timeout())
+                    .await
+                    .map_err(|_| AuthError::InternalError)?
+                    .ok_or(AuthError::InvalidSession)?;
+
+                if !verify_csrf(req.request(), &session) {
+                    return Err(AuthError::InvalidCsrfToken.into())
+                }
+
+                let user = service.user_repo
+                    .get_by_id(session.user_ext_id)
+                    .await
+                    .map_err(|_| AuthError::InternalError)?
+                    .ok_or(AuthError::UserNotFound)?;
+                let permissions = service.role_repo
+                    .permissions_of(session.user_ext_id)
+                    .await
+                    .map_err(|_| AuthError::InternalError)?;
+
+                {
+                    let mut ext = req.extensions_mut();
+                    ext.insert(session);
+                    ext.insert(Principal::User { user, permissions, client_id: None });
+                }
+
+                return service.service.call(req).await;
+            };
 
             let token = auth_header
                 .to_str()
                 .map_err(|_| AuthError::InvalidToken)?;
+            let token = token.strip_prefix("Bearer ").unwrap_or(token);
+
+            // Tokens are stored as digests, the filter hashes the raw token before the lookup.
 
             let token_res = service.token_repo.get_by_filter(OauthTokenFilter::new().token(token)).await?;
             let token_model = token_res.first().cloned();
@@ -72,18 +203,88 @@ define_middleware! {
                 return Err(AuthError::InvalidTokenType.into())
             }
 
-            let user = service.user_repo
-                .get_by_id(token.user_ext_id)
-                .await
-                .map_err(|_| AuthError::InternalError)?
-                .ok_or(AuthError::UserNotFound)?;
+            // Tokens without a user were issued to a client through the client credentials grant.
+            let principal = match (token.user_ext_id, token.client_id) {
+                (Some(user_ext_id), _) => {
+                    let user = service.user_repo
+                        .get_by_id(user_ext_id)
+                        .await
+                        .map_err(|_| AuthError::InternalError)?
+                        .ok_or(AuthError::UserNotFound)?;
+                    let permissions = service.role_repo
+                        .permissions_of(user_ext_id)
+                        .await
+                        .map_err(|_| AuthError::InternalError)?;
+
+                    // Clients only get the permissions the user allowed them through the scopes.
+                    let permissions = match token.client_id {
+                        Some(_) => permissions.restrict_to_scopes(&token.scopes),
+                        None => permissions,
+                    };
+
+                    Principal::User { user, permissions, client_id: token.client_id }
+                }
+                (None, Some(client_id)) => {
+                    let client = service.client_repo
+                        .get_by_row_id(client_id)
+                        .await
+                        .map_err(|_| AuthError::InternalError)?
+                        .ok_or(AuthError::ClientNotFound)?;
+
+                    Principal::Client(client)
+                }
+                (None, None) => return Err(AuthError::InvalidToken.into()),
+            };
 
             let mut ext = req.extensions_mut();
             ext.insert(token);
-            ext.insert(user);
+            ext.insert(principal);
         }
 
         // Continue with the request
         service.service.call(req).await
     }
 }
+
+impl Default for AuthMiddleware {
+    /// Uses the repositories over the global database pool.
+    fn default() -> Self {
+        Self::new(
+            *OAUTH_TOKEN_REPOSITORY,
+            *USERS_REPOSITORY,
+            *OAUTH_CLIENTS_REPOSITORY,
+            *SESSIONS_REPOSITORY,
+            *ROLES_REPOSITORY,
+        )
+    }
+}
+
+define_middleware! {
+    /// Only lets requests made with all of `permissions` through, responds with `403` otherwise.
+    ///
+    /// Has to be wrapped inside [`AuthMiddleware`], `api_scope!` does this for scopes that list
+    /// `permissions`.
+    #[derive(Debug)]
+    pub(crate) struct RequirePermissions {
+        permissions: &'static [Permission],
+    },
+
+    pub(crate) struct RequirePermissionsService;
+
+    |service: RequirePermissionsService<S>, req: ServiceRequest| async move {
+        {
+            let ext = req.extensions();
+            let principal = ext.get::<Principal>().ok_or(AuthError::MissingAuth)?;
+
+            if let Some(missing) = service
+                .permissions
+                .iter()
+                .find(|permission| !principal.permissions().contains(**permission))
+            {
+                return Err(AuthError::MissingPermission(*missing).into())
+            }
+        }
+
+        service.service.call(req).await
+    }
+}
@@ -0,0 +1,196 @@
+use crate::repositories::keyset::{Cursor, Keyset};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use sqlx_utils::traits::Model;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Hash the first event in the audit log is chained to.
+pub(crate) const GENESIS_HASH: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Whether what an audit event records succeeded.
+#[derive(Debug, Default, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum AuditOutcome {
+    #[default]
+    Success,
+    Failure,
+}
+
+impl AuditOutcome {
+    /// The outcome in the database.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+        }
+    }
+}
+
+/// A security relevant event, such as a sign-in or a permission change.
+///
+/// Events are chained by hash, see [`AuditEvent::chain_hash`].
+#[derive(Default, Debug, Clone, Eq, PartialEq, FromRow, Serialize, Deserialize)]
+pub(crate) struct AuditEvent {
+    pub(crate) id: Option<i64>,
+    /// What happened, see [`AuditAction`](crate::services::audit::AuditAction).
+    pub(crate) action: String,
+    /// See [`AuditOutcome`].
+    pub(crate) outcome: String,
+    /// The user that did it, if a user did it.
+    pub(crate) actor_user_ext_id: Option<Uuid>,
+    /// The client that did it, on its own or for the user.
+    pub(crate) actor_client_id: Option<String>,
+    /// What kind of thing it was done to, such as `user` or `client`.
+    pub(crate) target_type: Option<String>,
+    pub(crate) target_id: Option<String>,
+    pub(crate) ip: Option<String>,
+    pub(crate) user_agent: Option<String>,
+    /// JSON object with details of the event.
+    pub(crate) details: Option<String>,
+    pub(crate) created_at: NaiveDateTime,
+    /// Hash of the event before this one.
+    pub(crate) prev_hash: String,
+    pub(crate) hash: String,
+}
+
+impl AuditEvent {
+    /// Hash of the event chained to the event with the hash `prev_hash`.
+    ///
+    /// Covers every column but `id` and `hash`. Each field is tagged and length prefixed so
+    /// that no two different events hash the same input.
+    pub(crate) fn chain_hash(&self, prev_hash: &str) -> String {
+        let created_at = self.created_at.and_utc().timestamp_micros().to_string();
+        let actor_user = self.actor_user_ext_id.map(|id| id.to_string());
+        let fields = [
+            Some(self.action.as_str()),
+            Some(self.outcome.as_str()),
+            actor_user.as_deref(),
+            self.actor_client_id.as_deref(),
+            self.target_type.as_deref(),
+            self.target_id.as_deref(),
+            self.ip.as_deref(),
+            self.user_agent.as_deref(),
+            self.details.as_deref(),
+            Some(created_at.as_str()),
+        ];
+
+        let mut hasher = Sha256::new();
+        hasher.update(prev_hash.as_bytes());
+
+        for field in fields {
+            match field {
+                Some(value) => {
+                    hasher.update([1]);
+                    hasher.update((value.len() as u64).to_be_bytes());
+                    hasher.update(value.as_bytes());
+                }
+                None => hasher.update([0]),
+            }
+        }
+
+        hex::encode(hasher.finalize())
+    }
+
+    /// Whether the event is chained to `prev_hash` and its contents match its hash.
+    pub(crate) fn is_intact(&self, prev_hash: &str) -> bool {
+        self.prev_hash == prev_hash && self.hash == self.chain_hash(prev_hash)
+    }
+}
+
+impl Model for AuditEvent {
+    type Id = i64;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.id
+    }
+}
+
+impl Keyset for AuditEvent {
+    fn cursor(&self) -> Option<Cursor> {
+        Some(Cursor::created_at(self.created_at, self.id?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An event chained to `prev_hash` like the repository chains it.
+    fn chained(prev_hash: &str, action: &str) -> AuditEvent {
+        let mut event = AuditEvent {
+            action: action.to_string(),
+            outcome: AuditOutcome::Success.as_str().to_string(),
+            actor_user_ext_id: Some(Uuid::nil()),
+            target_type: Some("user".to_string()),
+            details: Some("{}".to_string()),
+            ..Default::default()
+        };
+        event.hash = event.chain_hash(prev_hash);
+        event.prev_hash = prev_hash.to_string();
+
+        event
+    }
+
+    #[test]
+    fn chained_events_are_intact() {
+        let first = chained(GENESIS_HASH, "auth.login");
+        let second = chained(&first.hash, "auth.logout");
+
+        assert!(first.is_intact(GENESIS_HASH));
+        assert!(second.is_intact(&first.hash));
+        assert_ne!(first.hash, second.hash);
+    }
+
+    #[test]
+    fn changed_events_are_not_intact() {
+        let mut event = chained(GENESIS_HASH, "auth.login");
+        event.outcome = AuditOutcome::Failure.as_str().to_string();
+
+        assert!(!event.is_intact(GENESIS_HASH));
+    }
+
+    #[test]
+    fn events_are_not_intact_after_another_event() {
+        let first = chained(GENESIS_HASH, "auth.login");
+        let second = chained(&first.hash, "auth.logout");
+
+        assert!(!second.is_intact(GENESIS_HASH));
+        assert!(!first.is_intact(&second.hash));
+    }
+
+    #[test]
+    fn moving_text_between_fields_changes_the_hash() {
+        let event = AuditEvent {
+            target_type: Some("user".to_string()),
+            target_id: Some("42".to_string()),
+            ..Default::default()
+        };
+        let shifted = AuditEvent {
+            target_type: Some("user4".to_string()),
+            target_id: Some("2".to_string()),
+            ..Default::default()
+        };
+        let missing = AuditEvent {
+            target_type: Some("user".to_string()),
+            target_id: Some(String::new()),
+            ..Default::default()
+        };
+        let unset = AuditEvent {
+            target_type: Some("user".to_string()),
+            ..Default::default()
+        };
+
+        assert_ne!(
+            event.chain_hash(GENESIS_HASH),
+            shifted.chain_hash(GENESIS_HASH)
+        );
+        assert_ne!(
+            missing.chain_hash(GENESIS_HASH),
+            unset.chain_hash(GENESIS_HASH)
+        );
+    }
+}
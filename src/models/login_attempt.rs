@@ -0,0 +1,27 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx_utils::traits::Model;
+
+/// Failed sign-ins for a username, address or MFA user.
+#[derive(
+    Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, FromRow, Serialize, Deserialize,
+)]
+pub(crate) struct LoginAttempt {
+    pub(crate) id: Option<i64>,
+    /// What `key` is, see [`AttemptKind`](crate::services::lockout::AttemptKind).
+    pub(crate) kind: String,
+    pub(crate) key: String,
+    /// Failures since the counter was last reset.
+    pub(crate) failures: i32,
+    pub(crate) last_failure_at: NaiveDateTime,
+    pub(crate) locked_until: Option<NaiveDateTime>,
+}
+
+impl Model for LoginAttempt {
+    type Id = i64;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.id
+    }
+}
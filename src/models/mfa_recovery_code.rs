@@ -0,0 +1,38 @@
+use crate::utils::hashing::token_digest;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx_utils::traits::Model;
+use uuid::Uuid;
+
+/// A single use code that stands in for a TOTP code.
+#[derive(
+    Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, FromRow, Serialize, Deserialize,
+)]
+pub(crate) struct MfaRecoveryCode {
+    pub(crate) id: Option<i64>,
+    pub(crate) user_ext_id: Uuid,
+    /// SHA-256 digest of the code, the code itself is never stored.
+    pub(crate) code_hash: String,
+    pub(crate) created_at: Option<NaiveDateTime>,
+}
+
+impl MfaRecoveryCode {
+    /// Creates a recovery code record, only the digest of `code` is kept.
+    pub(crate) fn new(code: &str, user_ext_id: Uuid) -> Self {
+        Self {
+            id: None,
+            user_ext_id,
+            code_hash: token_digest(code),
+            created_at: None,
+        }
+    }
+}
+
+impl Model for MfaRecoveryCode {
+    type Id = i64;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.id
+    }
+}
@@ -1,3 +1,16 @@
+pub(crate) mod audit_event;
+pub(crate) mod login_attempt;
+pub(crate) mod mfa_recovery_code;
+pub(crate) mod oauth_auth_code;
 pub(crate) mod oauth_client;
+pub(crate) mod oauth_client_secret;
 pub(crate) mod oauth_token;
+pub(crate) mod oidc_login_state;
+pub(crate) mod password_reset;
+pub(crate) mod permission;
+pub(crate) mod role;
+pub(crate) mod session;
 pub(crate) mod user;
+pub(crate) mod user_consent;
+pub(crate) mod user_identity;
+pub(crate) mod user_totp;
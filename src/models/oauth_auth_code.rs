@@ -0,0 +1,62 @@
+use crate::utils::hashing::token_digest;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx_utils::traits::Model;
+use uuid::Uuid;
+
+/// An authorization code issued by the authorization endpoint.
+///
+/// The code is bound to the client, redirect URI, scopes and PKCE code challenge of the
+/// authorization request it was issued for, and can be exchanged for a token once before it
+/// expires.
+#[derive(
+    Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, FromRow, Serialize, Deserialize,
+)]
+pub(crate) struct OAuthAuthCode {
+    pub(crate) id: Option<i64>,
+    /// SHA-256 digest of the code, the code itself is never stored.
+    pub(crate) code_hash: String,
+    /// Row id of the client the code was issued to.
+    pub(crate) client_id: i64,
+    pub(crate) user_ext_id: Uuid,
+    /// The redirect URI exactly as it was sent in the authorization request.
+    pub(crate) redirect_uri: String,
+    pub(crate) scopes: Vec<String>,
+    /// `S256` PKCE code challenge the code verifier has to match, if the request had one.
+    pub(crate) code_challenge: Option<String>,
+    pub(crate) expires_at: NaiveDateTime,
+    pub(crate) created_at: Option<NaiveDateTime>,
+}
+
+impl OAuthAuthCode {
+    /// Creates an authorization code record, only the digest of `code` is kept.
+    pub(crate) fn new(
+        code: &str,
+        client_id: i64,
+        user_ext_id: Uuid,
+        redirect_uri: impl Into<String>,
+        scopes: Vec<String>,
+        expires_at: NaiveDateTime,
+    ) -> Self {
+        Self {
+            id: None,
+            code_hash: token_digest(code),
+            client_id,
+            user_ext_id,
+            redirect_uri: redirect_uri.into(),
+            scopes,
+            code_challenge: None,
+            expires_at,
+            created_at: None,
+        }
+    }
+}
+
+impl Model for OAuthAuthCode {
+    type Id = i64;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.id
+    }
+}
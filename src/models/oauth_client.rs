@@ -1,62 +1,116 @@
+use crate::ApiResult;
+use crate::models::oauth_client_secret::OAuthClientSecret;
+use crate::models::permission::{Permission, Permissions};
+use crate::models::user::User;
+use crate::repositories::keyset::{Cursor, Keyset};
 use crate::traits::FromModel;
 use crate::traits::into_dto::IntoDTO;
 use actix_oauth::dto::create::OAuthCreateClientDTO;
 use actix_oauth::dto::{OAuthClientDTO, OAuthClientDTOCollection};
-use actix_oauth::types::{ClientId, ClientSecret, GrantType, RedirectUri, Scopes};
+use actix_oauth::types::{ClientId, ClientSecret, ClientType, GrantType, RedirectUri, Scopes};
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use sqlx_utils::traits::Model;
+use uuid::Uuid;
 
 #[derive(
     Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, FromRow, Serialize, Deserialize,
 )]
 pub(crate) struct OAuthClient {
+    /// Row id, tokens and authorization codes reference the client by it.
+    pub(crate) id: Option<i64>,
     pub(crate) client_id: String,
-    pub(crate) client_secret: String,
-    pub(crate) redirect_uri: String,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) logo_uri: Option<String>,
+    pub(crate) contact: Option<String>,
+    /// The user that registered the client, clients created before owners were tracked have none.
+    pub(crate) owner_ext_id: Option<Uuid>,
+    pub(crate) client_type: ClientType,
+    pub(crate) redirect_uris: Vec<String>,
     pub(crate) grant_types: Vec<GrantType>,
     pub(crate) scopes: Vec<String>,
     pub(crate) created_at: Option<NaiveDateTime>,
 }
 
 impl OAuthClient {
-    pub fn new(dto: OAuthCreateClientDTO) -> Self {
+    /// Creates a client owned by `owner` with a random id, and a random secret if the client is
+    /// confidential.
+    ///
+    /// The secret is stored separately from the client, it has to be inserted after the client
+    /// and handed to the caller now as it cannot be recovered later.
+    #[allow(clippy::type_complexity)]
+    pub fn new(
+        dto: OAuthCreateClientDTO,
+        owner: Uuid,
+    ) -> ApiResult<(Self, Option<(OAuthClientSecret, ClientSecret)>)> {
         let id = ClientId::new_random();
-        let secret = ClientSecret::new_random();
+        let secret = dto
+            .client_type
+            .has_secret()
+            .then(|| OAuthClientSecret::new(id.to_string()))
+            .transpose()?;
 
-        Self {
+        let client = Self {
+            id: None,
             client_id: id.to_string(),
-            client_secret: secret.secret().to_string(),
-            redirect_uri: dto.redirect_uri.to_string(),
+            name: dto.name,
+            description: dto.description,
+            logo_uri: dto.logo_uri,
+            contact: dto.contact,
+            owner_ext_id: Some(owner),
+            client_type: dto.client_type,
+            redirect_uris: dto
+                .redirect_uris
+                .iter()
+                .map(|uri| uri.to_string())
+                .collect(),
             grant_types: dto.grant_types,
             scopes: dto.scopes.to_vec(),
             created_at: None,
-        }
+        };
+
+        Ok((client, secret))
     }
-}
 
-/*impl IntoDTO<OAuthClientDTO> for OAuthClient {
-    fn into_dto(self) -> OAuthClientDTO {
-        OAuthClientDTO {
-            client_id: ClientId::new(self.client_id),
-            client_secret: ClientSecret::new(self.client_secret),
-            redirect_uri: RedirectUri::new(self.redirect_uri),
-            grant_types: self.grant_types,
-            scopes: Scopes::from_iter(self.scopes),
-            created_at: self
-                .created_at
-                .expect("Expected 'created_at' to be populated"),
-        }
+    /// Whether `user` may see and manage the client, either as its owner or with the
+    /// [`Permission::ClientsManage`] permission.
+    pub(crate) fn is_accessible_by(&self, user: &User, permissions: &Permissions) -> bool {
+        permissions.contains(Permission::ClientsManage)
+            || (self.owner_ext_id.is_some() && self.owner_ext_id == user.ext_id)
+    }
+
+    /// The registered redirect URIs, skipping any that no longer parse.
+    pub(crate) fn redirect_uris(&self) -> Vec<RedirectUri> {
+        self.redirect_uris
+            .iter()
+            .filter_map(|uri| RedirectUri::new(uri.as_str()).ok())
+            .collect()
+    }
+
+    /// Whether `requested` matches one of the registered redirect URIs.
+    ///
+    /// See [`RedirectUri::matches`] for the matching rules.
+    pub(crate) fn allows_redirect_uri(&self, requested: &RedirectUri) -> bool {
+        RedirectUri::is_registered(&self.redirect_uris(), requested)
     }
-}*/
+}
 
 impl FromModel<OAuthClient> for OAuthClientDTO {
     fn from_model(model: OAuthClient) -> Self {
+        let redirect_uris = model.redirect_uris();
+
         Self {
             client_id: ClientId::new(model.client_id),
-            client_secret: ClientSecret::new(model.client_secret),
-            redirect_uri: RedirectUri::new(model.redirect_uri),
+            name: model.name,
+            description: model.description,
+            logo_uri: model.logo_uri,
+            contact: model.contact,
+            owner: model.owner_ext_id.map(|owner| owner.to_string()),
+            client_type: model.client_type,
+            client_secret: None,
+            redirect_uris,
             grant_types: model.grant_types,
             scopes: Scopes::from_iter(model.scopes),
             created_at: model
@@ -80,3 +134,9 @@ impl Model for OAuthClient {
         Some(self.client_id.to_string())
     }
 }
+
+impl Keyset for OAuthClient {
+    fn cursor(&self) -> Option<Cursor> {
+        Some(Cursor::created_at(self.created_at?, self.id?))
+    }
+}
@@ -0,0 +1,79 @@
+use crate::ApiResult;
+use crate::traits::FromModel;
+use crate::traits::into_dto::IntoDTO;
+use crate::utils::hashing::hash_secret;
+use actix_oauth::dto::{OAuthClientSecretDTO, OAuthClientSecretDTOCollection};
+use actix_oauth::types::ClientSecret;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx_utils::traits::Model;
+
+/// A secret a confidential client can authenticate with.
+///
+/// Clients can have several valid secrets at once, older secrets get an expiry when a new one is
+/// issued so clients can switch over without downtime.
+#[derive(
+    Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, FromRow, Serialize, Deserialize,
+)]
+pub(crate) struct OAuthClientSecret {
+    pub(crate) id: Option<i64>,
+    pub(crate) client_id: String,
+    /// Argon2 hash of the secret, the secret itself is only known when it is issued.
+    pub(crate) secret_hash: String,
+    pub(crate) expires_at: Option<NaiveDateTime>,
+    pub(crate) revoked_at: Option<NaiveDateTime>,
+    pub(crate) last_used_at: Option<NaiveDateTime>,
+    pub(crate) created_at: Option<NaiveDateTime>,
+}
+
+impl OAuthClientSecret {
+    /// Creates a new random secret for a client.
+    ///
+    /// The returned secret has to be handed to the caller now as only its hash is stored.
+    pub(crate) fn new(client_id: impl Into<String>) -> ApiResult<(Self, ClientSecret)> {
+        let secret = ClientSecret::new_random();
+
+        let model = Self {
+            id: None,
+            client_id: client_id.into(),
+            secret_hash: hash_secret(secret.secret())?,
+            expires_at: None,
+            revoked_at: None,
+            last_used_at: None,
+            created_at: None,
+        };
+
+        Ok((model, secret))
+    }
+}
+
+impl FromModel<OAuthClientSecret> for OAuthClientSecretDTO {
+    fn from_model(model: OAuthClientSecret) -> Self {
+        Self {
+            id: model.id.expect("Expected 'id' to be populated"),
+            client_secret: None,
+            created_at: model
+                .created_at
+                .expect("Expected 'created_at' to be populated"),
+            expires_at: model.expires_at,
+            revoked_at: model.revoked_at,
+            last_used_at: model.last_used_at,
+        }
+    }
+}
+
+impl FromModel<Vec<OAuthClientSecret>> for OAuthClientSecretDTOCollection {
+    fn from_model(model: Vec<OAuthClientSecret>) -> Self {
+        let dto: Vec<OAuthClientSecretDTO> = model.into_dto();
+        dto.into()
+    }
+}
+
+impl Model for OAuthClientSecret {
+    type Id = i64;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.id
+    }
+}
@@ -1,3 +1,5 @@
+use crate::repositories::keyset::{Cursor, Keyset};
+use crate::utils::hashing::token_digest;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -43,9 +45,11 @@ impl Display for TokenType {
 )]
 pub struct OAuthToken {
     pub(crate) id: Option<i64>,
-    pub(crate) token: String,
+    /// SHA-256 digest of the token, the token itself is never stored.
+    pub(crate) token_hash: String,
     pub(crate) client_id: Option<i64>,
-    pub(crate) user_ext_id: Uuid,
+    /// The user the token was issued for, tokens from the client credentials grant have none.
+    pub(crate) user_ext_id: Option<Uuid>,
     pub(crate) token_type: TokenType,
     pub(crate) scopes: Vec<String>,
     pub(crate) expires_at: NaiveDateTime,
@@ -53,15 +57,16 @@ pub struct OAuthToken {
 }
 
 impl OAuthToken {
+    /// Creates a token record, only the digest of `token` is kept.
     pub(crate) fn new(
-        token: String,
-        user_ext_id: Uuid,
+        token: &str,
+        user_ext_id: Option<Uuid>,
         token_type: TokenType,
         expires_at: NaiveDateTime,
     ) -> Self {
         Self {
             id: None,
-            token,
+            token_hash: token_digest(token),
             client_id: None,
             user_ext_id,
             token_type,
@@ -79,3 +84,9 @@ impl Model for OAuthToken {
         self.id
     }
 }
+
+impl Keyset for OAuthToken {
+    fn cursor(&self) -> Option<Cursor> {
+        Some(Cursor::created_at(self.created_at?, self.id?))
+    }
+}
@@ -0,0 +1,63 @@
+use crate::utils::hashing::token_digest;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx_utils::traits::Model;
+use uuid::Uuid;
+
+/// A login that was sent to an upstream OpenID Connect provider.
+///
+/// Holds what is needed to verify the response of the provider when the user comes back.
+#[derive(
+    Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, FromRow, Serialize, Deserialize,
+)]
+pub(crate) struct OidcLoginState {
+    pub(crate) id: Option<i64>,
+    /// SHA-256 digest of the `state` parameter, the state itself is never stored.
+    pub(crate) state_hash: String,
+    /// SHA-256 digest of the nonce in the login cookie, binds the login to the browser that
+    /// started it.
+    pub(crate) binding_hash: String,
+    pub(crate) provider: String,
+    pub(crate) nonce: String,
+    pub(crate) pkce_verifier: String,
+    /// The signed in user the identity should be linked to, if any.
+    pub(crate) user_ext_id: Option<Uuid>,
+    /// Authorization request to continue once the user is signed in.
+    pub(crate) return_to: Option<String>,
+    pub(crate) expires_at: NaiveDateTime,
+    pub(crate) created_at: Option<NaiveDateTime>,
+}
+
+impl OidcLoginState {
+    /// Creates a login state record, only the digests of `state` and `binding` are kept.
+    pub(crate) fn new(
+        state: &str,
+        binding: &str,
+        provider: impl Into<String>,
+        nonce: impl Into<String>,
+        pkce_verifier: impl Into<String>,
+        expires_at: NaiveDateTime,
+    ) -> Self {
+        Self {
+            id: None,
+            state_hash: token_digest(state),
+            binding_hash: token_digest(binding),
+            provider: provider.into(),
+            nonce: nonce.into(),
+            pkce_verifier: pkce_verifier.into(),
+            user_ext_id: None,
+            return_to: None,
+            expires_at,
+            created_at: None,
+        }
+    }
+}
+
+impl Model for OidcLoginState {
+    type Id = i64;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.id
+    }
+}
@@ -0,0 +1,40 @@
+use crate::utils::hashing::token_digest;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx_utils::traits::Model;
+use uuid::Uuid;
+
+/// A password reset a user asked for, redeemed with the token mailed to them.
+#[derive(
+    Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, FromRow, Serialize, Deserialize,
+)]
+pub(crate) struct PasswordReset {
+    pub(crate) id: Option<i64>,
+    /// SHA-256 digest of the reset token, the token itself is never stored.
+    pub(crate) token_hash: String,
+    pub(crate) user_ext_id: Uuid,
+    pub(crate) expires_at: NaiveDateTime,
+    pub(crate) created_at: Option<NaiveDateTime>,
+}
+
+impl PasswordReset {
+    /// Creates a reset record, only the digest of `token` is kept.
+    pub(crate) fn new(token: &str, user_ext_id: Uuid, expires_at: NaiveDateTime) -> Self {
+        Self {
+            id: None,
+            token_hash: token_digest(token),
+            user_ext_id,
+            expires_at,
+            created_at: None,
+        }
+    }
+}
+
+impl Model for PasswordReset {
+    type Id = i64;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.id
+    }
+}
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use utoipa::ToSchema;
+
+/// Something a user may do beyond managing their own account and resources.
+///
+/// The permissions are fixed by the server and seeded into the `permissions` table, users get
+/// them through the roles they are assigned.
+#[derive(
+    Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, ToSchema,
+)]
+pub(crate) enum Permission {
+    /// List and view all users.
+    #[serde(rename = "users:read")]
+    UsersRead,
+    /// Delete any user.
+    #[serde(rename = "users:delete")]
+    UsersDelete,
+    /// Unlock users that were locked out after failed sign-ins.
+    #[serde(rename = "users:unlock")]
+    UsersUnlock,
+    /// View and manage OAuth clients owned by other users.
+    #[serde(rename = "clients:manage")]
+    ClientsManage,
+    /// List roles and their permissions.
+    #[serde(rename = "roles:read")]
+    RolesRead,
+    /// Assign roles to users and remove them.
+    #[serde(rename = "roles:assign")]
+    RolesAssign,
+    /// Query and export the audit log.
+    #[serde(rename = "audit:read")]
+    AuditRead,
+}
+
+impl Permission {
+    pub(crate) const ALL: [Permission; 7] = [
+        Self::UsersRead,
+        Self::UsersDelete,
+        Self::UsersUnlock,
+        Self::ClientsManage,
+        Self::RolesRead,
+        Self::RolesAssign,
+        Self::AuditRead,
+    ];
+
+    /// The name of the permission in the database.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::UsersRead => "users:read",
+            Self::UsersDelete => "users:delete",
+            Self::UsersUnlock => "users:unlock",
+            Self::ClientsManage => "clients:manage",
+            Self::RolesRead => "roles:read",
+            Self::RolesAssign => "roles:assign",
+            Self::AuditRead => "audit:read",
+        }
+    }
+
+    /// Gets a permission by its name in the database.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|permission| permission.as_str() == name)
+    }
+
+    /// The scope a client needs for the permission to apply to tokens it got for a user.
+    pub(crate) fn scope(&self) -> &'static str {
+        match self {
+            Self::UsersRead | Self::RolesRead | Self::AuditRead => "read",
+            Self::UsersDelete | Self::UsersUnlock | Self::ClientsManage | Self::RolesAssign => {
+                "write"
+            }
+        }
+    }
+}
+
+impl Display for Permission {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The permissions a request is made with.
+#[derive(Default, Debug, Clone, Hash, Eq, PartialEq)]
+pub(crate) struct Permissions(BTreeSet<Permission>);
+
+impl Permissions {
+    pub(crate) const NONE: Self = Self(BTreeSet::new());
+
+    pub(crate) fn contains(&self, permission: Permission) -> bool {
+        self.0.contains(&permission)
+    }
+
+    /// Only keeps the permissions `scopes` cover, for tokens a client got for a user.
+    pub(crate) fn restrict_to_scopes<S: AsRef<str>>(self, scopes: &[S]) -> Self {
+        self.0
+            .into_iter()
+            .filter(|permission| {
+                scopes
+                    .iter()
+                    .any(|scope| scope.as_ref() == permission.scope())
+            })
+            .collect()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Permission> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl FromIterator<Permission> for Permissions {
+    fn from_iter<T: IntoIterator<Item = Permission>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
@@ -0,0 +1,25 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx_utils::traits::Model;
+
+/// A named set of permissions that can be assigned to users.
+#[derive(
+    Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, FromRow, Serialize, Deserialize,
+)]
+pub(crate) struct Role {
+    pub(crate) id: Option<i64>,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    /// Names of the permissions the role grants.
+    pub(crate) permissions: Vec<String>,
+    pub(crate) created_at: Option<NaiveDateTime>,
+}
+
+impl Model for Role {
+    type Id = i64;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.id
+    }
+}
@@ -0,0 +1,61 @@
+use crate::utils::hashing::token_digest;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx_utils::traits::Model;
+use uuid::Uuid;
+
+/// A browser session of a user, authenticated by the session cookie.
+///
+/// Sessions end when they have not been used for the idle timeout or at `expires_at`, whichever
+/// comes first.
+#[derive(
+    Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, FromRow, Serialize, Deserialize,
+)]
+pub(crate) struct Session {
+    pub(crate) id: Option<i64>,
+    /// SHA-256 digest of the session token, the token itself is never stored.
+    pub(crate) session_hash: String,
+    /// SHA-256 digest of the CSRF token of the session.
+    pub(crate) csrf_hash: String,
+    pub(crate) user_ext_id: Uuid,
+    pub(crate) user_agent: Option<String>,
+    pub(crate) expires_at: NaiveDateTime,
+    pub(crate) last_seen_at: Option<NaiveDateTime>,
+    pub(crate) created_at: Option<NaiveDateTime>,
+}
+
+impl Session {
+    /// Creates a session record, only the digests of `token` and `csrf_token` are kept.
+    pub(crate) fn new(
+        token: &str,
+        csrf_token: &str,
+        user_ext_id: Uuid,
+        user_agent: Option<String>,
+        expires_at: NaiveDateTime,
+    ) -> Self {
+        Self {
+            id: None,
+            session_hash: token_digest(token),
+            csrf_hash: token_digest(csrf_token),
+            user_ext_id,
+            user_agent,
+            expires_at,
+            last_seen_at: None,
+            created_at: None,
+        }
+    }
+
+    /// Whether `csrf_token` is the CSRF token of the session.
+    pub(crate) fn verify_csrf(&self, csrf_token: impl AsRef<[u8]>) -> bool {
+        token_digest(csrf_token) == self.csrf_hash
+    }
+}
+
+impl Model for Session {
+    type Id = i64;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.id
+    }
+}
@@ -1,7 +1,7 @@
+use crate::repositories::keyset::{Cursor, Keyset};
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use sqlx_utils::sql_filter;
 use sqlx_utils::traits::Model;
 use uuid::Uuid;
 
@@ -14,6 +14,8 @@ pub(crate) struct User {
     pub(crate) username: String,
     pub(crate) password_hash: String,
     pub(crate) email: String,
+    /// When the user proved they own `email`, `None` until they do.
+    pub(crate) email_verified_at: Option<NaiveDateTime>,
     pub(crate) created_at: Option<NaiveDateTime>,
     pub(crate) updated_at: Option<NaiveDateTime>,
 }
@@ -26,18 +28,8 @@ impl Model for User {
     }
 }
 
-sql_filter! {
-    #[derive(Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-    pub struct UserFilter {
-        SELECT * FROM users
-        WHERE
-            ?id = i64
-            AND ext_id = Uuid
-            AND ?username = String
-            OR ?email = String
-            AND ?created_at as created_before < NaiveDateTime
-            AND ?created_at as created_after > NaiveDateTime
-            AND ?updated_at as updated_before < NaiveDateTime
-            AND ?updated_at as updated_after > NaiveDateTime
+impl Keyset for User {
+    fn cursor(&self) -> Option<Cursor> {
+        Some(Cursor::created_at(self.created_at?, self.id?))
     }
 }
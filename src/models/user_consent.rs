@@ -0,0 +1,37 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx_utils::traits::Model;
+use uuid::Uuid;
+
+/// The scopes a user has approved for a client.
+#[derive(
+    Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, FromRow, Serialize, Deserialize,
+)]
+pub(crate) struct UserConsent {
+    pub(crate) id: Option<i64>,
+    pub(crate) user_ext_id: Uuid,
+    pub(crate) client_id: String,
+    pub(crate) scopes: Vec<String>,
+    pub(crate) created_at: Option<NaiveDateTime>,
+    pub(crate) updated_at: Option<NaiveDateTime>,
+}
+
+impl UserConsent {
+    /// Whether the user has approved all of `scopes`.
+    pub(crate) fn covers<S: AsRef<str>>(&self, scopes: &[S]) -> bool {
+        scopes.iter().all(|scope| {
+            self.scopes
+                .iter()
+                .any(|approved| approved == scope.as_ref())
+        })
+    }
+}
+
+impl Model for UserConsent {
+    type Id = i64;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.id
+    }
+}
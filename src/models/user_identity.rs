@@ -0,0 +1,29 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx_utils::traits::Model;
+use uuid::Uuid;
+
+/// Links a user to their identity at an upstream OpenID Connect provider.
+#[derive(
+    Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, FromRow, Serialize, Deserialize,
+)]
+pub(crate) struct UserIdentity {
+    pub(crate) id: Option<i64>,
+    pub(crate) user_ext_id: Uuid,
+    /// Id of the provider in the server configuration.
+    pub(crate) provider: String,
+    /// The `sub` claim, or the claim configured for the provider.
+    pub(crate) subject: String,
+    pub(crate) email: Option<String>,
+    pub(crate) created_at: Option<NaiveDateTime>,
+    pub(crate) last_login_at: Option<NaiveDateTime>,
+}
+
+impl Model for UserIdentity {
+    type Id = i64;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.id
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use sqlx_utils::traits::Model;
+use uuid::Uuid;
+
+/// A TOTP authenticator of a user.
+///
+/// It has to be confirmed with a code before it is required at sign in.
+#[derive(
+    Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, FromRow, Serialize, Deserialize,
+)]
+pub(crate) struct UserTotp {
+    pub(crate) id: Option<i64>,
+    pub(crate) user_ext_id: Uuid,
+    /// The shared secret, encrypted with [`encrypt`](crate::utils::encryption::encrypt).
+    #[serde(skip)]
+    pub(crate) secret_ciphertext: Vec<u8>,
+    pub(crate) confirmed_at: Option<NaiveDateTime>,
+    /// Last time step a code was accepted for.
+    pub(crate) last_used_step: Option<i64>,
+    pub(crate) created_at: Option<NaiveDateTime>,
+}
+
+impl UserTotp {
+    pub(crate) fn new(user_ext_id: Uuid, secret_ciphertext: Vec<u8>) -> Self {
+        Self {
+            id: None,
+            user_ext_id,
+            secret_ciphertext,
+            confirmed_at: None,
+            last_used_step: None,
+            created_at: None,
+        }
+    }
+
+    /// Whether the authenticator has been confirmed and is required at sign in.
+    pub(crate) fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+}
+
+impl Model for UserTotp {
+    type Id = i64;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.id
+    }
+}
@@ -2,6 +2,9 @@ use crate::dto::Error;
 use crate::endpoints::{__path_health, api::v1::V1API};
 use crate::repositories::DatabaseHealth;
 use crate::services::health::{ProcessStats, ServerHealth};
+use crate::services::maintenance::{JobRun, JobStatus};
+use crate::services::oauth::{OAUTH_BASE_PATH, build_oauth_handler};
+use actix_oauth::traits::OAuth2Manager;
 use std::collections::BTreeMap;
 use utoipa::openapi::OpenApi as OpenApiSpec;
 use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
@@ -56,6 +59,8 @@ impl Modify for NormalizePath {
     }
 }
 
+/// Adds the security schemes the API supports, the `oauth2` scheme is generated from the
+/// configured OAuth handler so it always matches the grants the server actually serves.
 pub struct OpenApiSecurityConfig;
 
 impl Modify for OpenApiSecurityConfig {
@@ -79,6 +84,10 @@ impl Modify for OpenApiSecurityConfig {
                     .build(),
             );
         }
+
+        build_oauth_handler()
+            .security_scheme(OAUTH_BASE_PATH)
+            .modify(openapi);
     }
 }
 
@@ -91,7 +100,7 @@ impl Modify for OpenApiSecurityConfig {
     nest(
         (path = "/", api = V1API),
     ),
-    components(schemas(Error, ServerHealth, DatabaseHealth, ProcessStats), responses(Error, ServerHealth, DatabaseHealth, ProcessStats)),
+    components(schemas(Error, ServerHealth, DatabaseHealth, ProcessStats, JobStatus, JobRun), responses(Error, ServerHealth, DatabaseHealth, ProcessStats, JobStatus, JobRun)),
     tags(),
     modifiers(&NormalizePath, &OpenApiSecurityConfig)
 )]
@@ -0,0 +1,238 @@
+use crate::ApiResult;
+use crate::dto::Page;
+use crate::models::audit_event::{AuditEvent, AuditOutcome, GENESIS_HASH};
+use crate::repositories::SortDirection;
+use crate::repositories::keyset::PageParams;
+use chrono::{NaiveDateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, QueryBuilder, query, query_as, query_scalar};
+use sqlx_utils::repository;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+/// Number of events checked at a time by [`AuditEventsRepository::verify_chain`].
+const VERIFY_BATCH_SIZE: i64 = 1000;
+
+/// Key of the transaction level advisory lock held by [`AuditEventsRepository::append`], the
+/// bytes spell `audit_ev`.
+const APPEND_LOCK_KEY: i64 = 0x6175_6469_745f_6576;
+
+// Not a `Repository<AuditEvent>`, events can only be added through `append` as they have to be
+// chained to the event before them.
+repository! {
+    pub AuditEventsRepository;
+}
+
+/// Filters and ordering for searching the audit log, read from the query of `GET /audit/events`.
+///
+/// Events are ordered by when they happened, paging goes through [`PageParams`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditSearchParams {
+    /// Only events with this action, or with actions in this group when it ends in `.`, such
+    /// as `user.`.
+    pub action: Option<String>,
+    /// Only events with this outcome.
+    pub outcome: Option<AuditOutcome>,
+    /// Only events done by this user.
+    pub actor_user: Option<Uuid>,
+    /// Only events done by this client.
+    pub actor_client: Option<String>,
+    /// Only events done to this kind of thing, such as `user` or `client`.
+    pub target_type: Option<String>,
+    /// Only events done to the thing with this id.
+    pub target_id: Option<String>,
+    /// Only events from this address.
+    pub ip: Option<String>,
+    /// Only events after this time.
+    pub created_after: Option<NaiveDateTime>,
+    /// Only events before this time.
+    pub created_before: Option<NaiveDateTime>,
+    /// Direction to sort in, newest first by default.
+    pub order: Option<SortDirection>,
+}
+
+impl AuditSearchParams {
+    /// Adds the filters as a `WHERE` clause to `query`.
+    fn push_filters<'args>(&'args self, query: &mut QueryBuilder<'args, Postgres>) {
+        query.push(" WHERE TRUE");
+
+        if let Some(action) = &self.action {
+            if action.ends_with('.') {
+                query
+                    .push(" AND starts_with(action, ")
+                    .push_bind(action)
+                    .push(")");
+            } else {
+                query.push(" AND action = ").push_bind(action);
+            }
+        }
+        if let Some(outcome) = self.outcome {
+            query.push(" AND outcome = ").push_bind(outcome.as_str());
+        }
+        if let Some(actor) = self.actor_user {
+            query.push(" AND actor_user_ext_id = ").push_bind(actor);
+        }
+        if let Some(actor) = &self.actor_client {
+            query.push(" AND actor_client_id = ").push_bind(actor);
+        }
+        if let Some(target_type) = &self.target_type {
+            query.push(" AND target_type = ").push_bind(target_type);
+        }
+        if let Some(target_id) = &self.target_id {
+            query.push(" AND target_id = ").push_bind(target_id);
+        }
+        if let Some(ip) = &self.ip {
+            query.push(" AND ip = ").push_bind(ip);
+        }
+        if let Some(after) = self.created_after {
+            query.push(" AND created_at > ").push_bind(after);
+        }
+        if let Some(before) = self.created_before {
+            query.push(" AND created_at < ").push_bind(before);
+        }
+    }
+}
+
+/// Result of checking the hash chain of the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ChainStatus {
+    /// Number of events checked.
+    pub(crate) checked: i64,
+    /// The first event that was changed or does not follow the event before it, `None` if the
+    /// chain is intact.
+    pub(crate) first_invalid_id: Option<i64>,
+}
+
+impl AuditEventsRepository {
+    /// Appends `event` to the audit log and returns it as stored.
+    ///
+    /// Sets the time of the event and chains it to the last event. Appends are serialized with
+    /// an advisory lock held until the transaction ends, so every event is chained to the one
+    /// committed before it. Reading the log does not take the lock.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn append(&self, event: AuditEvent) -> ApiResult<AuditEvent> {
+        let mut tx = self.pool.begin().await?;
+
+        // `pg_advisory_xact_lock` returns `void`, which `query!` cannot describe.
+        query("SELECT pg_advisory_xact_lock($1)")
+            .bind(APPEND_LOCK_KEY)
+            .execute(&mut *tx)
+            .await?;
+
+        let prev_hash = query_scalar!("SELECT hash FROM audit_event ORDER BY id DESC LIMIT 1")
+            .fetch_optional(&mut *tx)
+            .await?
+            .unwrap_or_else(|| GENESIS_HASH.to_string());
+
+        // Postgres keeps microseconds, the hash has to match what is read back.
+        let mut event = AuditEvent {
+            id: None,
+            created_at: Utc::now().naive_utc().trunc_subsecs(6),
+            ..event
+        };
+        event.hash = event.chain_hash(&prev_hash);
+        event.prev_hash = prev_hash;
+
+        let id = query_scalar!(
+            "INSERT INTO audit_event (
+                action, outcome, actor_user_ext_id, actor_client_id, target_type, target_id,
+                ip, user_agent, details, created_at, prev_hash, hash
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+             RETURNING id",
+            event.action,
+            event.outcome,
+            event.actor_user_ext_id,
+            event.actor_client_id,
+            event.target_type,
+            event.target_id,
+            event.ip,
+            event.user_agent,
+            event.details,
+            event.created_at,
+            event.prev_hash,
+            event.hash,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(AuditEvent {
+            id: Some(id),
+            ..event
+        })
+    }
+
+    /// Gets the page `page` of the events matching `params`.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn search(
+        &self,
+        params: &AuditSearchParams,
+        page: &PageParams,
+    ) -> ApiResult<Page<AuditEvent>> {
+        let mut query = QueryBuilder::new(
+            "SELECT id, action, outcome, actor_user_ext_id, actor_client_id, target_type, target_id,
+                    ip, user_agent, details, created_at, prev_hash, hash
+             FROM audit_event",
+        );
+
+        params.push_filters(&mut query);
+        page.push_keyset(&mut query, params.order.unwrap_or_default())?;
+
+        let events: Vec<AuditEvent> = query.build_query_as().fetch_all(self.pool).await?;
+
+        Ok(page.into_page(events))
+    }
+
+    /// Checks that no event was changed and that every event follows the one before it.
+    ///
+    /// Removing events from the end of the log cannot be detected from the chain alone, compare
+    /// the number of events and the last hash with an earlier check for that.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn verify_chain(&self) -> ApiResult<ChainStatus> {
+        let mut checked = 0;
+        let mut last_id = 0;
+        let mut prev_hash = GENESIS_HASH.to_string();
+
+        loop {
+            let events = query_as!(
+                AuditEvent,
+                "SELECT id, action, outcome, actor_user_ext_id, actor_client_id, target_type,
+                        target_id, ip, user_agent, details, created_at, prev_hash, hash
+                 FROM audit_event
+                 WHERE id > $1
+                 ORDER BY id
+                 LIMIT $2",
+                last_id,
+                VERIFY_BATCH_SIZE
+            )
+            .fetch_all(self.pool)
+            .await?;
+
+            let Some(last) = events.last() else {
+                break;
+            };
+            last_id = last.id.unwrap_or_default();
+
+            for event in events {
+                checked += 1;
+
+                if !event.is_intact(&prev_hash) {
+                    return Ok(ChainStatus {
+                        checked,
+                        first_invalid_id: event.id,
+                    });
+                }
+
+                prev_hash = event.hash;
+            }
+        }
+
+        Ok(ChainStatus {
+            checked,
+            first_invalid_id: None,
+        })
+    }
+}
@@ -0,0 +1,391 @@
+//! Keyset paging for lists that are ordered by a [`SortColumn`] and `id`.
+//!
+//! Instead of skipping rows with `OFFSET`, which gets slower the further into the list a page
+//! is, a page starts right after the row the previous page ended on. The position of that row is
+//! handed to clients as an opaque [`Cursor`].
+
+use crate::ApiResult;
+use crate::dto::Page;
+use crate::error::ApiError;
+use crate::repositories::SortDirection;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Postgres, QueryBuilder};
+use sqlx_utils::traits::{Model, Repository};
+use utoipa::IntoParams;
+
+/// Number of items on a page when no `limit` is given.
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+/// Most items on a single page.
+pub const MAX_PAGE_LIMIT: i64 = 100;
+
+/// Column a list is sorted by, rows with the same value are ordered by `id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortColumn {
+    name: &'static str,
+    text: bool,
+}
+
+impl SortColumn {
+    /// The `created_at` column, which lists are sorted by unless they say otherwise.
+    pub const CREATED_AT: Self = Self::time("created_at");
+
+    /// A timestamp column.
+    pub const fn time(name: &'static str) -> Self {
+        Self { name, text: false }
+    }
+
+    /// A text column.
+    pub const fn text(name: &'static str) -> Self {
+        Self { name, text: true }
+    }
+
+    /// Whether `key` is a value of this column.
+    fn holds(&self, key: &SortKey) -> bool {
+        matches!(key, SortKey::Text(_)) == self.text
+    }
+}
+
+/// Value of the [`SortColumn`] at the row a cursor points to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SortKey {
+    Time(NaiveDateTime),
+    Text(String),
+}
+
+/// Position of a row in a list ordered by a [`SortColumn`] and `id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    pub key: SortKey,
+    pub id: i64,
+}
+
+impl Cursor {
+    /// Position of a row in a list ordered by `created_at`.
+    pub fn created_at(created_at: NaiveDateTime, id: i64) -> Self {
+        Self {
+            key: SortKey::Time(created_at),
+            id,
+        }
+    }
+
+    /// Encodes the cursor as the opaque string clients get.
+    pub fn encode(&self) -> String {
+        let key = match &self.key {
+            SortKey::Time(time) => format!("t{}", time.and_utc().timestamp_micros()),
+            SortKey::Text(text) => format!("s{text}"),
+        };
+
+        URL_SAFE_NO_PAD.encode(format!("{key}:{}", self.id))
+    }
+
+    /// Decodes a cursor from [`Cursor::encode`], `None` if it is not one.
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let decoded = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+        // Text keys can contain `:` themselves, the id never does.
+        let (key, id) = decoded.rsplit_once(':')?;
+
+        let key = match key.split_at_checked(1)? {
+            ("t", time) => {
+                SortKey::Time(DateTime::from_timestamp_micros(time.parse().ok()?)?.naive_utc())
+            }
+            ("s", text) => SortKey::Text(text.to_string()),
+            _ => return None,
+        };
+
+        Some(Self {
+            key,
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+/// Models that can be listed with keyset paging.
+pub trait Keyset {
+    /// Position of the model in its list ordered by `created_at`, `None` if it was not read from
+    /// the database.
+    fn cursor(&self) -> Option<Cursor>;
+}
+
+/// Paging of a list, read from the query of list endpoints.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageParams {
+    /// Most items to return, at most 100 and 50 by default.
+    pub limit: Option<i64>,
+    /// Cursor from `next` of the previous page, returns the items after it.
+    pub after: Option<String>,
+    /// Cursor from `prev` of the following page, returns the items before it.
+    pub before: Option<String>,
+}
+
+impl PageParams {
+    /// The limit clamped to what a single page holds.
+    pub fn limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_LIMIT)
+            .clamp(1, MAX_PAGE_LIMIT)
+    }
+
+    /// The cursor the page starts at and whether the page goes backwards from it.
+    fn cursor(&self) -> ApiResult<Option<(Cursor, bool)>> {
+        let (cursor, backwards) = match (&self.after, &self.before) {
+            (None, None) => return Ok(None),
+            (Some(after), None) => (after, false),
+            (None, Some(before)) => (before, true),
+            (Some(_), Some(_)) => {
+                return Err(ApiError::BadRequest(
+                    "Only one of `after` and `before` can be given".into(),
+                ));
+            }
+        };
+
+        let cursor =
+            Cursor::decode(cursor).ok_or_else(|| ApiError::BadRequest("Invalid cursor".into()))?;
+
+        Ok(Some((cursor, backwards)))
+    }
+
+    /// Adds the position of the page, its ordering by `created_at` and its limit to `query`.
+    ///
+    /// `query` has to end in a `WHERE` clause of a table with `created_at` and `id` columns.
+    /// One row more than the limit is fetched to know whether there are more pages.
+    pub(crate) fn push_keyset(
+        &self,
+        query: &mut QueryBuilder<'_, Postgres>,
+        order: SortDirection,
+    ) -> ApiResult<()> {
+        self.push_keyset_by(query, SortColumn::CREATED_AT, order)
+    }
+
+    /// Like [`PageParams::push_keyset`], for lists ordered by `column`.
+    pub(crate) fn push_keyset_by(
+        &self,
+        query: &mut QueryBuilder<'_, Postgres>,
+        column: SortColumn,
+        order: SortDirection,
+    ) -> ApiResult<()> {
+        let cursor = self.cursor()?;
+        let backwards = self.before.is_some();
+
+        // Pages before the cursor are fetched in reverse and turned around in `into_page`.
+        let order = if backwards { order.reverse() } else { order };
+
+        if let Some((cursor, _)) = cursor {
+            // A cursor of a list sorted by another column.
+            if !column.holds(&cursor.key) {
+                return Err(ApiError::BadRequest("Invalid cursor".into()));
+            }
+
+            let comparison = match order {
+                SortDirection::Asc => ">",
+                SortDirection::Desc => "<",
+            };
+
+            query.push(format!(" AND ({}, id) {comparison} (", column.name));
+
+            match cursor.key {
+                SortKey::Time(time) => query.push_bind(time),
+                SortKey::Text(text) => query.push_bind(text),
+            };
+
+            query.push(", ").push_bind(cursor.id).push(")");
+        }
+
+        query
+            .push(format!(
+                " ORDER BY {0} {1}, id {1} LIMIT ",
+                column.name,
+                order.as_sql()
+            ))
+            .push_bind(self.limit() + 1);
+
+        Ok(())
+    }
+
+    /// Turns the rows of a query built with [`PageParams::push_keyset`] into the page.
+    pub(crate) fn into_page<M: Keyset>(&self, rows: Vec<M>) -> Page<M> {
+        self.into_page_by(rows, M::cursor)
+    }
+
+    /// Like [`PageParams::into_page`], for lists ordered by another column. `cursor` gives the
+    /// position of a row in the list.
+    pub(crate) fn into_page_by<M>(
+        &self,
+        mut rows: Vec<M>,
+        cursor: impl Fn(&M) -> Option<Cursor>,
+    ) -> Page<M> {
+        let backwards = self.before.is_some();
+        let has_more = rows.len() as i64 > self.limit();
+
+        rows.truncate(self.limit() as usize);
+
+        if backwards {
+            rows.reverse();
+        }
+
+        let first = rows.first().and_then(&cursor).map(|cursor| cursor.encode());
+        let last = rows.last().and_then(&cursor).map(|cursor| cursor.encode());
+
+        // Going forwards there is a previous page if we started after something, and the other
+        // way around going backwards.
+        let (next, prev) = if backwards {
+            (last, first.filter(|_| has_more))
+        } else {
+            (
+                last.filter(|_| has_more),
+                first.filter(|_| self.after.is_some()),
+            )
+        };
+
+        Page {
+            items: rows,
+            total: None,
+            next,
+            prev,
+        }
+    }
+}
+
+/// Fetches pages of keyset paged lists for repositories.
+pub(crate) trait KeysetRepository<M>: Repository<M>
+where
+    M: Model + Keyset + for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    /// Runs `query` for the page `page` of the list ordered by `order`.
+    ///
+    /// `query` selects the rows and has to end in a `WHERE` clause, see
+    /// [`PageParams::push_keyset`].
+    async fn fetch_page(
+        &self,
+        mut query: QueryBuilder<'_, Postgres>,
+        page: &PageParams,
+        order: SortDirection,
+    ) -> ApiResult<Page<M>> {
+        page.push_keyset(&mut query, order)?;
+
+        let rows = query.build_query_as().fetch_all(self.pool()).await?;
+
+        Ok(page.into_page(rows))
+    }
+}
+
+impl<M, R> KeysetRepository<M> for R
+where
+    R: Repository<M>,
+    M: Model + Keyset + for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = Cursor::created_at(
+            DateTime::from_timestamp_micros(1_700_000_000_123_456)
+                .unwrap()
+                .naive_utc(),
+            42,
+        );
+
+        assert_eq!(Cursor::decode(&cursor.encode()), Some(cursor));
+    }
+
+    #[test]
+    fn text_cursor_round_trips() {
+        let cursor = Cursor {
+            key: SortKey::Text("user:name".to_string()),
+            id: 7,
+        };
+
+        assert_eq!(Cursor::decode(&cursor.encode()), Some(cursor));
+    }
+
+    #[test]
+    fn cursor_rejects_garbage() {
+        for garbage in [
+            String::new(),
+            "not a cursor!".to_string(),
+            URL_SAFE_NO_PAD.encode("t1700000000"),
+            URL_SAFE_NO_PAD.encode("t1700000000:abc"),
+            URL_SAFE_NO_PAD.encode("tabc:42"),
+            URL_SAFE_NO_PAD.encode("x1700000000:42"),
+            URL_SAFE_NO_PAD.encode(":42"),
+            URL_SAFE_NO_PAD.encode([0xff, 0xfe, b':', b'1']),
+        ] {
+            assert_eq!(Cursor::decode(&garbage), None, "{garbage:?}");
+        }
+    }
+
+    #[test]
+    fn page_params_reject_both_directions() {
+        let cursor = Cursor::created_at(NaiveDateTime::default(), 1).encode();
+        let params = PageParams {
+            after: Some(cursor.clone()),
+            before: Some(cursor),
+            ..Default::default()
+        };
+
+        assert!(params.cursor().is_err());
+    }
+
+    #[test]
+    fn keyset_orders_by_the_column() {
+        let params = PageParams {
+            after: Some(
+                Cursor {
+                    key: SortKey::Text("alice".to_string()),
+                    id: 3,
+                }
+                .encode(),
+            ),
+            ..Default::default()
+        };
+        let mut query = QueryBuilder::new("SELECT * FROM users WHERE TRUE");
+
+        params
+            .push_keyset_by(&mut query, SortColumn::text("username"), SortDirection::Asc)
+            .unwrap();
+
+        assert_eq!(
+            query.sql(),
+            "SELECT * FROM users WHERE TRUE AND (username, id) > ($1, $2) \
+             ORDER BY username ASC, id ASC LIMIT $3"
+        );
+    }
+
+    #[test]
+    fn keyset_rejects_cursors_of_other_columns() {
+        let params = PageParams {
+            before: Some(Cursor::created_at(NaiveDateTime::default(), 1).encode()),
+            ..Default::default()
+        };
+        let mut query = QueryBuilder::new("SELECT * FROM users WHERE TRUE");
+
+        assert!(
+            params
+                .push_keyset_by(&mut query, SortColumn::text("email"), SortDirection::Desc)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn page_params_clamp_the_limit() {
+        let limit = |limit| {
+            PageParams {
+                limit,
+                ..Default::default()
+            }
+            .limit()
+        };
+
+        assert_eq!(limit(None), DEFAULT_PAGE_LIMIT);
+        assert_eq!(limit(Some(0)), 1);
+        assert_eq!(limit(Some(1_000)), MAX_PAGE_LIMIT);
+    }
+}
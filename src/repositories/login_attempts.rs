@@ -0,0 +1,152 @@
+use crate::ApiResult;
+use crate::models::login_attempt::LoginAttempt;
+use chrono::NaiveDateTime;
+use sqlx::{query, query_as, query_scalar};
+use sqlx_utils::repository;
+use sqlx_utils::traits::Repository;
+
+repository! {
+    pub LoginAttemptsRepository<LoginAttempt>;
+
+    insert_one(model) {
+        query!(
+            "INSERT INTO login_attempts (kind, key, failures, locked_until)
+             VALUES ($1, $2, $3, $4)",
+            model.kind,
+            model.key,
+            model.failures,
+            model.locked_until,
+        )
+    };
+
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn get_by_id(&self, id: impl Into<i64>) -> sqlx_utils::Result<Option<LoginAttempt>> {
+        let id = id.into();
+
+        Ok(
+            query_as!(
+                LoginAttempt,
+                "SELECT id, kind, key, failures, last_failure_at, locked_until
+                 FROM login_attempts WHERE id = $1",
+                id
+            )
+            .fetch_optional(self.pool)
+            .await?
+        )
+    }
+}
+
+impl LoginAttemptsRepository {
+    /// Gets the failures recorded for `key`.
+    ///
+    /// Failures older than `window` seconds no longer count and are left out, unless the key is
+    /// still locked. `locked_until` is only set while the lock lasts.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get(
+        &self,
+        kind: &str,
+        key: &str,
+        window: f64,
+    ) -> ApiResult<Option<LoginAttempt>> {
+        Ok(query_as!(
+            LoginAttempt,
+            "SELECT id, kind, key, failures, last_failure_at,
+                    CASE WHEN locked_until > CURRENT_TIMESTAMP THEN locked_until END as locked_until
+             FROM login_attempts
+             WHERE kind = $1 AND key = $2
+               AND (last_failure_at > CURRENT_TIMESTAMP - make_interval(secs => $3)
+                    OR locked_until > CURRENT_TIMESTAMP)",
+            kind,
+            key,
+            window
+        )
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    /// Counts a failure for `key` and returns the updated record.
+    ///
+    /// The counter starts over when the last failure is older than `window` seconds.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn record_failure(
+        &self,
+        kind: &str,
+        key: &str,
+        window: f64,
+    ) -> ApiResult<LoginAttempt> {
+        Ok(query_as!(
+            LoginAttempt,
+            "INSERT INTO login_attempts (kind, key, failures)
+             VALUES ($1, $2, 1)
+             ON CONFLICT (kind, key) DO UPDATE
+             SET failures = CASE
+                     WHEN login_attempts.last_failure_at <= CURRENT_TIMESTAMP - make_interval(secs => $3)
+                     THEN 1
+                     ELSE login_attempts.failures + 1
+                 END,
+                 last_failure_at = CURRENT_TIMESTAMP
+             RETURNING id, kind, key, failures, last_failure_at, locked_until",
+            kind,
+            key,
+            window
+        )
+        .fetch_one(self.pool)
+        .await?)
+    }
+
+    /// Locks `key` for `duration` seconds and resets its counter.
+    ///
+    /// Returns when the lock ends, or `None` if the key was already locked so callers only
+    /// react to a lockout once.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn lock(
+        &self,
+        kind: &str,
+        key: &str,
+        duration: f64,
+    ) -> ApiResult<Option<NaiveDateTime>> {
+        Ok(query_scalar!(
+            r#"UPDATE login_attempts
+             SET locked_until = CURRENT_TIMESTAMP + make_interval(secs => $3), failures = 0
+             WHERE kind = $1 AND key = $2
+               AND (locked_until IS NULL OR locked_until <= CURRENT_TIMESTAMP)
+             RETURNING locked_until as "locked_until!""#,
+            kind,
+            key,
+            duration
+        )
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    /// Forgets the failures and lock of each of `keys` of the kind `kind`.
+    ///
+    /// Returns how many keys had failures or a lock.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn clear(&self, kind: &str, keys: &[String]) -> ApiResult<u64> {
+        let result = query!(
+            "DELETE FROM login_attempts WHERE kind = $1 AND key = ANY($2)",
+            kind,
+            keys
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Deletes records that no longer count for anything and returns how many were deleted.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn purge_stale(&self, window: f64) -> ApiResult<u64> {
+        let result = query!(
+            "DELETE FROM login_attempts
+             WHERE last_failure_at <= CURRENT_TIMESTAMP - make_interval(secs => $1)
+               AND (locked_until IS NULL OR locked_until <= CURRENT_TIMESTAMP)",
+            window
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
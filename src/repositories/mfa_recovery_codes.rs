@@ -0,0 +1,91 @@
+use crate::ApiResult;
+use crate::models::mfa_recovery_code::MfaRecoveryCode;
+use crate::utils::hashing::token_digest;
+use sqlx::{query, query_as};
+use sqlx_utils::repository;
+use sqlx_utils::traits::Repository;
+use uuid::Uuid;
+
+repository! {
+    pub MfaRecoveryCodesRepository<MfaRecoveryCode>;
+
+    insert_one(model) {
+        query!(
+            "INSERT INTO mfa_recovery_code (user_ext_id, code_hash)
+             VALUES ($1, $2)",
+            model.user_ext_id,
+            model.code_hash,
+        )
+    };
+
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn get_by_id(&self, id: impl Into<i64>) -> sqlx_utils::Result<Option<MfaRecoveryCode>> {
+        let id = id.into();
+
+        Ok(
+            query_as!(
+                MfaRecoveryCode,
+                "SELECT id, user_ext_id, code_hash, created_at
+                 FROM mfa_recovery_code WHERE id = $1",
+                id
+            )
+            .fetch_optional(self.pool)
+            .await?
+        )
+    }
+}
+
+impl MfaRecoveryCodesRepository {
+    /// Replaces all recovery codes of `user` with `codes`.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn replace(&self, user: Uuid, codes: &[MfaRecoveryCode]) -> ApiResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        query!("DELETE FROM mfa_recovery_code WHERE user_ext_id = $1", user)
+            .execute(&mut *tx)
+            .await?;
+
+        for code in codes {
+            query!(
+                "INSERT INTO mfa_recovery_code (user_ext_id, code_hash)
+                 VALUES ($1, $2)",
+                user,
+                code.code_hash
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Uses up the recovery code `code` of `user`.
+    ///
+    /// Returns `false` if the user has no such code, or it was already used.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn take(&self, user: Uuid, code: impl AsRef<[u8]>) -> ApiResult<bool> {
+        let code_hash = token_digest(code);
+
+        let result = query!(
+            "DELETE FROM mfa_recovery_code WHERE user_ext_id = $1 AND code_hash = $2",
+            user,
+            code_hash
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Deletes every recovery code of `user` and returns how many were deleted.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn delete_by_user(&self, user: Uuid) -> ApiResult<u64> {
+        let result = query!("DELETE FROM mfa_recovery_code WHERE user_ext_id = $1", user)
+            .execute(self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -5,10 +5,56 @@ use serde::{Deserialize, Serialize};
 use sqlx_utils::pool::get_db_pool;
 use utoipa::{ToResponse, ToSchema};
 
+pub mod audit_events;
+pub mod keyset;
+pub mod login_attempts;
+pub mod mfa_recovery_codes;
+pub mod oauth_auth_code;
+pub mod oauth_client_secrets;
 pub mod oauth_clients;
 pub mod oauth_token;
+pub mod oidc_login_states;
+pub mod password_resets;
+pub mod roles;
+pub mod sessions;
+pub mod user_consent;
+pub mod user_identities;
+pub mod user_totp;
 pub mod users;
 
+/// Direction results are sorted in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortDirection {
+    /// The direction in the query string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Asc => "asc",
+            Self::Desc => "desc",
+        }
+    }
+
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Self::Asc => Self::Desc,
+            Self::Desc => Self::Asc,
+        }
+    }
+}
+
 /// Health information and stats about the database the server is connected to.
 #[derive(Debug, Serialize, Deserialize, ToSchema, ToResponse)]
 pub struct DatabaseHealth {
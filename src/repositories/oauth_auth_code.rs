@@ -0,0 +1,96 @@
+use crate::ApiResult;
+use crate::models::oauth_auth_code::OAuthAuthCode;
+use crate::utils::hashing::token_digest;
+use sqlx::{query, query_as};
+use sqlx_utils::repository;
+use sqlx_utils::traits::Repository;
+
+repository! {
+    pub OauthAuthCodeRepository<OAuthAuthCode>;
+
+    insert_one(model) {
+        query!(
+            "INSERT INTO oauth_auth_code (
+                code_hash,
+                client_id,
+                user_ext_id,
+                redirect_uri,
+                scopes,
+                code_challenge,
+                expires_at
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            model.code_hash,
+            model.client_id,
+            model.user_ext_id,
+            model.redirect_uri,
+            model.scopes as _,
+            model.code_challenge,
+            model.expires_at,
+        )
+    };
+
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn get_by_id(&self, id: impl Into<i64>) -> sqlx_utils::Result<Option<OAuthAuthCode>> {
+        let id = id.into();
+
+        Ok(
+            query_as!(
+                OAuthAuthCode,
+                "SELECT
+                    id,
+                    code_hash,
+                    client_id,
+                    user_ext_id,
+                    redirect_uri,
+                    scopes,
+                    code_challenge,
+                    expires_at,
+                    created_at
+                 FROM oauth_auth_code WHERE id = $1 AND expires_at > CURRENT_TIMESTAMP",
+                id
+            )
+            .fetch_optional(self.pool)
+            .await?
+        )
+    }
+}
+
+impl OauthAuthCodeRepository {
+    /// Removes and returns the authorization code with the raw value `code`.
+    ///
+    /// Each code can only be taken once, expired codes are never returned.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn take(&self, code: impl AsRef<[u8]>) -> ApiResult<Option<OAuthAuthCode>> {
+        let code_hash = token_digest(code);
+
+        Ok(query_as!(
+            OAuthAuthCode,
+            "DELETE FROM oauth_auth_code
+             WHERE code_hash = $1 AND expires_at > CURRENT_TIMESTAMP
+             RETURNING
+                id,
+                code_hash,
+                client_id,
+                user_ext_id,
+                redirect_uri,
+                scopes,
+                code_challenge,
+                expires_at,
+                created_at",
+            code_hash
+        )
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    /// Deletes expired authorization codes and returns how many were deleted.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn purge_expired(&self) -> ApiResult<u64> {
+        let result = query!("DELETE FROM oauth_auth_code WHERE expires_at <= CURRENT_TIMESTAMP")
+            .execute(self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,244 @@
+use crate::ApiResult;
+use crate::error::ApiError;
+use crate::models::oauth_client_secret::OAuthClientSecret;
+use crate::utils::hashing::{hash_secret, is_hashed, needs_rehash, verify_dummy, verify_secret};
+use actix_oauth::types::ClientSecret;
+use chrono::{Local, TimeDelta};
+use sqlx::{query, query_as};
+use sqlx_utils::repository;
+use sqlx_utils::traits::Repository;
+use tracing::info;
+
+repository! {
+    pub OauthClientSecretsRepository<OAuthClientSecret>;
+
+    insert_one(model) {
+        query!(
+            "INSERT INTO oauth_client_secret (client_id, secret_hash, expires_at)
+             VALUES ($1, $2, $3)",
+            model.client_id,
+            model.secret_hash,
+            model.expires_at,
+        )
+    };
+
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn get_by_id(&self, id: impl Into<i64>) -> sqlx_utils::Result<Option<OAuthClientSecret>> {
+        let id = id.into();
+
+        Ok(
+            query_as!(
+                OAuthClientSecret,
+                "SELECT id, client_id, secret_hash, expires_at, revoked_at, last_used_at, created_at
+                 FROM oauth_client_secret
+                 WHERE id = $1",
+                id
+            )
+            .fetch_optional(self.pool)
+            .await?
+        )
+    }
+}
+
+impl OauthClientSecretsRepository {
+    /// Gets all secrets of a client, newest first, including expired and revoked ones.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_by_client(&self, client_id: &str) -> ApiResult<Vec<OAuthClientSecret>> {
+        Ok(query_as!(
+            OAuthClientSecret,
+            "SELECT id, client_id, secret_hash, expires_at, revoked_at, last_used_at, created_at
+             FROM oauth_client_secret
+             WHERE client_id = $1
+             ORDER BY created_at DESC",
+            client_id
+        )
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// Gets the secrets a client can currently authenticate with.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_active_by_client(
+        &self,
+        client_id: &str,
+    ) -> ApiResult<Vec<OAuthClientSecret>> {
+        Ok(query_as!(
+            OAuthClientSecret,
+            "SELECT id, client_id, secret_hash, expires_at, revoked_at, last_used_at, created_at
+             FROM oauth_client_secret
+             WHERE client_id = $1
+               AND revoked_at IS NULL
+               AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)",
+            client_id
+        )
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// Issues a new secret for a client.
+    ///
+    /// Secrets that are currently valid stay valid for `grace_period`, or until their existing
+    /// expiry if that is sooner. Fails with a bad request if the grace period ends beyond the
+    /// range of representable timestamps.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn rotate(
+        &self,
+        client_id: &str,
+        grace_period: TimeDelta,
+    ) -> ApiResult<(OAuthClientSecret, ClientSecret)> {
+        let grace_until = Local::now()
+            .checked_add_signed(grace_period)
+            .ok_or_else(|| ApiError::BadRequest("Grace period is out of range".into()))?
+            .naive_utc();
+        let (model, secret) = OAuthClientSecret::new(client_id)?;
+
+        let mut tx = self.pool.begin().await?;
+
+        query!(
+            "UPDATE oauth_client_secret
+             SET expires_at = LEAST(COALESCE(expires_at, $1), $1)
+             WHERE client_id = $2
+               AND revoked_at IS NULL
+               AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)",
+            grace_until,
+            client_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let model = query_as!(
+            OAuthClientSecret,
+            "INSERT INTO oauth_client_secret (client_id, secret_hash)
+             VALUES ($1, $2)
+             RETURNING id, client_id, secret_hash, expires_at, revoked_at, last_used_at, created_at",
+            model.client_id,
+            model.secret_hash
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok((model, secret))
+    }
+
+    /// Revokes a secret of a client.
+    ///
+    /// Returns `false` if the client has no such secret or it was already revoked.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn revoke(&self, client_id: &str, id: i64) -> ApiResult<bool> {
+        let result = query!(
+            "UPDATE oauth_client_secret
+             SET revoked_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND client_id = $2 AND revoked_at IS NULL",
+            id,
+            client_id
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Verifies `secret` against the active secrets of a client.
+    ///
+    /// Every active secret is checked even after a match so the time taken does not reveal
+    /// which secret matched, and a client without secrets is verified against a dummy hash.
+    /// The matching secret has its `last_used_at` updated.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn verify(&self, client_id: &str, secret: &ClientSecret) -> ApiResult<bool> {
+        let secrets = self.get_active_by_client(client_id).await?;
+
+        if secrets.is_empty() {
+            verify_dummy(secret.secret());
+
+            return Ok(false);
+        }
+
+        let mut matched = None;
+
+        for stored in &secrets {
+            let valid = if is_hashed(&stored.secret_hash) {
+                verify_secret(secret.secret(), &stored.secret_hash)
+            } else {
+                // Secret stored before secrets were hashed, `ClientSecret` compares in constant time.
+                ClientSecret::new(stored.secret_hash.as_str()) == *secret
+            };
+
+            if valid && matched.is_none() {
+                matched = Some(stored);
+            }
+        }
+
+        let Some(OAuthClientSecret {
+            id: Some(id),
+            secret_hash,
+            ..
+        }) = matched
+        else {
+            return Ok(false);
+        };
+
+        // Also replaces hashes made with outdated parameters, now that the secret is known.
+        if !is_hashed(secret_hash) || needs_rehash(secret_hash) {
+            self.set_hash(*id, &hash_secret(secret.secret())?).await?;
+        }
+
+        query!(
+            "UPDATE oauth_client_secret
+             SET last_used_at = CURRENT_TIMESTAMP
+             WHERE id = $1",
+            id
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Hashes all secrets that are still stored in plaintext.
+    ///
+    /// Returns the number of secrets that were updated.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn hash_plaintext_secrets(&self) -> ApiResult<usize> {
+        let secrets = query!(
+            "SELECT id, secret_hash
+             FROM oauth_client_secret
+             WHERE secret_hash NOT LIKE '$argon2%'"
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let mut updated = 0;
+
+        for secret in secrets {
+            if is_hashed(&secret.secret_hash) {
+                continue;
+            }
+
+            self.set_hash(secret.id, &hash_secret(&secret.secret_hash)?)
+                .await?;
+            updated += 1;
+        }
+
+        if updated > 0 {
+            info!(updated, "Hashed plaintext client secrets");
+        }
+
+        Ok(updated)
+    }
+
+    async fn set_hash(&self, id: i64, hash: &str) -> ApiResult<()> {
+        query!(
+            "UPDATE oauth_client_secret
+             SET secret_hash = $1
+             WHERE id = $2",
+            hash,
+            id
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,15 @@
 use crate::ApiResult;
+use crate::dto::Page;
 use crate::models::oauth_client::OAuthClient;
-use actix_oauth::types::GrantType;
-use sqlx::{query, query_as};
-use sqlx_utils::traits::Model;
+use crate::repositories::SortDirection;
+use crate::repositories::keyset::{KeysetRepository, PageParams};
+use crate::repositories::oauth_client_secrets::OAUTH_CLIENT_SECRETS_REPOSITORY;
+use crate::utils::hashing::verify_dummy;
+use actix_oauth::types::{ClientId, ClientSecret, ClientType, GrantType};
+use sqlx::{QueryBuilder, query, query_as};
+use sqlx_utils::traits::{Model, Repository};
 use sqlx_utils::{repository, types::Query};
+use uuid::Uuid;
 
 repository! {
     pub OauthClientsRepository<OAuthClient>;
@@ -11,11 +17,27 @@ repository! {
     #[inline]
     fn insert_one(client: &OAuthClient) -> Query<'_> {
         query!(
-            "INSERT INTO oauth_client (client_id, client_secret, redirect_uri, grant_types, scopes)
-             VALUES ($1, $2, $3, $4, $5)",
+            "INSERT INTO oauth_client (
+                client_id,
+                name,
+                description,
+                logo_uri,
+                contact,
+                owner_ext_id,
+                client_type,
+                redirect_uris,
+                grant_types,
+                scopes
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
             client.client_id,
-            client.client_secret,
-            client.redirect_uri,
+            client.name,
+            client.description,
+            client.logo_uri,
+            client.contact,
+            client.owner_ext_id,
+            client.client_type as _,
+            &client.redirect_uris as _,
             &client.grant_types as _,
             &client.scopes as _
         )
@@ -25,13 +47,21 @@ repository! {
     fn update_one(client: &OAuthClient) -> Query<'_> {
         query!(
             "UPDATE oauth_client
-             SET client_secret = $1,
-                 redirect_uri = $2,
-                 grant_types = $3,
-                 scopes = $4
-             WHERE client_id = $5",
-            client.client_secret,
-            client.redirect_uri,
+             SET name = $1,
+                 description = $2,
+                 logo_uri = $3,
+                 contact = $4,
+                 client_type = $5,
+                 redirect_uris = $6,
+                 grant_types = $7,
+                 scopes = $8
+             WHERE client_id = $9",
+            client.name,
+            client.description,
+            client.logo_uri,
+            client.contact,
+            client.client_type as _,
+            &client.redirect_uris as _,
             &client.grant_types as _,
             &client.scopes as _,
             client.client_id
@@ -50,9 +80,15 @@ repository! {
         Ok(query_as!(
             OAuthClient,
             "SELECT
+                id,
                 client_id,
-                client_secret,
-                redirect_uri,
+                name,
+                description,
+                logo_uri,
+                contact,
+                owner_ext_id,
+                client_type as \"client_type: ClientType\",
+                redirect_uris,
                 grant_types as \"grant_types: Vec<GrantType>\",
                 scopes,
                 created_at
@@ -68,9 +104,15 @@ repository! {
         Ok(query_as!(
             OAuthClient,
             "SELECT
+                id,
                 client_id,
-                client_secret,
-                redirect_uri,
+                name,
+                description,
+                logo_uri,
+                contact,
+                owner_ext_id,
+                client_type as \"client_type: ClientType\",
+                redirect_uris,
                 grant_types as \"grant_types: Vec<GrantType>\",
                 scopes,
                 created_at
@@ -91,9 +133,15 @@ impl OauthClientsRepository {
         scopes: Option<Vec<String>>,
     ) -> ApiResult<Vec<OAuthClient>> {
         let mut query = "SELECT
+                            id,
                             client_id,
-                            client_secret,
-                            redirect_uri,
+                            name,
+                            description,
+                            logo_uri,
+                            contact,
+                            owner_ext_id,
+                            client_type as \"client_type: ClientType\",
+                            redirect_uris,
                             grant_types as \"grant_types: Vec<GrantType>\",
                             scopes,
                             created_at
@@ -137,3 +185,140 @@ impl OauthClientsRepository {
         Ok(result)
     }
 }
+
+impl OauthClientsRepository {
+    /// Looks up a client and verifies its secret against the client's active secrets.
+    ///
+    /// Returns `None` if the client does not exist or the secret does not match. Unknown clients
+    /// are verified against a dummy hash so both cases take the same time.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn authenticate(
+        &self,
+        client_id: &ClientId,
+        client_secret: &ClientSecret,
+    ) -> ApiResult<Option<OAuthClient>> {
+        // Public clients have no secret and cannot authenticate with one.
+        let Some(client) = self
+            .get_by_id(client_id.as_str())
+            .await?
+            .filter(|client| client.client_type.has_secret())
+        else {
+            verify_dummy(client_secret.secret());
+
+            return Ok(None);
+        };
+
+        let valid = OAUTH_CLIENT_SECRETS_REPOSITORY
+            .verify(&client.client_id, client_secret)
+            .await?;
+
+        Ok(valid.then_some(client))
+    }
+
+    /// Gets a client by its row id, which tokens and authorization codes reference it by.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_by_row_id(&self, id: i64) -> ApiResult<Option<OAuthClient>> {
+        Ok(query_as!(
+            OAuthClient,
+            "SELECT
+                id,
+                client_id,
+                name,
+                description,
+                logo_uri,
+                contact,
+                owner_ext_id,
+                client_type as \"client_type: ClientType\",
+                redirect_uris,
+                grant_types as \"grant_types: Vec<GrantType>\",
+                scopes,
+                created_at
+             FROM oauth_client
+             WHERE id = $1",
+            id
+        )
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    /// Gets all clients owned by a user.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_by_owner(&self, owner: Uuid) -> ApiResult<Vec<OAuthClient>> {
+        Ok(query_as!(
+            OAuthClient,
+            "SELECT
+                id,
+                client_id,
+                name,
+                description,
+                logo_uri,
+                contact,
+                owner_ext_id,
+                client_type as \"client_type: ClientType\",
+                redirect_uris,
+                grant_types as \"grant_types: Vec<GrantType>\",
+                scopes,
+                created_at
+             FROM oauth_client
+             WHERE owner_ext_id = $1",
+            owner
+        )
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// Gets the page `page` of the clients owned by `owner`, or of all clients without an owner
+    /// given, newest first.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_page(
+        &self,
+        owner: Option<Uuid>,
+        page: &PageParams,
+    ) -> ApiResult<Page<OAuthClient>> {
+        let mut query = QueryBuilder::new(
+            "SELECT
+                id,
+                client_id,
+                name,
+                description,
+                logo_uri,
+                contact,
+                owner_ext_id,
+                client_type,
+                redirect_uris,
+                grant_types,
+                scopes,
+                created_at
+             FROM oauth_client
+             WHERE TRUE",
+        );
+
+        if let Some(owner) = owner {
+            query.push(" AND owner_ext_id = ").push_bind(owner);
+        }
+
+        self.fetch_page(query, page, SortDirection::Desc).await
+    }
+
+    /// Replaces the redirect URIs of a client.
+    ///
+    /// Returns `false` if the client does not exist.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn set_redirect_uris(
+        &self,
+        client_id: &str,
+        redirect_uris: &[String],
+    ) -> ApiResult<bool> {
+        let result = query!(
+            "UPDATE oauth_client
+             SET redirect_uris = $1
+             WHERE client_id = $2",
+            redirect_uris,
+            client_id
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,5 +1,9 @@
 use crate::ApiResult;
+use crate::dto::Page;
 use crate::models::oauth_token::OAuthToken;
+use crate::repositories::SortDirection;
+use crate::repositories::keyset::{KeysetRepository, PageParams};
+use crate::utils::hashing::token_digest;
 use sqlx::{QueryBuilder, query, query_as};
 use sqlx_utils::repository;
 use sqlx_utils::sql_filter;
@@ -11,21 +15,29 @@ sql_filter! {
     pub struct OauthTokenFilter {
         SELECT * FROM oauth_token
         WHERE
-            ?token = String
+            ?token_hash = String
             AND ?user_ext_id = Uuid
             AND ?token_type as token_types IN Vec<String>
             AND expires_at > "CURRENT_TIMESTAMP"
     }
 }
 
+impl OauthTokenFilter {
+    /// Filters on a raw token by its digest.
+    #[inline]
+    pub fn token(self, token: impl AsRef<[u8]>) -> Self {
+        self.token_hash(token_digest(token))
+    }
+}
+
 repository! {
     pub OauthTokenRepository<OAuthToken>;
 
     insert_one(model) {
         query!(
-            "INSERT INTO oauth_token (token, client_id, user_ext_id, token_type, scopes, expires_at)
+            "INSERT INTO oauth_token (token_hash, client_id, user_ext_id, token_type, scopes, expires_at)
              VALUES ($1, $2, $3, $4, $5, $6)",
-            model.token,
+            model.token_hash,
             model.client_id,
             model.user_ext_id,
             model.token_type as _,
@@ -43,7 +55,7 @@ repository! {
                 OAuthToken,
                 "SELECT
                     id,
-                    token,
+                    token_hash,
                     client_id,
                     user_ext_id,
                     token_type as \"token_type: _\",
@@ -69,26 +81,27 @@ repository! {
 }
 
 impl OauthTokenRepository {
+    /// Looks up a valid token by its raw value.
     #[tracing::instrument(skip_all)]
     pub(crate) async fn get_by_token(
         &self,
-        token: impl Into<String>,
+        token: impl AsRef<[u8]>,
     ) -> ApiResult<Option<OAuthToken>> {
-        let token = token.into();
+        let token_hash = token_digest(token);
 
         Ok(query_as!(
             OAuthToken,
             "SELECT
                 id,
-                token,
+                token_hash,
                 client_id,
                 user_ext_id,
                 token_type as \"token_type: _\",
                 scopes,
                 expires_at,
                 created_at
-             FROM oauth_token WHERE token = $1 AND expires_at > CURRENT_TIMESTAMP",
-            token
+             FROM oauth_token WHERE token_hash = $1 AND expires_at > CURRENT_TIMESTAMP",
+            token_hash
         )
         .fetch_optional(self.pool)
         .await?)
@@ -100,4 +113,45 @@ impl OauthTokenRepository {
     ) -> ApiResult<Vec<OAuthToken>> {
         self.get_by_any_filter(filter).await.map_err(Into::into)
     }
+
+    /// Gets the page `page` of the valid tokens issued for `user`, newest first.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_page_by_user(
+        &self,
+        user: Uuid,
+        page: &PageParams,
+    ) -> ApiResult<Page<OAuthToken>> {
+        let mut query = QueryBuilder::new(
+            "SELECT * FROM oauth_token WHERE expires_at > CURRENT_TIMESTAMP AND user_ext_id = ",
+        );
+        query.push_bind(user);
+
+        self.fetch_page(query, page, SortDirection::Desc).await
+    }
+
+    /// Revokes every token issued for `user` except the one with the row id `except`, returns how
+    /// many were revoked.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn revoke_by_user(&self, user: Uuid, except: Option<i64>) -> ApiResult<u64> {
+        let result = query!(
+            "DELETE FROM oauth_token
+             WHERE user_ext_id = $1 AND id IS DISTINCT FROM $2",
+            user,
+            except
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Deletes expired tokens and returns how many were deleted.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn purge_expired(&self) -> ApiResult<u64> {
+        let result = query!("DELETE FROM oauth_token WHERE expires_at <= CURRENT_TIMESTAMP")
+            .execute(self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
 }
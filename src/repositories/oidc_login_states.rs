@@ -0,0 +1,115 @@
+use crate::ApiResult;
+use crate::models::oidc_login_state::OidcLoginState;
+use crate::utils::hashing::token_digest;
+use sqlx::{query, query_as};
+use sqlx_utils::repository;
+use sqlx_utils::traits::Repository;
+
+repository! {
+    pub OidcLoginStatesRepository<OidcLoginState>;
+
+    insert_one(model) {
+        query!(
+            "INSERT INTO oidc_login_state (
+                state_hash,
+                binding_hash,
+                provider,
+                nonce,
+                pkce_verifier,
+                user_ext_id,
+                return_to,
+                expires_at
+             )
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            model.state_hash,
+            model.binding_hash,
+            model.provider,
+            model.nonce,
+            model.pkce_verifier,
+            model.user_ext_id,
+            model.return_to,
+            model.expires_at,
+        )
+    };
+
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn get_by_id(&self, id: impl Into<i64>) -> sqlx_utils::Result<Option<OidcLoginState>> {
+        let id = id.into();
+
+        Ok(
+            query_as!(
+                OidcLoginState,
+                "SELECT
+                    id,
+                    state_hash,
+                    binding_hash,
+                    provider,
+                    nonce,
+                    pkce_verifier,
+                    user_ext_id,
+                    return_to,
+                    expires_at,
+                    created_at
+                 FROM oidc_login_state
+                 WHERE id = $1 AND expires_at > CURRENT_TIMESTAMP",
+                id
+            )
+            .fetch_optional(self.pool)
+            .await?
+        )
+    }
+}
+
+impl OidcLoginStatesRepository {
+    /// Removes and returns the login started with `state` at `provider` by the browser holding
+    /// `binding`.
+    ///
+    /// Each state can only be used once, expired logins and logins started by another browser
+    /// are never returned.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn take(
+        &self,
+        provider: &str,
+        state: impl AsRef<[u8]>,
+        binding: impl AsRef<[u8]>,
+    ) -> ApiResult<Option<OidcLoginState>> {
+        let state_hash = token_digest(state);
+        let binding_hash = token_digest(binding);
+
+        Ok(query_as!(
+            OidcLoginState,
+            "DELETE FROM oidc_login_state
+             WHERE state_hash = $1
+               AND binding_hash = $2
+               AND provider = $3
+               AND expires_at > CURRENT_TIMESTAMP
+             RETURNING
+                id,
+                state_hash,
+                binding_hash,
+                provider,
+                nonce,
+                pkce_verifier,
+                user_ext_id,
+                return_to,
+                expires_at,
+                created_at",
+            state_hash,
+            binding_hash,
+            provider
+        )
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    /// Deletes logins that were never finished before they expired and returns how many were
+    /// deleted.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn purge_expired(&self) -> ApiResult<u64> {
+        let result = query!("DELETE FROM oidc_login_state WHERE expires_at <= CURRENT_TIMESTAMP")
+            .execute(self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
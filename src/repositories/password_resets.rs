@@ -0,0 +1,96 @@
+use crate::ApiResult;
+use crate::models::password_reset::PasswordReset;
+use crate::utils::hashing::token_digest;
+use sqlx::{query, query_as};
+use sqlx_utils::repository;
+use sqlx_utils::traits::Repository;
+use uuid::Uuid;
+
+repository! {
+    pub PasswordResetsRepository<PasswordReset>;
+
+    insert_one(model) {
+        query!(
+            "INSERT INTO password_reset (token_hash, user_ext_id, expires_at)
+             VALUES ($1, $2, $3)",
+            model.token_hash,
+            model.user_ext_id,
+            model.expires_at,
+        )
+    };
+
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn get_by_id(&self, id: impl Into<i64>) -> sqlx_utils::Result<Option<PasswordReset>> {
+        let id = id.into();
+
+        Ok(
+            query_as!(
+                PasswordReset,
+                "SELECT id, token_hash, user_ext_id, expires_at, created_at
+                 FROM password_reset WHERE id = $1 AND expires_at > CURRENT_TIMESTAMP",
+                id
+            )
+            .fetch_optional(self.pool)
+            .await?
+        )
+    }
+}
+
+impl PasswordResetsRepository {
+    /// Gets the reset with the raw token `token` without using it up.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_by_token(
+        &self,
+        token: impl AsRef<[u8]>,
+    ) -> ApiResult<Option<PasswordReset>> {
+        let token_hash = token_digest(token);
+
+        Ok(query_as!(
+            PasswordReset,
+            "SELECT id, token_hash, user_ext_id, expires_at, created_at
+             FROM password_reset
+             WHERE token_hash = $1 AND expires_at > CURRENT_TIMESTAMP",
+            token_hash
+        )
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    /// Removes and returns the reset with the raw token `token`.
+    ///
+    /// Each reset can only be taken once, expired resets are never returned.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn take(&self, token: impl AsRef<[u8]>) -> ApiResult<Option<PasswordReset>> {
+        let token_hash = token_digest(token);
+
+        Ok(query_as!(
+            PasswordReset,
+            "DELETE FROM password_reset
+             WHERE token_hash = $1 AND expires_at > CURRENT_TIMESTAMP
+             RETURNING id, token_hash, user_ext_id, expires_at, created_at",
+            token_hash
+        )
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    /// Deletes every reset of `user` and returns how many were deleted.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn delete_by_user(&self, user: Uuid) -> ApiResult<u64> {
+        let result = query!("DELETE FROM password_reset WHERE user_ext_id = $1", user)
+            .execute(self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Deletes expired resets and returns how many were deleted.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn purge_expired(&self) -> ApiResult<u64> {
+        let result = query!("DELETE FROM password_reset WHERE expires_at <= CURRENT_TIMESTAMP")
+            .execute(self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,166 @@
+use crate::ApiResult;
+use crate::models::permission::{Permission, Permissions};
+use crate::models::role::Role;
+use sqlx::{query, query_as, query_scalar};
+use sqlx_utils::repository;
+use sqlx_utils::traits::Repository;
+use uuid::Uuid;
+
+repository! {
+    pub RolesRepository<Role>;
+
+    insert_one(model) {
+        query!(
+            "INSERT INTO roles (name, description) VALUES ($1, $2)",
+            model.name,
+            model.description,
+        )
+    };
+
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn get_by_id(&self, id: impl Into<i64>) -> sqlx_utils::Result<Option<Role>> {
+        let id = id.into();
+
+        Ok(
+            query_as!(
+                Role,
+                r#"SELECT
+                    role.id,
+                    role.name as "name!",
+                    role.description,
+                    COALESCE(
+                        array_agg(permission.name ORDER BY permission.name)
+                            FILTER (WHERE permission.name IS NOT NULL),
+                        '{}'
+                    ) as "permissions!",
+                    role.created_at
+                 FROM roles role
+                 LEFT JOIN role_permissions ON role_permissions.role_id = role.id
+                 LEFT JOIN permissions permission ON permission.id = role_permissions.permission_id
+                 WHERE role.id = $1
+                 GROUP BY role.id"#,
+                id
+            )
+            .fetch_optional(self.pool)
+            .await?
+        )
+    }
+}
+
+impl RolesRepository {
+    /// Gets all roles with their permissions, ordered by name.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_all(&self) -> ApiResult<Vec<Role>> {
+        Ok(query_as!(
+            Role,
+            r#"SELECT
+                role.id,
+                role.name as "name!",
+                role.description,
+                COALESCE(
+                    array_agg(permission.name ORDER BY permission.name)
+                        FILTER (WHERE permission.name IS NOT NULL),
+                    '{}'
+                ) as "permissions!",
+                role.created_at
+             FROM roles role
+             LEFT JOIN role_permissions ON role_permissions.role_id = role.id
+             LEFT JOIN permissions permission ON permission.id = role_permissions.permission_id
+             GROUP BY role.id
+             ORDER BY role.name"#
+        )
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// Gets the id of the role called `name`.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_id_by_name(&self, name: &str) -> ApiResult<Option<i64>> {
+        Ok(query_scalar!("SELECT id FROM roles WHERE name = $1", name)
+            .fetch_optional(self.pool)
+            .await?)
+    }
+
+    /// Names of the roles assigned to `user`, ordered by name.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_names_by_user(&self, user: Uuid) -> ApiResult<Vec<String>> {
+        Ok(query_scalar!(
+            "SELECT role.name
+             FROM user_roles
+             JOIN roles role ON role.id = user_roles.role_id
+             WHERE user_roles.user_ext_id = $1
+             ORDER BY role.name",
+            user
+        )
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// All permissions `user` has through their roles.
+    ///
+    /// Permissions in the database the server does not know are skipped.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn permissions_of(&self, user: Uuid) -> ApiResult<Permissions> {
+        let names = query_scalar!(
+            "SELECT DISTINCT permission.name
+             FROM user_roles
+             JOIN role_permissions ON role_permissions.role_id = user_roles.role_id
+             JOIN permissions permission ON permission.id = role_permissions.permission_id
+             WHERE user_roles.user_ext_id = $1",
+            user
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(names
+            .iter()
+            .filter_map(|name| Permission::from_name(name))
+            .collect())
+    }
+
+    /// Assigns the role with the row id `role` to `user`, returns whether it was not already.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn assign(&self, user: Uuid, role: i64) -> ApiResult<bool> {
+        let result = query!(
+            "INSERT INTO user_roles (user_ext_id, role_id)
+             VALUES ($1, $2)
+             ON CONFLICT DO NOTHING",
+            user,
+            role
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Removes the role with the row id `role` from `user`, returns whether it was assigned.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn unassign(&self, user: Uuid, role: i64) -> ApiResult<bool> {
+        let result = query!(
+            "DELETE FROM user_roles WHERE user_ext_id = $1 AND role_id = $2",
+            user,
+            role
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Whether any user is assigned the role called `name`.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn is_assigned(&self, name: &str) -> ApiResult<bool> {
+        Ok(query_scalar!(
+            r#"SELECT EXISTS (
+                SELECT 1
+                FROM user_roles
+                JOIN roles role ON role.id = user_roles.role_id
+                WHERE role.name = $1
+            ) as "assigned!""#,
+            name
+        )
+        .fetch_one(self.pool)
+        .await?)
+    }
+}
@@ -0,0 +1,127 @@
+use crate::ApiResult;
+use crate::models::session::Session;
+use crate::utils::hashing::token_digest;
+use chrono::TimeDelta;
+use sqlx::{query, query_as};
+use sqlx_utils::repository;
+use sqlx_utils::traits::Repository;
+use uuid::Uuid;
+
+repository! {
+    pub SessionsRepository<Session>;
+
+    insert_one(model) {
+        query!(
+            "INSERT INTO session (session_hash, csrf_hash, user_ext_id, user_agent, expires_at)
+             VALUES ($1, $2, $3, $4, $5)",
+            model.session_hash,
+            model.csrf_hash,
+            model.user_ext_id,
+            model.user_agent,
+            model.expires_at,
+        )
+    };
+
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn get_by_id(&self, id: impl Into<i64>) -> sqlx_utils::Result<Option<Session>> {
+        let id = id.into();
+
+        Ok(
+            query_as!(
+                Session,
+                "SELECT
+                    id,
+                    session_hash,
+                    csrf_hash,
+                    user_ext_id,
+                    user_agent,
+                    expires_at,
+                    last_seen_at,
+                    created_at
+                 FROM session WHERE id = $1 AND expires_at > CURRENT_TIMESTAMP",
+                id
+            )
+            .fetch_optional(self.pool)
+            .await?
+        )
+    }
+}
+
+impl SessionsRepository {
+    /// Gets the session with the raw token `token` and marks it as used.
+    ///
+    /// Sessions past their absolute timeout or unused for longer than `idle_timeout` are never
+    /// returned.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn touch(
+        &self,
+        token: impl AsRef<[u8]>,
+        idle_timeout: TimeDelta,
+    ) -> ApiResult<Option<Session>> {
+        let session_hash = token_digest(token);
+
+        Ok(query_as!(
+            Session,
+            "UPDATE session
+             SET last_seen_at = CURRENT_TIMESTAMP
+             WHERE session_hash = $1
+               AND expires_at > CURRENT_TIMESTAMP
+               AND last_seen_at > CURRENT_TIMESTAMP - make_interval(secs => $2)
+             RETURNING
+                id,
+                session_hash,
+                csrf_hash,
+                user_ext_id,
+                user_agent,
+                expires_at,
+                last_seen_at,
+                created_at",
+            session_hash,
+            idle_timeout.num_seconds() as f64
+        )
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    /// Ends the session with the raw token `token`, returns whether it existed.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn delete_by_token(&self, token: impl AsRef<[u8]>) -> ApiResult<bool> {
+        let session_hash = token_digest(token);
+
+        let result = query!("DELETE FROM session WHERE session_hash = $1", session_hash)
+            .execute(self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Ends every session of `user` except the one with the row id `except`, returns how many
+    /// were ended.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn delete_by_user(&self, user: Uuid, except: Option<i64>) -> ApiResult<u64> {
+        let result = query!(
+            "DELETE FROM session WHERE user_ext_id = $1 AND id IS DISTINCT FROM $2",
+            user,
+            except
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Deletes sessions that ended through either timeout and returns how many were deleted.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn purge_expired(&self, idle_timeout: TimeDelta) -> ApiResult<u64> {
+        let result = query!(
+            "DELETE FROM session
+             WHERE expires_at <= CURRENT_TIMESTAMP
+                OR last_seen_at <= CURRENT_TIMESTAMP - make_interval(secs => $1)",
+            idle_timeout.num_seconds() as f64
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,171 @@
+use crate::ApiResult;
+use crate::models::user_consent::UserConsent;
+use chrono::TimeDelta;
+use sqlx::{query, query_as};
+use sqlx_utils::repository;
+use sqlx_utils::traits::Repository;
+use uuid::Uuid;
+
+repository! {
+    pub UserConsentRepository<UserConsent>;
+
+    insert_one(model) {
+        query!(
+            "INSERT INTO user_consent (user_ext_id, client_id, scopes)
+             VALUES ($1, $2, $3)",
+            model.user_ext_id,
+            model.client_id,
+            &model.scopes as _,
+        )
+    };
+
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn get_by_id(&self, id: impl Into<i64>) -> sqlx_utils::Result<Option<UserConsent>> {
+        let id = id.into();
+
+        Ok(
+            query_as!(
+                UserConsent,
+                "SELECT id, user_ext_id, client_id, scopes, created_at, updated_at
+                 FROM user_consent
+                 WHERE id = $1",
+                id
+            )
+            .fetch_optional(self.pool)
+            .await?
+        )
+    }
+}
+
+impl UserConsentRepository {
+    /// Gets the consent a user has given a client, if any.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get(&self, user: Uuid, client_id: &str) -> ApiResult<Option<UserConsent>> {
+        Ok(query_as!(
+            UserConsent,
+            "SELECT id, user_ext_id, client_id, scopes, created_at, updated_at
+             FROM user_consent
+             WHERE user_ext_id = $1 AND client_id = $2",
+            user,
+            client_id
+        )
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    /// Gets all clients a user has given consent to, most recently updated first.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_by_user(&self, user: Uuid) -> ApiResult<Vec<UserConsent>> {
+        Ok(query_as!(
+            UserConsent,
+            "SELECT id, user_ext_id, client_id, scopes, created_at, updated_at
+             FROM user_consent
+             WHERE user_ext_id = $1
+             ORDER BY updated_at DESC",
+            user
+        )
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// Records that a user approved `scopes` for a client.
+    ///
+    /// The scopes are added to the ones approved before, so a client asking for fewer scopes
+    /// later does not take away what was already approved.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn grant(
+        &self,
+        user: Uuid,
+        client_id: &str,
+        scopes: &[String],
+    ) -> ApiResult<UserConsent> {
+        Ok(query_as!(
+            UserConsent,
+            "INSERT INTO user_consent (user_ext_id, client_id, scopes)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (user_ext_id, client_id) DO UPDATE
+             SET scopes = ARRAY(
+                     SELECT DISTINCT unnest(user_consent.scopes || EXCLUDED.scopes)
+                 ),
+                 updated_at = CURRENT_TIMESTAMP
+             RETURNING id, user_ext_id, client_id, scopes, created_at, updated_at",
+            user,
+            client_id,
+            scopes
+        )
+        .fetch_one(self.pool)
+        .await?)
+    }
+
+    /// Revokes the consent a user has given a client.
+    ///
+    /// The tokens and authorization codes issued to the client on behalf of the user are
+    /// deleted with it, so the client loses access immediately. Returns `false` if the user had
+    /// not given the client consent.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn revoke(&self, user: Uuid, client_id: &str) -> ApiResult<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = query!(
+            "DELETE FROM user_consent
+             WHERE user_ext_id = $1 AND client_id = $2",
+            user,
+            client_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        query!(
+            "DELETE FROM oauth_token
+             WHERE user_ext_id = $1
+               AND client_id = (SELECT id FROM oauth_client WHERE client_id = $2)",
+            user,
+            client_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        query!(
+            "DELETE FROM oauth_auth_code
+             WHERE user_ext_id = $1
+               AND client_id = (SELECT id FROM oauth_client WHERE client_id = $2)",
+            user,
+            client_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Deletes consent that has not been used for `max_age` and returns how many were deleted.
+    ///
+    /// Consent counts as used while the client holds a token issued for the user, so users are
+    /// only asked again by clients they stopped using.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn purge_unused(&self, max_age: TimeDelta) -> ApiResult<u64> {
+        let result = query!(
+            "DELETE FROM user_consent consent
+             WHERE consent.updated_at < CURRENT_TIMESTAMP - make_interval(secs => $1)
+               AND NOT EXISTS (
+                   SELECT 1
+                   FROM oauth_token token
+                   JOIN oauth_client client ON client.id = token.client_id
+                   WHERE token.user_ext_id = consent.user_ext_id
+                     AND client.client_id = consent.client_id
+                     AND token.expires_at > CURRENT_TIMESTAMP
+               )",
+            max_age.num_seconds() as f64
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,92 @@
+use crate::ApiResult;
+use crate::models::user_identity::UserIdentity;
+use sqlx::{query, query_as};
+use sqlx_utils::repository;
+use sqlx_utils::traits::Repository;
+use uuid::Uuid;
+
+repository! {
+    pub UserIdentitiesRepository<UserIdentity>;
+
+    insert_one(model) {
+        query!(
+            "INSERT INTO user_identities (user_ext_id, provider, subject, email, last_login_at)
+             VALUES ($1, $2, $3, $4, $5)",
+            model.user_ext_id,
+            model.provider,
+            model.subject,
+            model.email,
+            model.last_login_at,
+        )
+    };
+
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn get_by_id(&self, id: impl Into<i64>) -> sqlx_utils::Result<Option<UserIdentity>> {
+        let id = id.into();
+
+        Ok(
+            query_as!(
+                UserIdentity,
+                "SELECT id, user_ext_id, provider, subject, email, created_at, last_login_at
+                 FROM user_identities
+                 WHERE id = $1",
+                id
+            )
+            .fetch_optional(self.pool)
+            .await?
+        )
+    }
+}
+
+impl UserIdentitiesRepository {
+    /// Gets the identity a provider knows a user by.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_by_subject(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> ApiResult<Option<UserIdentity>> {
+        Ok(query_as!(
+            UserIdentity,
+            "SELECT id, user_ext_id, provider, subject, email, created_at, last_login_at
+             FROM user_identities
+             WHERE provider = $1 AND subject = $2",
+            provider,
+            subject
+        )
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    /// Gets all identities linked to a user.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_by_user(&self, user: Uuid) -> ApiResult<Vec<UserIdentity>> {
+        Ok(query_as!(
+            UserIdentity,
+            "SELECT id, user_ext_id, provider, subject, email, created_at, last_login_at
+             FROM user_identities
+             WHERE user_ext_id = $1
+             ORDER BY created_at",
+            user
+        )
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// Records a login with an identity, keeping the email the provider reported up to date.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn touch(&self, id: i64, email: Option<&str>) -> ApiResult<()> {
+        query!(
+            "UPDATE user_identities
+             SET last_login_at = CURRENT_TIMESTAMP,
+                 email = COALESCE($1, email)
+             WHERE id = $2",
+            email,
+            id
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
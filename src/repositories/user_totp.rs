@@ -0,0 +1,107 @@
+use crate::ApiResult;
+use crate::models::user_totp::UserTotp;
+use sqlx::{query, query_as};
+use sqlx_utils::repository;
+use sqlx_utils::traits::Repository;
+use uuid::Uuid;
+
+repository! {
+    pub UserTotpRepository<UserTotp>;
+
+    // Replaces an authenticator that was never confirmed, a confirmed one is left as is.
+    insert_one(model) {
+        query!(
+            "INSERT INTO user_totp (user_ext_id, secret_ciphertext)
+             VALUES ($1, $2)
+             ON CONFLICT (user_ext_id) DO UPDATE
+             SET secret_ciphertext = EXCLUDED.secret_ciphertext,
+                 confirmed_at = NULL,
+                 last_used_step = NULL,
+                 created_at = CURRENT_TIMESTAMP
+             WHERE user_totp.confirmed_at IS NULL",
+            model.user_ext_id,
+            model.secret_ciphertext,
+        )
+    };
+
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn get_by_id(&self, id: impl Into<i64>) -> sqlx_utils::Result<Option<UserTotp>> {
+        let id = id.into();
+
+        Ok(
+            query_as!(
+                UserTotp,
+                "SELECT id, user_ext_id, secret_ciphertext, confirmed_at, last_used_step, created_at
+                 FROM user_totp WHERE id = $1",
+                id
+            )
+            .fetch_optional(self.pool)
+            .await?
+        )
+    }
+}
+
+impl UserTotpRepository {
+    /// Gets the authenticator of `user`, confirmed or not.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn get_by_user(&self, user: Uuid) -> ApiResult<Option<UserTotp>> {
+        Ok(query_as!(
+            UserTotp,
+            "SELECT id, user_ext_id, secret_ciphertext, confirmed_at, last_used_step, created_at
+             FROM user_totp WHERE user_ext_id = $1",
+            user
+        )
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    /// Confirms the authenticator of `user` with a code for the time step `step`.
+    ///
+    /// Returns `false` if the user has no unconfirmed authenticator.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn confirm(&self, user: Uuid, step: i64) -> ApiResult<bool> {
+        let result = query!(
+            "UPDATE user_totp
+             SET confirmed_at = CURRENT_TIMESTAMP, last_used_step = $2
+             WHERE user_ext_id = $1 AND confirmed_at IS NULL",
+            user,
+            step
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Records that a code for the time step `step` was used with the confirmed authenticator of
+    /// `user`.
+    ///
+    /// Returns `false` if a code for this or a later step was already used, so every code is
+    /// accepted at most once even when requests race.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn use_step(&self, user: Uuid, step: i64) -> ApiResult<bool> {
+        let result = query!(
+            "UPDATE user_totp
+             SET last_used_step = $2
+             WHERE user_ext_id = $1
+               AND confirmed_at IS NOT NULL
+               AND (last_used_step IS NULL OR last_used_step < $2)",
+            user,
+            step
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Removes the authenticator of `user`, returns `false` if they had none.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn delete_by_user(&self, user: Uuid) -> ApiResult<bool> {
+        let result = query!("DELETE FROM user_totp WHERE user_ext_id = $1", user)
+            .execute(self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,31 +1,113 @@
 use crate::ApiResult;
+use crate::dto::Page;
+use crate::error::ApiError;
 use crate::models::user::User;
-use argon2::{
-    Argon2,
-    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
-};
+use crate::repositories::SortDirection;
+use crate::repositories::keyset::{Cursor, PageParams, SortColumn, SortKey};
+use crate::utils::hashing::{hash_secret, needs_rehash, verify_dummy, verify_secret};
 use chrono::NaiveDateTime;
-use sqlx::{PgPool, query, query_as};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, QueryBuilder, query, query_as, query_scalar};
 use sqlx_utils::repository;
 use sqlx_utils::traits::{Model, Repository};
 use sqlx_utils::types::Query;
-use tracing::error;
+use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
 
 repository! {
     pub UsersRepository;
 }
 
-#[derive(Debug, Default)]
+/// Filters and ordering for listing users, read from the query of `GET /users`.
+///
+/// Users are ordered by `sort`, paging goes through [`PageParams`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct UserSearchParams {
+    /// Only users whose username contains this, ignoring case.
     pub username_contains: Option<String>,
+    /// Only users whose email contains this, ignoring case.
     pub email_contains: Option<String>,
+    /// Only users created after this time.
     pub created_after: Option<NaiveDateTime>,
+    /// Only users created before this time.
     pub created_before: Option<NaiveDateTime>,
-    pub limit: Option<i64>,
-    pub offset: Option<i64>,
+    /// Field to sort by, `created_at` by default.
+    pub sort: Option<UserSortField>,
+    /// Direction to sort in, `desc` by default.
+    pub order: Option<SortDirection>,
+}
+
+impl UserSearchParams {
+    /// Adds the filters as a `WHERE` clause to `query`.
+    fn push_filters<'args>(&'args self, query: &mut QueryBuilder<'args, Postgres>) {
+        query.push(" WHERE TRUE");
+
+        if let Some(username) = &self.username_contains {
+            query
+                .push(" AND username ILIKE ")
+                .push_bind(format!("%{}%", escape_like(username)));
+        }
+        if let Some(email) = &self.email_contains {
+            query
+                .push(" AND email ILIKE ")
+                .push_bind(format!("%{}%", escape_like(email)));
+        }
+        if let Some(after) = self.created_after {
+            query.push(" AND created_at > ").push_bind(after);
+        }
+        if let Some(before) = self.created_before {
+            query.push(" AND created_at < ").push_bind(before);
+        }
+    }
+}
+
+/// Fields users can be sorted by.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UserSortField {
+    Username,
+    Email,
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl UserSortField {
+    /// The column to sort by.
+    pub fn column(&self) -> SortColumn {
+        match self {
+            Self::Username => SortColumn::text("username"),
+            Self::Email => SortColumn::text("email"),
+            Self::CreatedAt => SortColumn::CREATED_AT,
+            Self::UpdatedAt => SortColumn::time("updated_at"),
+        }
+    }
+
+    /// Position of `user` in a list sorted by this field.
+    pub(crate) fn cursor(&self, user: &User) -> Option<Cursor> {
+        let key = match self {
+            Self::Username => SortKey::Text(user.username.clone()),
+            Self::Email => SortKey::Text(user.email.clone()),
+            Self::CreatedAt => SortKey::Time(user.created_at?),
+            Self::UpdatedAt => SortKey::Time(user.updated_at?),
+        };
+
+        Some(Cursor { key, id: user.id? })
+    }
+}
+
+/// Escapes the wildcards of `LIKE` patterns so user input only matches literally.
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
 
 impl UsersRepository {
+    /// Creates a user and returns it as stored.
     #[tracing::instrument(skip_all)]
     pub(crate) async fn create_user(
         &self,
@@ -40,37 +122,151 @@ impl UsersRepository {
             username: username.to_string(),
             password_hash,
             email: email.to_string(),
+            email_verified_at: None,
             created_at: None,
             updated_at: None,
         };
 
         self.insert(&user).await?;
-        Ok(user)
+
+        // Read it back for the values the database fills in.
+        self.find_by_username(username)
+            .await?
+            .ok_or(ApiError::InternalError)
     }
 
+    /// Gets the user with `username` if `password` is theirs.
+    ///
+    /// Unknown usernames take as long to check as wrong passwords, so responses do not reveal
+    /// which users exist. Passwords hashed with outdated parameters are rehashed, see
+    /// [`UsersRepository::rehash_if_outdated`].
     #[tracing::instrument(skip_all)]
-    pub(crate) fn verify_password(&self, password: &impl AsRef<[u8]>, hash: &str) -> bool {
-        let parsed_hash = match PasswordHash::new(hash) {
-            Ok(pass) => pass,
-            Err(error) => {
-                error!(error = %error);
-
-                return false;
+    pub(crate) async fn authenticate(
+        &self,
+        username: impl AsRef<str>,
+        password: impl AsRef<[u8]>,
+    ) -> ApiResult<Option<User>> {
+        match self.find_by_username(username).await? {
+            Some(user) if self.verify_password(&password, &user.password_hash) => {
+                Ok(Some(self.rehash_if_outdated(user, &password).await))
+            }
+            Some(_) => Ok(None),
+            None => {
+                verify_dummy(password);
+                Ok(None)
             }
+        }
+    }
+
+    /// Saves changes to the username and email of `user`.
+    ///
+    /// Fails with [`ApiError::BadRequest`] if another user already has the username or email.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn update_profile(&self, user: &User) -> ApiResult<()> {
+        let taken = query_scalar!(
+            r#"SELECT EXISTS (
+                SELECT 1 FROM users
+                WHERE (username = $1 OR email = $2) AND ext_id <> $3
+            ) as "taken!""#,
+            user.username,
+            user.email,
+            user.ext_id
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        if taken {
+            return Err(ApiError::BadRequest(
+                "The username or email is already in use".into(),
+            ));
+        }
+
+        self.update(user).await?;
+        Ok(())
+    }
+
+    /// Marks `email` as verified for `user`, returns whether it is still their email and was not
+    /// verified yet.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn mark_email_verified(&self, user: Uuid, email: &str) -> ApiResult<bool> {
+        let result = query!(
+            "UPDATE users
+             SET email_verified_at = CURRENT_TIMESTAMP
+             WHERE ext_id = $1 AND email = $2 AND email_verified_at IS NULL",
+            user,
+            email
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replaces the password of `user` with `password`.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn change_password(&self, user: &User, password: &str) -> ApiResult<()> {
+        let user = User {
+            password_hash: self.hash_password(&password)?,
+            ..user.clone()
         };
 
-        Argon2::default()
-            .verify_password(password.as_ref(), &parsed_hash)
-            .is_ok()
+        self.update(&user).await?;
+        Ok(())
     }
 
+    /// Replaces the password hash of `user` if it was made with an outdated algorithm, other
+    /// parameters or without the current pepper, so raising the parameters takes effect as
+    /// users sign in. `password` has to be the verified password of the user.
+    ///
+    /// Returns the user with the hash that is stored. A failure to rehash is logged, the old
+    /// hash keeps working.
     #[tracing::instrument(skip_all)]
-    fn hash_password(&self, password: &impl AsRef<[u8]>) -> ApiResult<String> {
-        let salt = SaltString::generate(&mut OsRng);
+    pub(crate) async fn rehash_if_outdated(&self, user: User, password: &impl AsRef<[u8]>) -> User {
+        if !needs_rehash(&user.password_hash) {
+            return user;
+        }
 
-        let argon2 = Argon2::default();
+        let password_hash = match self.hash_password(password) {
+            Ok(hash) => hash,
+            Err(error) => {
+                warn!(%error, "failed to rehash a password");
+                return user;
+            }
+        };
+
+        // Only replaces the hash that was verified, in case the password changed meanwhile.
+        let result = query!(
+            "UPDATE users
+             SET password_hash = $1
+             WHERE ext_id = $2 AND password_hash = $3",
+            password_hash,
+            user.ext_id,
+            user.password_hash
+        )
+        .execute(self.pool)
+        .await;
+
+        match result {
+            Ok(result) if result.rows_affected() > 0 => User {
+                password_hash,
+                ..user
+            },
+            Ok(_) => user,
+            Err(error) => {
+                warn!(%error, "failed to store a rehashed password");
+                user
+            }
+        }
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub(crate) fn verify_password(&self, password: &impl AsRef<[u8]>, hash: &str) -> bool {
+        verify_secret(password, hash)
+    }
 
-        Ok(argon2.hash_password(password.as_ref(), &salt)?.to_string())
+    #[tracing::instrument(skip_all)]
+    fn hash_password(&self, password: &impl AsRef<[u8]>) -> ApiResult<String> {
+        hash_secret(password)
     }
 }
 
@@ -98,11 +294,13 @@ impl Repository<User> for UsersRepository {
              SET username = $1,
                  password_hash = $2,
                  email = $3,
+                 email_verified_at = $4,
                  updated_at = CURRENT_TIMESTAMP
-             WHERE ext_id = $4",
+             WHERE ext_id = $5",
             model.username,
             model.password_hash,
             model.email,
+            model.email_verified_at,
             model.ext_id
         )
     }
@@ -120,7 +318,7 @@ impl Repository<User> for UsersRepository {
     async fn get_all(&self) -> sqlx_utils::Result<Vec<User>> {
         Ok(query_as!(
             User,
-            "SELECT id, ext_id, username, password_hash, email, created_at, updated_at FROM users"
+            "SELECT id, ext_id, username, password_hash, email, email_verified_at, created_at, updated_at FROM users"
         )
         .fetch_all(self.pool)
         .await?)
@@ -134,7 +332,7 @@ impl Repository<User> for UsersRepository {
         let id = id.into();
         Ok(query_as!(
             User,
-            "SELECT id, ext_id, username, password_hash, email, created_at, updated_at
+            "SELECT id, ext_id, username, password_hash, email, email_verified_at, created_at, updated_at
              FROM users
              WHERE ext_id = $1",
             id
@@ -149,7 +347,7 @@ impl UsersRepository {
     pub async fn find_by_email(&self, email: &str) -> ApiResult<Option<User>> {
         Ok(query_as!(
             User,
-            "SELECT id, ext_id, username, password_hash, email, created_at, updated_at
+            "SELECT id, ext_id, username, password_hash, email, email_verified_at, created_at, updated_at
              FROM users
              WHERE email = $1",
             email
@@ -158,68 +356,86 @@ impl UsersRepository {
         .await?)
     }
 
+    /// Gets the user with the username `username`, or with it as their verified email.
+    ///
+    /// Unverified emails are never matched, so nobody can sign in with an address they have not
+    /// proven to own.
     #[tracing::instrument(skip_all)]
     pub async fn find_by_username(&self, username: impl AsRef<str>) -> ApiResult<Option<User>> {
         Ok(query_as!(
             User,
-            "SELECT id, ext_id, username, password_hash, email, created_at, updated_at
+            "SELECT id, ext_id, username, password_hash, email, email_verified_at, created_at, updated_at
              FROM users
-             WHERE username = $1 OR email = $1",
+             WHERE username = $1 OR (email = $1 AND email_verified_at IS NOT NULL)",
             username.as_ref()
         )
         .fetch_optional(self.pool)
         .await?)
     }
 
+    /// Gets the page `page` of the users matching `params`, with the number of users matching
+    /// across all pages.
     #[tracing::instrument(skip_all)]
-    pub async fn search(&self, params: &UserSearchParams) -> ApiResult<Vec<User>> {
-        let mut query = "SELECT id, ext_id, username, password_hash, email, created_at, updated_at 
-                        FROM users WHERE 1=1"
-            .to_string();
-        let mut bindings = vec![];
-
-        if let Some(username) = &params.username_contains {
-            query.push_str(" AND username ILIKE $1");
-            bindings.push(format!("%{}%", username));
-        }
+    pub(crate) async fn search(
+        &self,
+        params: &UserSearchParams,
+        page: &PageParams,
+    ) -> ApiResult<Page<User>> {
+        let sort = params.sort.unwrap_or_default();
 
-        if let Some(email) = &params.email_contains {
-            let param_num = bindings.len() + 1;
-            query.push_str(&format!(" AND email ILIKE ${}", param_num));
-            bindings.push(format!("%{}%", email));
-        }
+        let mut query = QueryBuilder::new(
+            "SELECT id, ext_id, username, password_hash, email, email_verified_at, created_at, updated_at FROM users",
+        );
 
-        if let Some(after) = &params.created_after {
-            let param_num = bindings.len() + 1;
-            query.push_str(&format!(" AND created_at > ${}", param_num));
-            bindings.push(after.to_string());
-        }
+        params.push_filters(&mut query);
+        page.push_keyset_by(&mut query, sort.column(), params.order.unwrap_or_default())?;
 
-        if let Some(before) = &params.created_before {
-            let param_num = bindings.len() + 1;
-            query.push_str(&format!(" AND created_at < ${}", param_num));
-            bindings.push(before.to_string());
-        }
+        let users: Vec<User> = query.build_query_as().fetch_all(self.pool).await?;
 
-        query.push_str(" ORDER BY created_at DESC");
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM users");
 
-        if let Some(limit) = params.limit {
-            let param_num = bindings.len() + 1;
-            query.push_str(&format!(" LIMIT ${}", param_num));
-            bindings.push(limit.to_string());
-        }
+        params.push_filters(&mut count);
 
-        if let Some(offset) = params.offset {
-            let param_num = bindings.len() + 1;
-            query.push_str(&format!(" OFFSET ${}", param_num));
-            bindings.push(offset.to_string());
-        }
+        let total: i64 = count.build_query_scalar().fetch_one(self.pool).await?;
 
-        let mut query_builder = sqlx::query_as::<_, User>(&query);
-        for binding in bindings {
-            query_builder = query_builder.bind(binding);
-        }
+        Ok(Page {
+            total: Some(total),
+            ..page.into_page_by(users, |user| sort.cursor(user))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::web;
+
+    #[test]
+    fn search_params_read_the_sort_field() {
+        let params = web::Query::<UserSearchParams>::from_query("sort=username&order=asc").unwrap();
+
+        assert_eq!(params.sort, Some(UserSortField::Username));
+        assert_eq!(params.order, Some(SortDirection::Asc));
+        assert!(web::Query::<UserSearchParams>::from_query("sort=password_hash").is_err());
+    }
+
+    #[test]
+    fn sort_field_cursor_uses_its_column() {
+        let user = User {
+            id: Some(4),
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            created_at: Some(NaiveDateTime::default()),
+            ..Default::default()
+        };
+
+        let cursor = UserSortField::Email.cursor(&user).unwrap();
+        assert_eq!(cursor.key, SortKey::Text("alice@example.com".to_string()));
+        assert_eq!(cursor.id, 4);
+
+        let cursor = UserSortField::CreatedAt.cursor(&user).unwrap();
+        assert_eq!(cursor.key, SortKey::Time(NaiveDateTime::default()));
 
-        Ok(query_builder.fetch_all(self.pool).await?)
+        assert_eq!(UserSortField::UpdatedAt.cursor(&user), None);
     }
 }
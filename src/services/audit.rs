@@ -0,0 +1,180 @@
+//! The audit log, an append-only record of security relevant events.
+//!
+//! Endpoints and OAuth handlers record what they did with [`AuditEntry`], taking the address,
+//! user agent and actor from the request. Events are chained by hash in the database, see
+//! [`AuditEvent::chain_hash`], so changes to the log can be detected with
+//! [`AuditEventsRepository::verify_chain`](crate::repositories::audit_events::AuditEventsRepository::verify_chain).
+
+use crate::middleware::Principal;
+use crate::models::audit_event::{AuditEvent, AuditOutcome};
+use crate::repositories::audit_events::AUDIT_EVENTS_REPOSITORY;
+use crate::services::lockout::client_ip;
+use actix_web::http::header::USER_AGENT;
+use actix_web::{HttpMessage, HttpRequest};
+use tracing::error;
+use uuid::Uuid;
+
+/// Longest user agent that is kept, longer ones are cut off.
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+/// What an audit event records.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub(crate) enum AuditAction {
+    /// A user signed in, or failed to, with their password.
+    Login,
+    /// A user ended their session.
+    Logout,
+    /// A user gave an MFA code while signing in.
+    Mfa,
+    /// A username, address or user's MFA codes were locked out after failed attempts.
+    LockedOut,
+    /// Tokens were issued to a client.
+    TokenIssued,
+    UserCreated,
+    UserUpdated,
+    UserDeleted,
+    /// A user changed their password.
+    PasswordChanged,
+    /// A user set a new password with a reset token.
+    PasswordReset,
+    /// A user that was locked out was unlocked by an admin.
+    UserUnlocked,
+    MfaEnabled,
+    MfaDisabled,
+    RecoveryCodesRegenerated,
+    ClientCreated,
+    ClientUpdated,
+    ClientSecretCreated,
+    ClientSecretRevoked,
+    /// A user revoked the access they granted a client.
+    GrantRevoked,
+    RoleAssigned,
+    RoleRemoved,
+}
+
+impl AuditAction {
+    /// The action in the database.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Login => "auth.login",
+            Self::Logout => "auth.logout",
+            Self::Mfa => "auth.mfa",
+            Self::LockedOut => "auth.locked_out",
+            Self::TokenIssued => "token.issued",
+            Self::UserCreated => "user.created",
+            Self::UserUpdated => "user.updated",
+            Self::UserDeleted => "user.deleted",
+            Self::PasswordChanged => "user.password_changed",
+            Self::PasswordReset => "user.password_reset",
+            Self::UserUnlocked => "user.unlocked",
+            Self::MfaEnabled => "user.mfa_enabled",
+            Self::MfaDisabled => "user.mfa_disabled",
+            Self::RecoveryCodesRegenerated => "user.recovery_codes_regenerated",
+            Self::ClientCreated => "client.created",
+            Self::ClientUpdated => "client.updated",
+            Self::ClientSecretCreated => "client.secret_created",
+            Self::ClientSecretRevoked => "client.secret_revoked",
+            Self::GrantRevoked => "grant.revoked",
+            Self::RoleAssigned => "role.assigned",
+            Self::RoleRemoved => "role.removed",
+        }
+    }
+}
+
+/// An event to record in the audit log.
+///
+/// ```ignore
+/// AuditEntry::new(AuditAction::UserDeleted)
+///     .request(&req)
+///     .target_user(user_id)
+///     .record()
+///     .await;
+/// ```
+#[derive(Debug, Clone)]
+#[must_use = "entries are only recorded with `record`"]
+pub(crate) struct AuditEntry(AuditEvent);
+
+impl AuditEntry {
+    pub(crate) fn new(action: AuditAction) -> Self {
+        Self(AuditEvent {
+            action: action.as_str().to_string(),
+            outcome: AuditOutcome::Success.as_str().to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Takes the address, user agent and, for authenticated requests, the actor from `req`.
+    pub(crate) fn request(mut self, req: &HttpRequest) -> Self {
+        self.0.ip = client_ip(req);
+        self.0.user_agent = req
+            .headers()
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|user_agent| user_agent.chars().take(MAX_USER_AGENT_LENGTH).collect());
+
+        match req.extensions().get::<Principal>() {
+            Some(Principal::User { user, .. }) => self.0.actor_user_ext_id = user.ext_id,
+            Some(Principal::Client(client)) => {
+                self.0.actor_client_id = Some(client.client_id.clone())
+            }
+            None => {}
+        }
+
+        self
+    }
+
+    /// Sets the user that did it, for requests that are not authenticated yet, like sign-ins.
+    pub(crate) fn actor_user(mut self, user: Option<Uuid>) -> Self {
+        self.0.actor_user_ext_id = user;
+        self
+    }
+
+    /// Sets the client that did it, or that it was done through.
+    pub(crate) fn actor_client(mut self, client_id: impl Into<String>) -> Self {
+        self.0.actor_client_id = Some(client_id.into());
+        self
+    }
+
+    /// Sets what it was done to.
+    pub(crate) fn target(mut self, target_type: &str, target_id: impl ToString) -> Self {
+        self.0.target_type = Some(target_type.to_string());
+        self.0.target_id = Some(target_id.to_string());
+        self
+    }
+
+    pub(crate) fn target_user(self, user: Uuid) -> Self {
+        self.target("user", user)
+    }
+
+    pub(crate) fn target_client(self, client_id: &str) -> Self {
+        self.target("client", client_id)
+    }
+
+    pub(crate) fn outcome(mut self, outcome: AuditOutcome) -> Self {
+        self.0.outcome = outcome.as_str().to_string();
+        self
+    }
+
+    /// Marks it as failed, for attempts that were rejected.
+    pub(crate) fn failure(self) -> Self {
+        self.outcome(AuditOutcome::Failure)
+    }
+
+    /// Adds details to the event, which has to be a JSON object.
+    ///
+    /// Never put secrets such as passwords, codes or tokens in the details.
+    pub(crate) fn details(mut self, details: serde_json::Value) -> Self {
+        self.0.details = Some(details.to_string());
+        self
+    }
+
+    /// Appends the event to the audit log.
+    ///
+    /// A failure to record is logged rather than returned, so a broken audit log does not take
+    /// sign-ins and the rest of the API down with it.
+    pub(crate) async fn record(self) {
+        if let Err(err) = AUDIT_EVENTS_REPOSITORY.append(self.0).await {
+            error!(error = %err, "failed to record an audit event");
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::error::ApiError;
 use crate::repositories::{DatabaseHealth, check_db_health};
+use crate::services::maintenance::{JobStatus, job_statuses};
 use crate::state::AppState;
 use crate::{ApiResult, dto};
 use actix_web::{HttpResponse, Responder};
@@ -22,6 +23,8 @@ dto! {
         process_stats: ProcessStats,
         /// Information about the database.
         database: DatabaseHealth,
+        /// Status of the background maintenance jobs on this replica.
+        maintenance: Vec<JobStatus>,
     }
 }
 
@@ -84,6 +87,7 @@ pub(crate) async fn check_health(state: &AppState) -> ApiResult<ServerHealth> {
         database: database_health,
         start_time: state.started_at().clone(),
         process_stats,
+        maintenance: job_statuses(),
     };
 
     Ok(response)
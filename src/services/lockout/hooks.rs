@@ -0,0 +1,121 @@
+//! Hooks told about lockouts, which ones run is configured with `LOCKOUT_HOOKS`:
+//!
+//! * `log` logs a warning.
+//! * `mail` mails the user whose username or MFA codes were locked out. Password reset requests
+//!   that were cut off are not mailed about, that would only add to the mails.
+
+use super::{AttemptKey, AttemptKind};
+use crate::ApiResult;
+use crate::models::user::User;
+use crate::repositories::users::USERS_REPOSITORY;
+use crate::services::mail::{self, Mail};
+use crate::statics::LOCKOUT_HOOKS;
+use chrono::NaiveDateTime;
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use sqlx_utils::traits::Repository;
+use std::fmt::Debug;
+use std::sync::LazyLock;
+use tracing::warn;
+use uuid::Uuid;
+
+/// A key that was locked out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Lockout {
+    pub(crate) key: AttemptKey,
+    /// Failures that led to the lockout.
+    pub(crate) failures: i32,
+    pub(crate) locked_until: NaiveDateTime,
+}
+
+/// Reacts to lockouts.
+pub(crate) trait LockoutHook: Debug + Send + Sync {
+    fn notify<'a>(&'a self, lockout: &'a Lockout) -> BoxFuture<'a, ApiResult<()>>;
+}
+
+/// Logs lockouts.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct LogHook;
+
+impl LockoutHook for LogHook {
+    fn notify<'a>(&'a self, lockout: &'a Lockout) -> BoxFuture<'a, ApiResult<()>> {
+        warn!(
+            kind = lockout.key.kind.as_str(),
+            key = %lockout.key.key,
+            failures = lockout.failures,
+            locked_until = %lockout.locked_until,
+            "locked out after too many attempts"
+        );
+
+        async { Ok(()) }.boxed()
+    }
+}
+
+/// Mails users that were locked out, so they learn someone is guessing their password.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct MailHook;
+
+impl LockoutHook for MailHook {
+    fn notify<'a>(&'a self, lockout: &'a Lockout) -> BoxFuture<'a, ApiResult<()>> {
+        async move {
+            let user: Option<User> = match lockout.key.kind {
+                AttemptKind::Username => USERS_REPOSITORY.find_by_username(&lockout.key.key).await?,
+                AttemptKind::Mfa => match lockout.key.key.parse::<Uuid>() {
+                    Ok(user) => USERS_REPOSITORY.get_by_id(user).await?,
+                    Err(_) => None,
+                },
+                AttemptKind::Ip | AttemptKind::ResetEmail | AttemptKind::ResetIp => None,
+            };
+
+            let Some(user) = user else {
+                return Ok(());
+            };
+
+            let what = match lockout.key.kind {
+                AttemptKind::Mfa => "two-factor codes",
+                _ => "passwords",
+            };
+
+            mail::send(&Mail {
+                to: user.email,
+                subject: "Your account was locked".to_string(),
+                body: format!(
+                    "Hi {},\n\nSigning in to your account was locked after {} wrong {what}. You can sign in again after {} UTC.\n\nIf this was not you, someone may be trying to guess your password. Consider changing it.",
+                    user.username, lockout.failures, lockout.locked_until
+                ),
+            })
+            .await
+        }
+        .boxed()
+    }
+}
+
+/// The hooks configured with `LOCKOUT_HOOKS`.
+static HOOKS: LazyLock<Vec<Box<dyn LockoutHook>>> = LazyLock::new(|| {
+    LOCKOUT_HOOKS
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| -> Option<Box<dyn LockoutHook>> {
+            match name {
+                "log" => Some(Box::new(LogHook)),
+                "mail" => Some(Box::new(MailHook)),
+                other => {
+                    warn!("unknown lockout hook '{other}', ignoring it");
+                    None
+                }
+            }
+        })
+        .collect()
+});
+
+/// Tells every hook about `lockout` in the background, so responses do not wait on them.
+pub(super) fn notify(lockout: Lockout) {
+    tokio::spawn(async move {
+        for hook in HOOKS.iter() {
+            if let Err(error) = hook.notify(&lockout).await {
+                warn!(%error, ?hook, "lockout hook failed");
+            }
+        }
+    });
+}
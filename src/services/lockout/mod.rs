@@ -0,0 +1,361 @@
+//! Protection against guessing passwords and MFA codes.
+//!
+//! Failed sign-ins are counted per username and per client address, failed MFA codes per user.
+//! Every failure delays the next attempt for the same key a bit longer, and once a key reaches
+//! its threshold it is locked out for `LOGIN_LOCKOUT_DURATION` seconds. Locked out attempts fail
+//! like wrong credentials do and after the same work, so responses reveal neither whether a user
+//! exists nor whether they are locked out. Lockouts are reported to the [`hooks`].
+//!
+//! Password reset requests are counted the same way per email and per client address, see
+//! [`allow_reset_request`], so the reset endpoint cannot be used to flood inboxes.
+
+mod hooks;
+
+use hooks::Lockout;
+
+use crate::ApiResult;
+use crate::models::user::User;
+use crate::repositories::login_attempts::LOGIN_ATTEMPTS_REPOSITORY;
+use crate::repositories::users::USERS_REPOSITORY;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::services::mfa;
+use crate::statics::{
+    LOGIN_DELAY_BASE_MS, LOGIN_DELAY_MAX_MS, LOGIN_FAILURE_WINDOW, LOGIN_LOCKOUT_DURATION,
+    LOGIN_MAX_FAILURES, LOGIN_MAX_FAILURES_PER_IP, PASSWORD_RESET_MAX_PER_EMAIL,
+    PASSWORD_RESET_MAX_PER_IP, TRUST_FORWARDED_FOR,
+};
+use crate::utils::hashing::verify_dummy;
+use actix_web::HttpRequest;
+use serde_json::json;
+use std::net::SocketAddr;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// What failures are counted for.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub(crate) enum AttemptKind {
+    /// The username or email a sign-in was attempted with.
+    Username,
+    /// The address a sign-in was attempted from.
+    Ip,
+    /// The user an MFA code was given for.
+    Mfa,
+    /// The email a password reset was requested for.
+    ResetEmail,
+    /// The address a password reset was requested from.
+    ResetIp,
+}
+
+impl AttemptKind {
+    /// The kind in the database.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Username => "username",
+            Self::Ip => "ip",
+            Self::Mfa => "mfa",
+            Self::ResetEmail => "reset_email",
+            Self::ResetIp => "reset_ip",
+        }
+    }
+
+    fn max_failures(&self) -> i32 {
+        match self {
+            Self::Ip => *LOGIN_MAX_FAILURES_PER_IP,
+            Self::Username | Self::Mfa => *LOGIN_MAX_FAILURES,
+            Self::ResetEmail => *PASSWORD_RESET_MAX_PER_EMAIL,
+            Self::ResetIp => *PASSWORD_RESET_MAX_PER_IP,
+        }
+    }
+}
+
+/// Something failures are counted for.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub(crate) struct AttemptKey {
+    pub(crate) kind: AttemptKind,
+    pub(crate) key: String,
+}
+
+impl AttemptKey {
+    pub(crate) fn username(username: &str) -> Self {
+        Self {
+            kind: AttemptKind::Username,
+            key: username.trim().to_string(),
+        }
+    }
+
+    pub(crate) fn ip(ip: String) -> Self {
+        Self {
+            kind: AttemptKind::Ip,
+            key: ip,
+        }
+    }
+
+    pub(crate) fn mfa(user: Uuid) -> Self {
+        Self {
+            kind: AttemptKind::Mfa,
+            key: user.to_string(),
+        }
+    }
+
+    pub(crate) fn reset_email(email: &str) -> Self {
+        Self {
+            kind: AttemptKind::ResetEmail,
+            key: email.trim().to_lowercase(),
+        }
+    }
+
+    pub(crate) fn reset_ip(ip: String) -> Self {
+        Self {
+            kind: AttemptKind::ResetIp,
+            key: ip,
+        }
+    }
+}
+
+/// The address `req` was made from.
+///
+/// Forwarding headers are only trusted with `TRUST_FORWARDED_FOR`, otherwise anyone could make
+/// their attempts count against someone else's address.
+pub(crate) fn client_ip(req: &HttpRequest) -> Option<String> {
+    if !*TRUST_FORWARDED_FOR {
+        return req.peer_addr().map(|addr| addr.ip().to_string());
+    }
+
+    let info = req.connection_info();
+    let addr = info.realip_remote_addr()?;
+
+    Some(
+        addr.parse::<SocketAddr>()
+            .map(|addr| addr.ip().to_string())
+            .unwrap_or_else(|_| addr.to_string()),
+    )
+}
+
+/// Checks a username and password, slowing down and locking out guessing.
+///
+/// Takes the same time whether or not the user exists, and a locked out username or address
+/// fails like a wrong password. Every attempt is recorded in the audit log.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn authenticate(
+    username: &str,
+    password: impl AsRef<[u8]>,
+    req: &HttpRequest,
+) -> ApiResult<Option<User>> {
+    let keys: Vec<_> = [
+        Some(AttemptKey::username(username)),
+        client_ip(req).map(AttemptKey::ip),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let entry = AuditEntry::new(AuditAction::Login).request(req);
+
+    if !throttle(&keys).await? {
+        verify_dummy(&password);
+        entry
+            .failure()
+            .details(json!({ "username": username, "reason": "locked_out" }))
+            .record()
+            .await;
+        return Ok(None);
+    }
+
+    match USERS_REPOSITORY.authenticate(username, password).await? {
+        Some(user) => {
+            record_success(&keys).await?;
+            entry.actor_user(user.ext_id).record().await;
+            Ok(Some(user))
+        }
+        None => {
+            record_failure(&keys, req).await?;
+            entry
+                .failure()
+                .details(json!({ "username": username, "reason": "invalid_credentials" }))
+                .record()
+                .await;
+            Ok(None)
+        }
+    }
+}
+
+/// Checks an MFA code of `user` like [`mfa::verify`], slowing down and locking out guessing.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn verify_mfa(user: Uuid, code: &str, req: &HttpRequest) -> ApiResult<bool> {
+    let keys: Vec<_> = [
+        Some(AttemptKey::mfa(user)),
+        client_ip(req).map(AttemptKey::ip),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let entry = AuditEntry::new(AuditAction::Mfa)
+        .request(req)
+        .actor_user(Some(user));
+
+    if !throttle(&keys).await? {
+        entry
+            .failure()
+            .details(json!({ "reason": "locked_out" }))
+            .record()
+            .await;
+        return Ok(false);
+    }
+
+    if mfa::verify(user, code).await? {
+        record_success(&keys).await?;
+        entry.record().await;
+        Ok(true)
+    } else {
+        record_failure(&keys, req).await?;
+        entry
+            .failure()
+            .details(json!({ "reason": "invalid_code" }))
+            .record()
+            .await;
+        Ok(false)
+    }
+}
+
+/// Counts a password reset request for `email` with the request `req`, returns whether a reset
+/// mail may be sent.
+///
+/// Every request counts, not just failed ones, as each can send a mail. Once the email or the
+/// address reached its threshold, requests are refused until the lockout ends. The caller
+/// responds the same either way, so this reveals nothing about the email.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn allow_reset_request(email: &str, req: &HttpRequest) -> ApiResult<bool> {
+    let keys: Vec<_> = [
+        Some(AttemptKey::reset_email(email)),
+        client_ip(req).map(AttemptKey::reset_ip),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    for key in &keys {
+        let attempt = LOGIN_ATTEMPTS_REPOSITORY
+            .get(key.kind.as_str(), &key.key, *LOGIN_FAILURE_WINDOW as f64)
+            .await?;
+
+        if attempt.is_some_and(|attempt| attempt.locked_until.is_some()) {
+            return Ok(false);
+        }
+    }
+
+    record_failure(&keys, req).await?;
+
+    Ok(true)
+}
+
+/// Waits out the delay `keys` earned with earlier failures.
+///
+/// Returns `false` right away if any of them is locked out.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn throttle(keys: &[AttemptKey]) -> ApiResult<bool> {
+    let mut failures = 0;
+
+    for key in keys {
+        let Some(attempt) = LOGIN_ATTEMPTS_REPOSITORY
+            .get(key.kind.as_str(), &key.key, *LOGIN_FAILURE_WINDOW as f64)
+            .await?
+        else {
+            continue;
+        };
+
+        if attempt.locked_until.is_some() {
+            return Ok(false);
+        }
+
+        failures = failures.max(attempt.failures);
+    }
+
+    if failures > 0 {
+        tokio::time::sleep(delay(failures)).await;
+    }
+
+    Ok(true)
+}
+
+/// Counts a failure for each of `keys` of an attempt with the request `req`, locking out the
+/// ones that reached their threshold.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn record_failure(keys: &[AttemptKey], req: &HttpRequest) -> ApiResult<()> {
+    for key in keys {
+        let attempt = LOGIN_ATTEMPTS_REPOSITORY
+            .record_failure(key.kind.as_str(), &key.key, *LOGIN_FAILURE_WINDOW as f64)
+            .await?;
+
+        if attempt.failures < key.kind.max_failures() {
+            continue;
+        }
+
+        if let Some(locked_until) = LOGIN_ATTEMPTS_REPOSITORY
+            .lock(key.kind.as_str(), &key.key, *LOGIN_LOCKOUT_DURATION as f64)
+            .await?
+        {
+            AuditEntry::new(AuditAction::LockedOut)
+                .request(req)
+                .target(key.kind.as_str(), &key.key)
+                .details(json!({ "failures": attempt.failures, "locked_until": locked_until }))
+                .record()
+                .await;
+
+            hooks::notify(Lockout {
+                key: key.clone(),
+                failures: attempt.failures,
+                locked_until,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Forgets the failures of `keys` after a successful attempt.
+///
+/// Addresses keep their failures, otherwise signing in to one account would let an address
+/// keep guessing the passwords of others.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn record_success(keys: &[AttemptKey]) -> ApiResult<()> {
+    for key in keys.iter().filter(|key| key.kind != AttemptKind::Ip) {
+        LOGIN_ATTEMPTS_REPOSITORY
+            .clear(key.kind.as_str(), std::slice::from_ref(&key.key))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Lifts the lockout of `user` and forgets their failures, for their username, email and MFA
+/// codes.
+///
+/// Returns whether anything was cleared.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn unlock_user(user: &User) -> ApiResult<bool> {
+    let usernames = [user.username.clone(), user.email.clone()];
+    let mut cleared = LOGIN_ATTEMPTS_REPOSITORY
+        .clear(AttemptKind::Username.as_str(), &usernames)
+        .await?;
+
+    if let Some(ext_id) = user.ext_id {
+        cleared += LOGIN_ATTEMPTS_REPOSITORY
+            .clear(AttemptKind::Mfa.as_str(), &[ext_id.to_string()])
+            .await?;
+    }
+
+    Ok(cleared > 0)
+}
+
+/// Delay before an attempt for a key with `failures` failures, doubling with every failure.
+fn delay(failures: i32) -> Duration {
+    let doublings = u32::try_from(failures.saturating_sub(1))
+        .unwrap_or(0)
+        .min(16);
+    let millis = LOGIN_DELAY_BASE_MS
+        .saturating_mul(1 << doublings)
+        .min(*LOGIN_DELAY_MAX_MS);
+
+    Duration::from_millis(millis)
+}
@@ -0,0 +1,72 @@
+use crate::ApiResult;
+use crate::error::ApiError;
+use crate::services::mail::{Mail, MailTransport};
+use crate::statics::{MAIL_DIR, MAIL_FROM};
+use chrono::Utc;
+use futures::future::BoxFuture;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+/// Writes mail to files in a directory, or prints it when it has none.
+#[derive(Debug, Clone)]
+pub(crate) struct FileTransport {
+    dir: Option<PathBuf>,
+}
+
+impl FileTransport {
+    /// Writes to `MAIL_DIR`.
+    pub(crate) fn from_env() -> Self {
+        Self {
+            dir: Some(PathBuf::from(MAIL_DIR.as_str())),
+        }
+    }
+
+    /// Prints mail to stdout.
+    pub(crate) fn stdout() -> Self {
+        Self { dir: None }
+    }
+
+    fn format(mail: &Mail) -> String {
+        format!(
+            "From: {}\nTo: {}\nDate: {}\nSubject: {}\n\n{}\n",
+            *MAIL_FROM,
+            mail.to,
+            Utc::now().to_rfc2822(),
+            mail.subject,
+            mail.body
+        )
+    }
+}
+
+impl MailTransport for FileTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> BoxFuture<'a, ApiResult<()>> {
+        Box::pin(async move {
+            let contents = Self::format(mail);
+
+            let Some(dir) = &self.dir else {
+                let mut stdout = tokio::io::stdout();
+                stdout
+                    .write_all(contents.as_bytes())
+                    .await
+                    .map_err(mail_error)?;
+                stdout.flush().await.map_err(mail_error)?;
+
+                return Ok(());
+            };
+
+            tokio::fs::create_dir_all(dir).await.map_err(mail_error)?;
+
+            let path = dir.join(format!(
+                "{}-{}.eml",
+                Utc::now().format("%Y%m%dT%H%M%S"),
+                Uuid::new_v4()
+            ));
+            tokio::fs::write(path, contents).await.map_err(mail_error)
+        })
+    }
+}
+
+fn mail_error(error: std::io::Error) -> ApiError {
+    ApiError::Mail(error.to_string())
+}
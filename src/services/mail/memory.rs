@@ -0,0 +1,40 @@
+use crate::ApiResult;
+use crate::services::mail::{Mail, MailTransport};
+use futures::future::BoxFuture;
+use std::sync::Mutex;
+
+/// Keeps mail in memory instead of sending it, so tests can check what was sent.
+#[derive(Debug, Default)]
+pub(crate) struct MemoryTransport {
+    sent: Mutex<Vec<Mail>>,
+}
+
+// Only tests read the mail back.
+#[cfg(test)]
+impl MemoryTransport {
+    /// The mail sent so far, oldest first.
+    pub(crate) fn sent(&self) -> Vec<Mail> {
+        self.sent
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone()
+    }
+
+    /// Removes and returns the mail sent so far.
+    pub(crate) fn take(&self) -> Vec<Mail> {
+        std::mem::take(&mut *self.sent.lock().unwrap_or_else(|error| error.into_inner()))
+    }
+}
+
+impl MailTransport for MemoryTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> BoxFuture<'a, ApiResult<()>> {
+        Box::pin(async move {
+            self.sent
+                .lock()
+                .unwrap_or_else(|error| error.into_inner())
+                .push(mail.clone());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,62 @@
+//! Sending mail to users, such as the email verification mail.
+//!
+//! Mail goes through a [`MailTransport`], which one is used is configured with `MAIL_TRANSPORT`:
+//!
+//! * `smtp` sends it through the SMTP server at `SMTP_URL`.
+//! * `file` writes every mail to a file in `MAIL_DIR`.
+//! * `stdout` prints it, the default so development works without a mail server.
+//! * `memory` keeps it in memory, for tests.
+
+mod file;
+mod memory;
+mod smtp;
+
+pub(crate) use file::FileTransport;
+pub(crate) use memory::MemoryTransport;
+pub(crate) use smtp::SmtpTransport;
+
+use crate::ApiResult;
+use crate::statics::MAIL_TRANSPORT;
+use futures::future::BoxFuture;
+use std::fmt::Debug;
+use std::sync::LazyLock;
+use tracing::warn;
+
+/// A mail to a single recipient.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Mail {
+    pub(crate) to: String,
+    pub(crate) subject: String,
+    /// Plain text body.
+    pub(crate) body: String,
+}
+
+/// Delivers mail.
+pub(crate) trait MailTransport: Debug + Send + Sync {
+    fn send<'a>(&'a self, mail: &'a Mail) -> BoxFuture<'a, ApiResult<()>>;
+}
+
+/// The transport configured with `MAIL_TRANSPORT`.
+static TRANSPORT: LazyLock<Box<dyn MailTransport>> =
+    LazyLock::new(|| match MAIL_TRANSPORT.as_str() {
+        "smtp" => match SmtpTransport::from_env() {
+            Ok(transport) => Box::new(transport),
+            Err(error) => {
+                warn!(%error, "failed to set up the SMTP transport, printing mail instead");
+                Box::new(FileTransport::stdout())
+            }
+        },
+        "file" => Box::new(FileTransport::from_env()),
+        "memory" => Box::new(MemoryTransport::default()),
+        "stdout" => Box::new(FileTransport::stdout()),
+        other => {
+            warn!("unknown mail transport '{other}', printing mail instead");
+            Box::new(FileTransport::stdout())
+        }
+    });
+
+/// Sends `mail` through the configured transport.
+#[tracing::instrument(skip_all, fields(to = %mail.to))]
+pub(crate) async fn send(mail: &Mail) -> ApiResult<()> {
+    TRANSPORT.send(mail).await
+}
@@ -0,0 +1,54 @@
+use crate::ApiResult;
+use crate::error::ApiError;
+use crate::services::mail::{Mail, MailTransport};
+use crate::statics::{MAIL_FROM, SMTP_URL};
+use futures::future::BoxFuture;
+use lettre::message::Mailbox;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+/// Sends mail through an SMTP server.
+#[derive(Debug, Clone)]
+pub(crate) struct SmtpTransport {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpTransport {
+    /// Connects to the server at `SMTP_URL` and sends from `MAIL_FROM`.
+    pub(crate) fn from_env() -> ApiResult<Self> {
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::from_url(&SMTP_URL)
+            .map_err(|error| ApiError::Mail(error.to_string()))?
+            .build();
+        let from = MAIL_FROM
+            .parse()
+            .map_err(|error: lettre::address::AddressError| ApiError::Mail(error.to_string()))?;
+
+        Ok(Self { transport, from })
+    }
+}
+
+impl MailTransport for SmtpTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> BoxFuture<'a, ApiResult<()>> {
+        Box::pin(async move {
+            let to = mail
+                .to
+                .parse()
+                .map_err(|error: lettre::address::AddressError| {
+                    ApiError::Mail(error.to_string())
+                })?;
+            let message = Message::builder()
+                .from(self.from.clone())
+                .to(to)
+                .subject(&mail.subject)
+                .body(mail.body.clone())
+                .map_err(|error| ApiError::Mail(error.to_string()))?;
+
+            self.transport
+                .send(message)
+                .await
+                .map_err(|error| ApiError::Mail(error.to_string()))?;
+
+            Ok(())
+        })
+    }
+}
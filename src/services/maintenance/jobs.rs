@@ -0,0 +1,95 @@
+//! The maintenance jobs, each removes data the server no longer needs.
+
+use crate::ApiResult;
+use crate::repositories::login_attempts::LOGIN_ATTEMPTS_REPOSITORY;
+use crate::repositories::oauth_auth_code::OAUTH_AUTH_CODE_REPOSITORY;
+use crate::repositories::oauth_token::OAUTH_TOKEN_REPOSITORY;
+use crate::repositories::oidc_login_states::OIDC_LOGIN_STATES_REPOSITORY;
+use crate::repositories::password_resets::PASSWORD_RESETS_REPOSITORY;
+use crate::repositories::sessions::SESSIONS_REPOSITORY;
+use crate::repositories::user_consent::USER_CONSENT_REPOSITORY;
+use crate::services::session::idle_timeout;
+use crate::statics::{
+    AUTH_CODE_PURGE_INTERVAL, CONSENT_CLEANUP_INTERVAL, CONSENT_MAX_AGE,
+    LOGIN_ATTEMPTS_PURGE_INTERVAL, LOGIN_FAILURE_WINDOW, PASSWORD_RESET_PURGE_INTERVAL,
+    SESSION_CLEANUP_INTERVAL, TOKEN_PURGE_INTERVAL,
+};
+use chrono::TimeDelta;
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use std::sync::LazyLock;
+
+/// Advisory locks of the jobs are `LOCK_NAMESPACE + <index of the job>`, so they do not collide
+/// with locks taken for anything else.
+pub(super) const LOCK_NAMESPACE: i64 = 0x6665_7272_6963_0000;
+
+/// A job that runs on one replica at a time, every `interval` seconds.
+pub(super) struct Job {
+    pub(super) name: &'static str,
+    pub(super) interval: &'static LazyLock<u64>,
+    /// Runs the job once, returns how many rows it removed.
+    pub(super) run: fn() -> BoxFuture<'static, ApiResult<u64>>,
+}
+
+pub(super) static JOBS: [Job; 6] = [
+    Job {
+        name: "purge_tokens",
+        interval: &TOKEN_PURGE_INTERVAL,
+        run: || OAUTH_TOKEN_REPOSITORY.purge_expired().boxed(),
+    },
+    Job {
+        name: "purge_auth_codes",
+        interval: &AUTH_CODE_PURGE_INTERVAL,
+        run: || OAUTH_AUTH_CODE_REPOSITORY.purge_expired().boxed(),
+    },
+    Job {
+        name: "cleanup_consent",
+        interval: &CONSENT_CLEANUP_INTERVAL,
+        run: cleanup_consent,
+    },
+    Job {
+        name: "cleanup_sessions",
+        interval: &SESSION_CLEANUP_INTERVAL,
+        run: cleanup_sessions,
+    },
+    Job {
+        name: "purge_password_resets",
+        interval: &PASSWORD_RESET_PURGE_INTERVAL,
+        run: || PASSWORD_RESETS_REPOSITORY.purge_expired().boxed(),
+    },
+    Job {
+        name: "purge_login_attempts",
+        interval: &LOGIN_ATTEMPTS_PURGE_INTERVAL,
+        run: || {
+            LOGIN_ATTEMPTS_REPOSITORY
+                .purge_stale(*LOGIN_FAILURE_WINDOW as f64)
+                .boxed()
+        },
+    },
+];
+
+/// Removes consent the client has not had a token for in `CONSENT_MAX_AGE` seconds.
+fn cleanup_consent() -> BoxFuture<'static, ApiResult<u64>> {
+    async {
+        let max_age = (*CONSENT_MAX_AGE)
+            .try_into()
+            .ok()
+            .and_then(TimeDelta::try_seconds)
+            .unwrap_or(TimeDelta::MAX);
+
+        USER_CONSENT_REPOSITORY.purge_unused(max_age).await
+    }
+    .boxed()
+}
+
+/// Removes browser sessions that timed out and logins through upstream providers that were
+/// never finished.
+fn cleanup_sessions() -> BoxFuture<'static, ApiResult<u64>> {
+    async {
+        let sessions = SESSIONS_REPOSITORY.purge_expired(idle_timeout()).await?;
+        let logins = OIDC_LOGIN_STATES_REPOSITORY.purge_expired().await?;
+
+        Ok(sessions + logins)
+    }
+    .boxed()
+}
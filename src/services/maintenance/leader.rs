@@ -0,0 +1,69 @@
+//! Leader election between replicas with Postgres advisory locks.
+//!
+//! The replica that holds the advisory lock of a job is its leader and the only one running it.
+//! Locks are held on a connection of their own, outside the pool, and are released by Postgres
+//! when that connection closes, so another replica takes over if the leader goes away.
+
+use crate::ApiResult;
+use crate::statics::DATABASE_URL;
+use sqlx::{Connection, PgConnection, query_scalar};
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use tokio::sync::Mutex;
+
+static LEADERSHIP: LazyLock<Mutex<Leadership>> =
+    LazyLock::new(|| Mutex::new(Leadership::default()));
+
+#[derive(Default)]
+struct Leadership {
+    connection: Option<PgConnection>,
+    /// Keys of the locks held on `connection`.
+    held: HashSet<i64>,
+}
+
+impl Leadership {
+    async fn try_lead(&mut self, key: i64) -> ApiResult<bool> {
+        let connection = match &mut self.connection {
+            Some(connection) => connection,
+            None => {
+                self.held.clear();
+                self.connection
+                    .insert(PgConnection::connect(&DATABASE_URL).await?)
+            }
+        };
+
+        let result = if self.held.contains(&key) {
+            // The lock lives as long as the connection, which has to still be open.
+            connection.ping().await.map(|_| true)
+        } else {
+            query_scalar!(r#"SELECT pg_try_advisory_lock($1) as "locked!""#, key)
+                .fetch_one(&mut *connection)
+                .await
+        };
+
+        match result {
+            Ok(locked) => {
+                if locked {
+                    self.held.insert(key);
+                }
+
+                Ok(locked)
+            }
+            Err(error) => {
+                // Postgres releases the locks once the connection is gone, start over.
+                self.connection = None;
+                self.held.clear();
+
+                Err(error.into())
+            }
+        }
+    }
+}
+
+/// Tries to become the leader of the job with the lock `key`.
+///
+/// Returns `true` if this replica is the leader, either because it took the lock now or
+/// already held it.
+pub(super) async fn try_lead(key: i64) -> ApiResult<bool> {
+    LEADERSHIP.lock().await.try_lead(key).await
+}
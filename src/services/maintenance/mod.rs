@@ -0,0 +1,168 @@
+//! Background jobs that keep the database free of data the server no longer needs.
+//!
+//! Every job runs on its own task, every replica schedules all of them but only the replica that
+//! holds the advisory lock of a job runs it, see [`leader`]. Jobs wait for the database to be
+//! prepared, then run after a random delay of up to `MAINTENANCE_JITTER` seconds and again
+//! every interval plus such a delay.
+//!
+//! The outcome of the last run of each job on this replica is reported by `/health`.
+
+mod jobs;
+mod leader;
+
+use crate::statics::{MAINTENANCE_ENABLED, MAINTENANCE_JITTER};
+use chrono::Utc;
+use jobs::{JOBS, Job, LOCK_NAMESPACE};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tracing::{debug, error, info};
+use utoipa::{ToResponse, ToSchema};
+
+static DATABASE_READY: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::Sender::new(false));
+
+static STATUS: LazyLock<Mutex<BTreeMap<&'static str, JobStatus>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+/// Status of a maintenance job on this replica.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+pub struct JobStatus {
+    /// Name of the job.
+    pub name: String,
+    /// Seconds between runs, not counting the jitter.
+    pub interval_seconds: u64,
+    /// Whether this replica runs the job.
+    pub leader: bool,
+    /// The last run on this replica, if it has run the job.
+    pub last_run: Option<JobRun>,
+}
+
+/// Outcome of a run of a maintenance job.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+pub struct JobRun {
+    /// When the run started.
+    pub started_at: chrono::DateTime<Utc>,
+    /// How long the run took in milliseconds.
+    pub duration_ms: u64,
+    /// How many rows were removed.
+    pub rows_affected: u64,
+    /// Why the run failed, if it did.
+    pub error: Option<String>,
+}
+
+/// Starts the maintenance jobs, unless `MAINTENANCE_ENABLED` is turned off.
+///
+/// Jobs do not run before [`database_ready`] is called.
+pub fn start() {
+    if !*MAINTENANCE_ENABLED {
+        info!("Maintenance jobs are disabled");
+        return;
+    }
+
+    for (index, job) in JOBS.iter().enumerate() {
+        set_status(job, false, None);
+        tokio::spawn(schedule(job, LOCK_NAMESPACE + index as i64));
+    }
+}
+
+/// Lets the maintenance jobs start running, called once the database is prepared.
+pub fn database_ready() {
+    DATABASE_READY.send_replace(true);
+}
+
+/// Status of every maintenance job on this replica, ordered by name.
+pub(crate) fn job_statuses() -> Vec<JobStatus> {
+    STATUS
+        .lock()
+        .map(|status| status.values().cloned().collect())
+        .unwrap_or_default()
+}
+
+async fn schedule(job: &'static Job, lock_key: i64) {
+    let mut ready = DATABASE_READY.subscribe();
+
+    if ready.wait_for(|ready| *ready).await.is_err() {
+        return;
+    }
+
+    tokio::time::sleep(jitter()).await;
+
+    loop {
+        run(job, lock_key).await;
+
+        tokio::time::sleep(Duration::from_secs(**job.interval) + jitter()).await;
+    }
+}
+
+#[tracing::instrument(skip_all, fields(job = job.name))]
+async fn run(job: &Job, lock_key: i64) {
+    let leader = match leader::try_lead(lock_key).await {
+        Ok(leader) => leader,
+        Err(error) => {
+            error!(error = ?error, "Failed to take part in leader election");
+            false
+        }
+    };
+
+    if !leader {
+        debug!("Another replica runs the job");
+        set_status(job, false, None);
+        return;
+    }
+
+    let started_at = Utc::now();
+    let start = Instant::now();
+    let result = (job.run)().await;
+    let duration_ms = start.elapsed().as_millis().try_into().unwrap_or(u64::MAX);
+
+    let run = match result {
+        Ok(rows_affected) => {
+            info!(rows_affected, duration_ms, "Maintenance job finished");
+
+            JobRun {
+                started_at,
+                duration_ms,
+                rows_affected,
+                error: None,
+            }
+        }
+        Err(error) => {
+            error!(error = ?error, duration_ms, "Maintenance job failed");
+
+            JobRun {
+                started_at,
+                duration_ms,
+                rows_affected: 0,
+                error: Some(error.to_string()),
+            }
+        }
+    };
+
+    set_status(job, true, Some(run));
+}
+
+fn set_status(job: &Job, leader: bool, run: Option<JobRun>) {
+    let Ok(mut status) = STATUS.lock() else {
+        return;
+    };
+
+    let status = status.entry(job.name).or_insert_with(|| JobStatus {
+        name: job.name.to_string(),
+        interval_seconds: **job.interval,
+        leader,
+        last_run: None,
+    });
+
+    status.leader = leader;
+
+    if run.is_some() {
+        status.last_run = run;
+    }
+}
+
+fn jitter() -> Duration {
+    Duration::from_secs(rand::thread_rng().gen_range(0..=*MAINTENANCE_JITTER))
+}
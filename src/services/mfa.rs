@@ -0,0 +1,314 @@
+//! Multi-factor authentication with TOTP (RFC 6238) authenticators.
+//!
+//! Users enroll an authenticator, confirm it with a first code and get single use recovery codes
+//! for when they lose it. Once confirmed, signing in with a password takes a code as well: the
+//! password grant fails with `mfa_required` and an MFA token that is exchanged together with a
+//! code, and the login page of the authorization endpoint asks for a code on a second page.
+//!
+//! The shared secrets are encrypted at rest, recovery codes are only stored as digests.
+
+use crate::ApiResult;
+use crate::error::ApiError;
+use crate::models::mfa_recovery_code::MfaRecoveryCode;
+use crate::models::user::User;
+use crate::models::user_totp::UserTotp;
+use crate::repositories::mfa_recovery_codes::MFA_RECOVERY_CODES_REPOSITORY;
+use crate::repositories::user_totp::USER_TOTP_REPOSITORY;
+use crate::statics::{MFA_ISSUER, MFA_TOKEN_TTL};
+use crate::utils::encryption::{decrypt, encrypt};
+use crate::utils::signing;
+use chrono::{DateTime, TimeDelta, Utc};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::distributions::{Alphanumeric, DistString};
+use sha1::Sha1;
+use sqlx_utils::traits::Repository;
+use uuid::Uuid;
+
+type HmacSha1 = Hmac<Sha1>;
+
+const PURPOSE: &str = "mfa";
+/// Seconds a code is valid for.
+const PERIOD: i64 = 30;
+const DIGITS: u32 = 6;
+/// Steps of clock drift between the server and authenticators that are tolerated each way.
+const SKEW: i64 = 1;
+/// Length of the shared secrets in bytes, the length of an HMAC-SHA1 key.
+const SECRET_LENGTH: usize = 20;
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+/// A secret for a new authenticator.
+pub(crate) struct TotpEnrollment {
+    /// The secret, base32 encoded for entering it by hand.
+    pub(crate) secret: String,
+    /// `otpauth://` URI of the secret, shown as a QR code for authenticator apps to scan.
+    pub(crate) provisioning_uri: String,
+}
+
+/// Whether `user` has a confirmed authenticator and has to give a code when signing in.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn is_enrolled(user: Uuid) -> ApiResult<bool> {
+    Ok(USER_TOTP_REPOSITORY
+        .get_by_user(user)
+        .await?
+        .is_some_and(|totp| totp.is_confirmed()))
+}
+
+/// Creates a new authenticator secret for `user`, replacing any that was not confirmed yet.
+///
+/// Fails with [`ApiError::BadRequest`] if the user already has a confirmed authenticator.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn enroll(user: &User) -> ApiResult<TotpEnrollment> {
+    let user_ext_id = user.ext_id.ok_or(ApiError::InternalError)?;
+
+    if is_enrolled(user_ext_id).await? {
+        return Err(ApiError::BadRequest(
+            "An authenticator is already set up, remove it first".into(),
+        ));
+    }
+
+    let secret = rand::random::<[u8; SECRET_LENGTH]>();
+
+    USER_TOTP_REPOSITORY
+        .insert(&UserTotp::new(user_ext_id, encrypt(&secret)?))
+        .await?;
+
+    Ok(TotpEnrollment {
+        secret: BASE32_NOPAD.encode(&secret),
+        provisioning_uri: provisioning_uri(&user.username, &secret),
+    })
+}
+
+/// Confirms the authenticator of `user` with a code from it.
+///
+/// Returns the new recovery codes, or `None` if the code is wrong or the user has no
+/// authenticator waiting to be confirmed.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn confirm(user: Uuid, code: &str) -> ApiResult<Option<Vec<String>>> {
+    let Some(totp) = USER_TOTP_REPOSITORY.get_by_user(user).await? else {
+        return Ok(None);
+    };
+    if totp.is_confirmed() {
+        return Ok(None);
+    }
+
+    let secret = decrypt(&totp.secret_ciphertext)?;
+    let Some(step) = matching_step(&secret, code) else {
+        return Ok(None);
+    };
+
+    if !USER_TOTP_REPOSITORY.confirm(user, step).await? {
+        return Ok(None);
+    }
+
+    regenerate_recovery_codes(user).await.map(Some)
+}
+
+/// Checks a code from the confirmed authenticator of `user`, or one of their recovery codes.
+///
+/// Every code is accepted once, recovery codes are used up.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn verify(user: Uuid, code: &str) -> ApiResult<bool> {
+    let Some(totp) = USER_TOTP_REPOSITORY
+        .get_by_user(user)
+        .await?
+        .filter(UserTotp::is_confirmed)
+    else {
+        return Ok(false);
+    };
+
+    let code = code.trim();
+
+    if is_totp_code(code) {
+        let secret = decrypt(&totp.secret_ciphertext)?;
+
+        return match matching_step(&secret, code) {
+            Some(step) => USER_TOTP_REPOSITORY.use_step(user, step).await,
+            None => Ok(false),
+        };
+    }
+
+    MFA_RECOVERY_CODES_REPOSITORY
+        .take(user, normalize_recovery_code(code))
+        .await
+}
+
+/// Replaces the recovery codes of `user` with new ones and returns them.
+///
+/// The codes are only ever shown here, just their digests are stored.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn regenerate_recovery_codes(user: Uuid) -> ApiResult<Vec<String>> {
+    let mut rng = rand::thread_rng();
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let code = Alphanumeric
+                .sample_string(&mut rng, RECOVERY_CODE_LENGTH)
+                .to_lowercase();
+            let (first, second) = code.split_at(RECOVERY_CODE_LENGTH / 2);
+
+            format!("{first}-{second}")
+        })
+        .collect();
+
+    let models: Vec<_> = codes
+        .iter()
+        .map(|code| MfaRecoveryCode::new(&normalize_recovery_code(code), user))
+        .collect();
+
+    MFA_RECOVERY_CODES_REPOSITORY.replace(user, &models).await?;
+
+    Ok(codes)
+}
+
+/// Removes the authenticator and recovery codes of `user`.
+///
+/// Returns `false` if the user had no authenticator.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn disable(user: Uuid) -> ApiResult<bool> {
+    MFA_RECOVERY_CODES_REPOSITORY.delete_by_user(user).await?;
+    USER_TOTP_REPOSITORY.delete_by_user(user).await
+}
+
+/// Creates a token that proves `user` passed the first factor, until it expires.
+pub(crate) fn mfa_token(user: Uuid) -> String {
+    let ttl = i64::try_from(*MFA_TOKEN_TTL)
+        .ok()
+        .and_then(TimeDelta::try_seconds)
+        .unwrap_or(TimeDelta::MAX);
+    let expires_at = Utc::now()
+        .checked_add_signed(ttl)
+        .unwrap_or(DateTime::<Utc>::MAX_UTC);
+
+    signing::sign(PURPOSE, &format!("{user}:{}", expires_at.timestamp()))
+}
+
+/// Gets the user a token from [`mfa_token`] was created for, `None` if it is invalid or expired.
+pub(crate) fn verify_mfa_token(token: &str) -> Option<Uuid> {
+    let payload = signing::verify(PURPOSE, token)?;
+    let (user, expires_at) = payload.split_once(':')?;
+
+    if expires_at.parse::<i64>().ok()? <= Utc::now().timestamp() {
+        return None;
+    }
+
+    user.parse().ok()
+}
+
+/// The code for the time step `step` (RFC 4226, Section 5.3).
+fn hotp(secret: &[u8], step: i64) -> u32 {
+    let mut mac = HmacSha1::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = usize::from(hash[hash.len() - 1] & 0x0f);
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    binary % 10u32.pow(DIGITS)
+}
+
+/// Finds the time step `code` is valid for, tolerating [`SKEW`] steps of drift.
+fn matching_step(secret: &[u8], code: &str) -> Option<i64> {
+    let code = code.trim();
+    if !is_totp_code(code) {
+        return None;
+    }
+
+    let code: u32 = code.parse().ok()?;
+    let step = Utc::now().timestamp().div_euclid(PERIOD);
+
+    (step - SKEW..=step + SKEW).find(|&step| hotp(secret, step) == code)
+}
+
+fn is_totp_code(code: &str) -> bool {
+    code.len() == DIGITS as usize && code.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// Recovery codes are accepted regardless of case and dashes.
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|char| char.to_ascii_lowercase())
+        .collect()
+}
+
+/// The `otpauth://` URI authenticator apps read the secret from.
+fn provisioning_uri(username: &str, secret: &[u8]) -> String {
+    // Spaces have to be `%20` in the URI, not `+`. A literal `+` is already escaped.
+    let encode = |value: &str| {
+        form_urlencoded::byte_serialize(value.as_bytes())
+            .collect::<String>()
+            .replace('+', "%20")
+    };
+
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={DIGITS}&period={PERIOD}",
+        encode(&MFA_ISSUER),
+        encode(username),
+        BASE32_NOPAD.encode(secret),
+        encode(&MFA_ISSUER)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Secret of the test vectors in RFC 4226 and RFC 6238.
+    const SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn matches_the_rfc_4226_vectors() {
+        let expected = [
+            755224, 287082, 359152, 969429, 338314, 254676, 287922, 162583, 399871, 520489,
+        ];
+
+        for (counter, code) in expected.into_iter().enumerate() {
+            assert_eq!(hotp(SECRET, counter as i64), code, "counter {counter}");
+        }
+    }
+
+    #[test]
+    fn matches_the_rfc_6238_vectors() {
+        // The SHA-1 vectors of RFC 6238, Appendix B, cut to six digits.
+        let expected = [
+            (59, 287082),
+            (1111111109, 81804),
+            (1111111111, 50471),
+            (1234567890, 5924),
+            (2000000000, 279037),
+            (20000000000, 353130),
+        ];
+
+        for (time, code) in expected {
+            assert_eq!(hotp(SECRET, time / PERIOD), code, "time {time}");
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_codes() {
+        assert!(!is_totp_code("12345"));
+        assert!(!is_totp_code("1234567"));
+        assert!(!is_totp_code("12a456"));
+        assert!(!is_totp_code("+12345"));
+        assert!(is_totp_code("012345"));
+    }
+
+    #[test]
+    fn normalizes_recovery_codes() {
+        assert_eq!(normalize_recovery_code("AbCd-E12 345"), "abcde12345");
+    }
+
+    #[test]
+    fn mfa_tokens_round_trip() {
+        let user = Uuid::new_v4();
+
+        assert_eq!(verify_mfa_token(&mfa_token(user)), Some(user));
+        assert_eq!(verify_mfa_token("garbage"), None);
+    }
+}
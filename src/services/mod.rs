@@ -1,5 +1,16 @@
 //! Services are the main business logic, if there is need for fetching data from a database we should create a repository
 //! for that instead and then use that repository in the service, this is to minimize the amount of logic in each layer.
 
+pub(crate) mod audit;
 pub(crate) mod health;
+pub(crate) mod lockout;
+pub(crate) mod mail;
+pub(crate) mod maintenance;
+pub(crate) mod mfa;
 pub(crate) mod oauth;
+pub(crate) mod oidc;
+pub(crate) mod password_policy;
+pub(crate) mod password_reset;
+pub(crate) mod roles;
+pub(crate) mod session;
+pub(crate) mod verification;
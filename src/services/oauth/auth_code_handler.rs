@@ -0,0 +1,82 @@
+use crate::repositories::oauth_auth_code::OAUTH_AUTH_CODE_REPOSITORY;
+use crate::repositories::oauth_clients::OAUTH_CLIENTS_REPOSITORY;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::services::oauth::{authenticate_client, create_token_response};
+use actix_oauth::error::Oauth2ErrorType;
+use actix_oauth::handler::HandlerReturn;
+use actix_oauth::types::{
+    AuthorizationCode, ClientId, ClientSecret, CodeVerifier, GrantType, RedirectUri,
+};
+use actix_web::HttpRequest;
+use serde_json::json;
+use sqlx_utils::traits::Repository;
+
+/// Exchanges an authorization code issued by the authorization endpoint for a token.
+///
+/// Codes are removed when they are exchanged so each can only be used once, and are only
+/// accepted from the client they were issued to with the redirect URI of the authorization
+/// request (RFC 6749, Section 4.1.3). The token gets the scopes the user approved.
+///
+/// Confidential clients authenticate with their secret. Public clients have none and send no
+/// secret, they prove they started the authorization request with the code verifier instead.
+/// Codes issued for a request with a code challenge are only exchanged with a verifier that
+/// matches it, and a verifier is rejected for codes issued without one (RFC 7636, Section 4.6).
+#[inline]
+#[tracing::instrument(skip_all, level = "debug")]
+pub(crate) async fn auth_code_handler(
+    req: HttpRequest,
+    code: AuthorizationCode,
+    redirect_uri: RedirectUri,
+    client_id: ClientId,
+    client_secret: Option<ClientSecret>,
+    code_verifier: Option<CodeVerifier>,
+) -> HandlerReturn {
+    let client = match client_secret {
+        Some(client_secret) => authenticate_client(&client_id, &client_secret).await?,
+        None => OAUTH_CLIENTS_REPOSITORY
+            .get_by_id(client_id.as_str())
+            .await
+            .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?
+            .filter(|client| !client.client_type.has_secret())
+            .ok_or(Oauth2ErrorType::InvalidClient)?,
+    };
+
+    if !client.grant_types.contains(&GrantType::AuthorizationCode) {
+        return Err(Oauth2ErrorType::UnauthorizedClient);
+    }
+
+    let code = OAUTH_AUTH_CODE_REPOSITORY
+        .take(code.secret())
+        .await
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?
+        .ok_or(Oauth2ErrorType::InvalidGrant)?;
+
+    if client.id != Some(code.client_id) || code.redirect_uri != redirect_uri.as_str() {
+        return Err(Oauth2ErrorType::InvalidGrant);
+    }
+
+    let verified = match (&code.code_challenge, &code_verifier) {
+        (Some(challenge), Some(verifier)) => verifier.matches_s256(challenge),
+        (None, None) => true,
+        _ => false,
+    };
+
+    if !verified {
+        return Err(Oauth2ErrorType::InvalidGrant);
+    }
+
+    let response = create_token_response(code.user_ext_id, client.id, &code.scopes)
+        .await
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?;
+
+    AuditEntry::new(AuditAction::TokenIssued)
+        .request(&req)
+        .actor_user(Some(code.user_ext_id))
+        .actor_client(client.client_id)
+        .target_user(code.user_ext_id)
+        .details(json!({ "grant_type": "authorization_code", "scopes": code.scopes }))
+        .record()
+        .await;
+
+    Ok(response)
+}
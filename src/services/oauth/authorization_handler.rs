@@ -0,0 +1,261 @@
+use crate::dto::{ConsentAnswer, ConsentPromptDTO};
+use crate::models::oauth_auth_code::OAuthAuthCode;
+use crate::repositories::oauth_auth_code::OAUTH_AUTH_CODE_REPOSITORY;
+use crate::repositories::oauth_clients::OAUTH_CLIENTS_REPOSITORY;
+use crate::repositories::user_consent::USER_CONSENT_REPOSITORY;
+use crate::services::oauth::pages::{consent_page, login_page};
+use crate::services::oauth::{SCOPES, user_from_request};
+use crate::services::oidc::OidcProvider;
+use crate::services::session::CSRF_COOKIE;
+use actix_oauth::dto::{AuthorizationRequest, ResponseType};
+use actix_oauth::error::Oauth2ErrorType;
+use actix_oauth::handler::AuthorizationReturn;
+use actix_oauth::types::{
+    AuthorizationCode, ClientId, CodeChallengeMethod, GrantType, is_valid_pkce_value,
+};
+use actix_web::http::header::{ACCEPT, LOCATION, WWW_AUTHENTICATE};
+use actix_web::http::{Method, StatusCode};
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
+use chrono::{Local, TimeDelta};
+use serde::Deserialize;
+use sqlx_utils::traits::Repository;
+
+/// How long an authorization code can be exchanged for, RFC 6749, Section 4.1.2 recommends at
+/// most ten minutes.
+const AUTHORIZATION_CODE_LIFETIME: TimeDelta = TimeDelta::minutes(5);
+
+/// Parameters of the authorization request that are specific to this server.
+#[derive(Debug, Deserialize)]
+struct AuthorizationParams {
+    /// Answer to the consent prompt, only accepted from users authenticated with a bearer token.
+    consent: Option<ConsentAnswer>,
+    /// Upstream provider to sign in with when the user is not signed in.
+    provider: Option<String>,
+}
+
+/// Whether the request asks for JSON rather than the pages shown in the browser.
+fn wants_json(req: &HttpRequest) -> bool {
+    req.headers()
+        .get(ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains(mime::APPLICATION_JSON.as_ref()))
+}
+
+/// Handles requests to the authorization endpoint.
+///
+/// Requests are validated in the order RFC 6749, Section 4.1.2.1 requires: problems with the
+/// client or redirect URI are returned directly as the redirect URI cannot be trusted, anything
+/// after that is reported by redirecting back to the client.
+///
+/// Users that are not signed in are shown a login page, or sign in through an upstream provider
+/// by adding `provider=<id>`. Either way they come back to this request once signed in.
+///
+/// The user is asked to approve the requested scopes unless they have approved them for the
+/// client before. Users signed in with a session answer the prompt on the consent page, which
+/// posts the answer with the CSRF token of the session to the consent endpoint, see
+/// [`answer_consent`]. Users authenticated with a bearer token answer it by sending the request
+/// again as a `POST` with `consent=approve` or `consent=deny`, answers in `GET` requests are
+/// ignored so a link cannot answer it on the user's behalf. Requests that accept JSON get the
+/// prompt as [`ConsentPromptDTO`] instead of a page.
+///
+/// Once the scopes are approved an authorization code bound to the client, redirect URI, scopes
+/// and PKCE code challenge is issued and the user is redirected back to the client with it.
+/// Only `S256` challenges are accepted, and public clients have to send one (RFC 7636) as they
+/// cannot authenticate when they exchange the code.
+#[tracing::instrument(skip_all, level = "debug")]
+pub(crate) async fn authorization_handler(
+    req: HttpRequest,
+    auth_req: AuthorizationRequest,
+) -> AuthorizationReturn {
+    let uri = req.uri().to_string();
+
+    authorize(&req, auth_req, &uri, None).await
+}
+
+/// Continues the authorization request at `uri` with the answer a user signed in with a session
+/// gave on the consent page.
+///
+/// The caller has to have checked the CSRF token of the session the answer was sent with.
+#[tracing::instrument(skip_all, level = "debug")]
+pub(crate) async fn answer_consent(
+    req: &HttpRequest,
+    uri: &str,
+    answer: ConsentAnswer,
+) -> AuthorizationReturn {
+    let query = uri.split_once('?').map_or("", |(_, query)| query);
+    let auth_req = web::Query::<AuthorizationRequest>::from_query(query)
+        .map_err(|_| Oauth2ErrorType::InvalidRequest)?
+        .into_inner();
+
+    authorize(req, auth_req, uri, Some(answer)).await
+}
+
+/// Handles the authorization request at `uri`, `answer` is an answer to the consent prompt that
+/// has already been checked.
+async fn authorize(
+    req: &HttpRequest,
+    auth_req: AuthorizationRequest,
+    uri: &str,
+    answer: Option<ConsentAnswer>,
+) -> AuthorizationReturn {
+    let query = uri.split_once('?').map_or("", |(_, query)| query);
+    let params = web::Query::<AuthorizationParams>::from_query(query)
+        .map_err(|_| Oauth2ErrorType::InvalidRequest)?
+        .into_inner();
+
+    let client = OAUTH_CLIENTS_REPOSITORY
+        .get_by_id(auth_req.client_id.as_str())
+        .await
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?
+        .ok_or(Oauth2ErrorType::InvalidClient)?;
+
+    if !client.allows_redirect_uri(&auth_req.redirect_uri) {
+        return Err(Oauth2ErrorType::InvalidRequest);
+    }
+
+    let user = user_from_request(req)
+        .await
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?;
+
+    let Some((user, session)) = user else {
+        let Some(provider) = params.provider else {
+            if wants_json(req) {
+                return Ok(HttpResponse::Unauthorized()
+                    .insert_header((WWW_AUTHENTICATE, "Bearer"))
+                    .finish());
+            }
+
+            return Ok(login_page(StatusCode::OK, uri, None));
+        };
+        let provider = OidcProvider::get(&provider).ok_or(Oauth2ErrorType::InvalidRequest)?;
+
+        let login = provider
+            .start_login(None, Some(uri.to_string()))
+            .await
+            .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?;
+
+        return Ok(login.redirect());
+    };
+    let user_ext_id = user.ext_id.ok_or(Oauth2ErrorType::ServerError)?;
+
+    let redirect_uri = &auth_req.redirect_uri;
+    let state = auth_req.state.as_deref();
+
+    if !matches!(auth_req.response_type, ResponseType::Code) {
+        return Ok(Oauth2ErrorType::UnsupportedResponseType.redirect(redirect_uri, state));
+    }
+
+    if !client.grant_types.contains(&GrantType::AuthorizationCode) {
+        return Ok(Oauth2ErrorType::UnauthorizedClient.redirect(redirect_uri, state));
+    }
+
+    // Clients that do not ask for specific scopes get the scopes they are registered with.
+    let scopes = match &auth_req.scope {
+        Some(scopes) if !scopes.is_empty() => scopes.to_vec(),
+        _ => client.scopes.clone(),
+    };
+
+    if !scopes
+        .iter()
+        .all(|scope| SCOPES.contains(scope) && client.scopes.contains(scope))
+    {
+        return Ok(Oauth2ErrorType::InvalidScope.redirect(redirect_uri, state));
+    }
+
+    // `plain` is also the method when none is sent, it offers no protection once the
+    // authorization request is seen so only `S256` challenges are accepted.
+    let code_challenge = match (&auth_req.code_challenge, auth_req.code_challenge_method) {
+        (Some(challenge), Some(CodeChallengeMethod::S256)) if is_valid_pkce_value(challenge) => {
+            Some(challenge.clone())
+        }
+        (None, None) if client.client_type.has_secret() => None,
+        _ => return Ok(Oauth2ErrorType::InvalidRequest.redirect(redirect_uri, state)),
+    };
+
+    let consent = USER_CONSENT_REPOSITORY
+        .get(user_ext_id, &client.client_id)
+        .await
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?;
+
+    if !consent.is_some_and(|consent| consent.covers(&scopes)) {
+        // Answers in the request itself are only taken from bearer tokens, which other sites
+        // cannot send and are first-party tokens, see `user_from_request`. Users signed in with a
+        // session answer through the consent endpoint, which checks the CSRF token.
+        let answer = answer.or_else(|| {
+            params
+                .consent
+                .filter(|_| req.method() == Method::POST && session.is_none())
+        });
+
+        match answer {
+            Some(ConsentAnswer::Approve) => {
+                USER_CONSENT_REPOSITORY
+                    .grant(user_ext_id, &client.client_id, &scopes)
+                    .await
+                    .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?;
+            }
+            Some(ConsentAnswer::Deny) => {
+                return Ok(Oauth2ErrorType::AccessDenied.redirect(redirect_uri, state));
+            }
+            None => {
+                let prompt = ConsentPromptDTO {
+                    client_id: ClientId::new(client.client_id),
+                    name: client.name,
+                    description: client.description,
+                    logo_uri: client.logo_uri,
+                    scopes: scopes
+                        .into_iter()
+                        .map(|scope| {
+                            let description = SCOPES.description(&scope).unwrap_or_default();
+                            let description = description.to_string();
+
+                            (scope, description)
+                        })
+                        .collect(),
+                };
+
+                if wants_json(req) {
+                    return Ok(HttpResponse::Ok().json(prompt));
+                }
+
+                let csrf_token = req.cookie(CSRF_COOKIE);
+                let csrf_token = csrf_token.as_ref().map(|cookie| cookie.value());
+
+                return Ok(consent_page(&prompt, uri, csrf_token.unwrap_or_default()));
+            }
+        }
+    }
+
+    let code = AuthorizationCode::new_random();
+    let expires_at = Local::now()
+        .checked_add_signed(AUTHORIZATION_CODE_LIFETIME)
+        .ok_or_else(|| {
+            Oauth2ErrorType::InternalError("Authorization code expiry is out of range".into())
+        })?
+        .naive_utc();
+
+    let mut auth_code = OAuthAuthCode::new(
+        code.secret(),
+        client.id.ok_or(Oauth2ErrorType::ServerError)?,
+        user_ext_id,
+        redirect_uri.as_str(),
+        scopes,
+        expires_at,
+    );
+    auth_code.code_challenge = code_challenge;
+
+    OAUTH_AUTH_CODE_REPOSITORY
+        .insert(&auth_code)
+        .await
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?;
+
+    let mut query = vec![("code", code.secret().as_str())];
+
+    if let Some(state) = state {
+        query.push(("state", state));
+    }
+
+    Ok(HttpResponse::Found()
+        .insert_header((LOCATION, redirect_uri.with_query(query)))
+        .finish())
+}
@@ -0,0 +1,40 @@
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::services::oauth::{authenticate_client, create_client_token_response};
+use actix_oauth::error::Oauth2ErrorType;
+use actix_oauth::handler::HandlerReturn;
+use actix_oauth::types::{ClientId, ClientSecret, GrantType};
+use actix_web::HttpRequest;
+use serde_json::json;
+
+/// Issues a token to a client acting on its own behalf (RFC 6749, Section 4.4).
+///
+/// The token is not tied to a user and gets all scopes the client is registered with.
+#[inline]
+#[tracing::instrument(skip_all, level = "debug")]
+pub(crate) async fn client_credentials_handler(
+    req: HttpRequest,
+    client_id: ClientId,
+    client_secret: ClientSecret,
+) -> HandlerReturn {
+    let client = authenticate_client(&client_id, &client_secret).await?;
+
+    if !client.grant_types.contains(&GrantType::ClientCredentials) {
+        return Err(Oauth2ErrorType::UnauthorizedClient);
+    }
+
+    let id = client.id.ok_or(Oauth2ErrorType::ServerError)?;
+
+    let response = create_client_token_response(id, &client.scopes)
+        .await
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?;
+
+    AuditEntry::new(AuditAction::TokenIssued)
+        .request(&req)
+        .actor_client(&client.client_id)
+        .target_client(&client.client_id)
+        .details(json!({ "grant_type": "client_credentials", "scopes": client.scopes }))
+        .record()
+        .await;
+
+    Ok(response)
+}
@@ -0,0 +1,41 @@
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::services::oauth::create_token_response;
+use crate::services::{lockout, mfa};
+use actix_oauth::error::Oauth2ErrorType;
+use actix_oauth::handler::HandlerReturn;
+use actix_oauth::types::MfaToken;
+use actix_web::HttpRequest;
+use serde_json::json;
+
+/// Issues the tokens a password grant withheld with `mfa_required`, once a code checks out.
+#[inline]
+#[tracing::instrument(skip_all, level = "debug")]
+pub(crate) async fn mfa_otp_handler(
+    req: HttpRequest,
+    mfa_token: MfaToken,
+    otp: String,
+) -> HandlerReturn {
+    let user_ext_id =
+        mfa::verify_mfa_token(mfa_token.secret()).ok_or(Oauth2ErrorType::InvalidGrant)?;
+
+    if !lockout::verify_mfa(user_ext_id, &otp, &req)
+        .await
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?
+    {
+        return Err(Oauth2ErrorType::InvalidGrant);
+    }
+
+    let response = create_token_response(user_ext_id, None, &[])
+        .await
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?;
+
+    AuditEntry::new(AuditAction::TokenIssued)
+        .request(&req)
+        .actor_user(Some(user_ext_id))
+        .target_user(user_ext_id)
+        .details(json!({ "grant_type": "mfa_otp" }))
+        .record()
+        .await;
+
+    Ok(response)
+}
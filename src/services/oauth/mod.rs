@@ -1,25 +1,173 @@
+use crate::models::oauth_client::OAuthClient;
 use crate::models::oauth_token::{OAuthToken, TokenType};
+use crate::models::session::Session;
+use crate::models::user::User;
+use crate::repositories::oauth_client_secrets::OAUTH_CLIENT_SECRETS_REPOSITORY;
+use crate::repositories::oauth_clients::OAUTH_CLIENTS_REPOSITORY;
 use crate::repositories::oauth_token::OAUTH_TOKEN_REPOSITORY;
+use crate::repositories::users::USERS_REPOSITORY;
+use crate::services::session::session_from_request;
 use crate::{ApiResult, ServerResult};
 use actix_oauth::dto::TokenResponse;
+use actix_oauth::error::Oauth2ErrorType;
 use actix_oauth::handler::OAuth2HandlerBuilder;
 use actix_oauth::traits::OAuth2Manager;
+use actix_oauth::types::{ClientId, ClientSecret, ScopeRegistry};
+use actix_web::HttpRequest;
 use actix_web::dev::HttpServiceFactory;
+use actix_web::http::header::AUTHORIZATION;
 use chrono::{Local, TimeDelta};
 use sqlx_utils::traits::Repository;
+use std::sync::LazyLock;
 use uuid::Uuid;
 
+mod auth_code_handler;
+pub(crate) mod authorization_handler;
+mod client_credentials_handler;
+mod mfa_otp_handler;
+pub(crate) mod pages;
 mod password_handler;
 
+/// Path the OAuth endpoints are mounted under.
+pub(crate) const OAUTH_BASE_PATH: &str = "/api/v1";
+
+/// All scopes that clients can request.
+pub(crate) static SCOPES: LazyLock<ScopeRegistry> = LazyLock::new(|| {
+    ScopeRegistry::new()
+        .with_scope("read", "Read access to your account and its resources")
+        .with_scope("write", "Modify your account and its resources")
+});
+
+/// Builds the OAuth handler with all grants the server supports.
 #[inline]
-pub(crate) async fn oauth_handler() -> ServerResult<impl OAuth2Manager + HttpServiceFactory> {
-    Ok(OAuth2HandlerBuilder::new()
+pub(crate) fn build_oauth_handler() -> impl OAuth2Manager + HttpServiceFactory {
+    OAuth2HandlerBuilder::new()
         .password_handler(password_handler::password_handler)
-        .build())
+        .authorization_code_handler(auth_code_handler::auth_code_handler)
+        .client_credentials_handler(client_credentials_handler::client_credentials_handler)
+        .authorization_handler(authorization_handler::authorization_handler)
+        .mfa_otp_handler(mfa_otp_handler::mfa_otp_handler)
+        .scopes(SCOPES.clone())
+        .build()
+}
+
+#[inline]
+pub(crate) async fn oauth_handler() -> ServerResult<impl OAuth2Manager + HttpServiceFactory> {
+    Ok(build_oauth_handler())
+}
+
+/// Hashes client secrets that were stored before secrets were hashed.
+///
+/// Called on startup so no plaintext secrets are left in the database, secrets are also
+/// hashed when they are first used should this not have run.
+pub(crate) async fn hash_plaintext_client_secrets() -> ApiResult<()> {
+    OAUTH_CLIENT_SECRETS_REPOSITORY
+        .hash_plaintext_secrets()
+        .await?;
+
+    Ok(())
+}
+
+/// Authenticates a client by its id and secret.
+///
+/// Handlers for grants that authenticate the client should go through this so secrets are
+/// always verified against the stored hash in constant time.
+///
+/// Public clients have no secret and always fail to authenticate, which bars them from the
+/// grants that require client authentication. The authorization code grant lets them use PKCE
+/// instead, see [`auth_code_handler`](auth_code_handler::auth_code_handler).
+#[tracing::instrument(skip_all, level = "debug")]
+pub(crate) async fn authenticate_client(
+    client_id: &ClientId,
+    client_secret: &ClientSecret,
+) -> Result<OAuthClient, Oauth2ErrorType> {
+    OAUTH_CLIENTS_REPOSITORY
+        .authenticate(client_id, client_secret)
+        .await
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?
+        .ok_or(Oauth2ErrorType::InvalidClient)
+}
+
+/// Gets the user a request is made by, with the session when it was made with one.
+///
+/// Requests are authenticated with a bearer token, or the session cookie set when the user
+/// signed in on the login page or through an upstream provider. Returns `None` if the request
+/// has neither, or the token is not a valid access token of a user.
+///
+/// Only first-party tokens count, tokens a client obtained for the user through a grant are
+/// ignored so the client cannot approve consent or link identities in the user's name.
+#[tracing::instrument(skip_all, level = "debug")]
+pub(crate) async fn user_from_request(
+    req: &HttpRequest,
+) -> ApiResult<Option<(User, Option<Session>)>> {
+    let header = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.strip_prefix("Bearer ").unwrap_or(value));
+
+    let (user_ext_id, session) = match header {
+        Some(token) => {
+            let user_ext_id = OAUTH_TOKEN_REPOSITORY
+                .get_by_token(token)
+                .await?
+                .filter(|token| token.token_type == TokenType::Access && token.client_id.is_none())
+                .and_then(|token| token.user_ext_id);
+
+            (user_ext_id, None)
+        }
+        None => match session_from_request(req).await? {
+            Some(session) => (Some(session.user_ext_id), Some(session)),
+            None => (None, None),
+        },
+    };
+
+    let Some(user_ext_id) = user_ext_id else {
+        return Ok(None);
+    };
+
+    Ok(USERS_REPOSITORY
+        .get_by_id(user_ext_id)
+        .await?
+        .map(|user| (user, session)))
+}
+
+/// Issues an access and refresh token for a user.
+///
+/// Tokens issued through a grant that involves a client are linked to it by its row id, so
+/// they can be revoked with the client's grant. `scopes` are the scopes the tokens carry.
+pub(crate) async fn create_token_response(
+    user_ext_id: Uuid,
+    client_id: Option<i64>,
+    scopes: &[String],
+) -> ApiResult<TokenResponse> {
+    issue_tokens(TokenResponse::new(), Some(user_ext_id), client_id, scopes).await
 }
 
-async fn create_token_response(user_ext_id: Uuid) -> ApiResult<TokenResponse> {
-    let token = TokenResponse::new();
+/// Issues an access token for a client acting on its own behalf.
+///
+/// No refresh token is issued, the client can request a new token with its credentials
+/// (RFC 6749, Section 4.4.3).
+pub(crate) async fn create_client_token_response(
+    client_id: i64,
+    scopes: &[String],
+) -> ApiResult<TokenResponse> {
+    issue_tokens(
+        TokenResponse::without_refresh_token(),
+        None,
+        Some(client_id),
+        scopes,
+    )
+    .await
+}
+
+/// Stores the tokens of `token`, only their digests are kept.
+async fn issue_tokens(
+    token: TokenResponse,
+    user_ext_id: Option<Uuid>,
+    client_id: Option<i64>,
+    scopes: &[String],
+) -> ApiResult<TokenResponse> {
     let token_repo = *OAUTH_TOKEN_REPOSITORY;
 
     let expires = Local::now()
@@ -28,19 +176,30 @@ async fn create_token_response(user_ext_id: Uuid) -> ApiResult<TokenResponse> {
         .naive_utc();
 
     let access_token = OAuthToken::new(
-        token.access_token.secret().to_string(),
+        token.access_token.secret(),
         user_ext_id,
         TokenType::Access,
         expires,
     );
-    let refresh_token = OAuthToken::new(
-        token.refresh_token.secret().to_string(),
-        user_ext_id,
-        TokenType::Refresh,
-        expires,
-    );
+    let refresh_token = token.refresh_token.as_ref().map(|refresh_token| {
+        OAuthToken::new(
+            refresh_token.secret(),
+            user_ext_id,
+            TokenType::Refresh,
+            expires,
+        )
+    });
+
+    let tokens = [Some(access_token), refresh_token]
+        .into_iter()
+        .flatten()
+        .map(|mut model| {
+            model.client_id = client_id;
+            model.scopes = scopes.to_vec();
+            model
+        });
 
-    token_repo.save_all([access_token, refresh_token]).await?;
+    token_repo.save_all(tokens).await?;
 
     Ok(token)
 }
@@ -0,0 +1,200 @@
+//! Pages the authorization endpoint shows users in the browser.
+//!
+//! The pages are plain HTML forms without scripts. Everything that comes from users or clients
+//! is escaped, and the pages may not be framed so they cannot be used for clickjacking.
+
+use crate::dto::ConsentPromptDTO;
+use crate::services::oauth::OAUTH_BASE_PATH;
+use crate::services::oidc::OIDC_PROVIDERS;
+use actix_web::http::StatusCode;
+use actix_web::http::header::{CACHE_CONTROL, CONTENT_SECURITY_POLICY, X_FRAME_OPTIONS};
+use actix_web::{HttpResponse, HttpResponseBuilder};
+
+/// Path of the endpoint the login page posts to, relative to [`OAUTH_BASE_PATH`].
+pub(crate) const LOGIN_ENDPOINT: &str = "/authorize/login";
+
+/// Path of the endpoint the MFA page posts to, relative to [`OAUTH_BASE_PATH`].
+pub(crate) const MFA_ENDPOINT: &str = "/authorize/mfa";
+
+/// Path of the endpoint the consent page posts to, relative to [`OAUTH_BASE_PATH`].
+pub(crate) const CONSENT_ENDPOINT: &str = "/authorize/consent";
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:24rem;margin:4rem auto;padding:0 1rem}\
+    label,input,button{display:block;width:100%;margin-top:.5rem}\
+    form{margin-top:1rem}.error{color:#b00020}img{max-width:4rem}";
+
+/// The login page, signing in continues the authorization request at `return_to`.
+///
+/// Users can also sign in through any of the configured upstream providers.
+pub(crate) fn login_page(status: StatusCode, return_to: &str, error: Option<&str>) -> HttpResponse {
+    let error = error
+        .map(|error| format!("<p class=\"error\">{}</p>", escape(error)))
+        .unwrap_or_default();
+
+    let providers: String = OIDC_PROVIDERS
+        .iter()
+        .map(|provider| {
+            format!(
+                "<li><a href=\"{}\">Sign in with {}</a></li>",
+                escape(&with_param(return_to, "provider", &provider.config.id)),
+                escape(&provider.config.name)
+            )
+        })
+        .collect();
+    let providers = if providers.is_empty() {
+        providers
+    } else {
+        format!("<ul>{providers}</ul>")
+    };
+
+    let body = format!(
+        "<h1>Sign in</h1>{error}\
+         <form method=\"post\" action=\"{OAUTH_BASE_PATH}{LOGIN_ENDPOINT}\">\
+         <input type=\"hidden\" name=\"return_to\" value=\"{}\">\
+         <label for=\"username\">Username</label>\
+         <input id=\"username\" name=\"username\" autocomplete=\"username\" required autofocus>\
+         <label for=\"password\">Password</label>\
+         <input id=\"password\" name=\"password\" type=\"password\" autocomplete=\"current-password\" required>\
+         <button type=\"submit\">Sign in</button>\
+         </form>{providers}",
+        escape(return_to)
+    );
+
+    page(HttpResponseBuilder::new(status), "Sign in", &body)
+}
+
+/// The page users with an authenticator give a code on after their password checked out.
+///
+/// `mfa_token` proves the password was already checked, the page continues the authorization
+/// request at `return_to` once the code checks out.
+pub(crate) fn mfa_page(
+    status: StatusCode,
+    return_to: &str,
+    mfa_token: &str,
+    error: Option<&str>,
+) -> HttpResponse {
+    let error = error
+        .map(|error| format!("<p class=\"error\">{}</p>", escape(error)))
+        .unwrap_or_default();
+
+    let body = format!(
+        "<h1>Two-factor authentication</h1>{error}\
+         <p>Enter the code from your authenticator app, or one of your recovery codes.</p>\
+         <form method=\"post\" action=\"{OAUTH_BASE_PATH}{MFA_ENDPOINT}\">\
+         <input type=\"hidden\" name=\"return_to\" value=\"{}\">\
+         <input type=\"hidden\" name=\"mfa_token\" value=\"{}\">\
+         <label for=\"code\">Code</label>\
+         <input id=\"code\" name=\"code\" autocomplete=\"one-time-code\" required autofocus>\
+         <button type=\"submit\">Verify</button>\
+         </form>",
+        escape(return_to),
+        escape(mfa_token)
+    );
+
+    page(
+        HttpResponseBuilder::new(status),
+        "Two-factor authentication",
+        &body,
+    )
+}
+
+/// The consent page, the form answers the prompt for the authorization request at `return_to`.
+///
+/// The CSRF token of the session is sent in the form rather than the URL, so it does not end up
+/// in logs, the history or `Referer` headers.
+pub(crate) fn consent_page(
+    prompt: &ConsentPromptDTO,
+    return_to: &str,
+    csrf_token: &str,
+) -> HttpResponse {
+    let logo = prompt
+        .logo_uri
+        .as_deref()
+        .map(|logo| format!("<img src=\"{}\" alt=\"\">", escape(logo)))
+        .unwrap_or_default();
+    let description = prompt
+        .description
+        .as_deref()
+        .map(|description| format!("<p>{}</p>", escape(description)))
+        .unwrap_or_default();
+
+    let scopes: String = prompt
+        .scopes
+        .iter()
+        .map(|(scope, description)| {
+            format!(
+                "<li><strong>{}</strong> {}</li>",
+                escape(scope),
+                escape(description)
+            )
+        })
+        .collect();
+
+    let body = format!(
+        "{logo}<h1>Authorize {}</h1>{description}\
+         <p>The application is asking for permission to:</p><ul>{scopes}</ul>\
+         <form method=\"post\" action=\"{OAUTH_BASE_PATH}{CONSENT_ENDPOINT}\">\
+         <input type=\"hidden\" name=\"return_to\" value=\"{}\">\
+         <input type=\"hidden\" name=\"csrf_token\" value=\"{}\">\
+         <button type=\"submit\" name=\"consent\" value=\"approve\">Allow</button>\
+         <button type=\"submit\" name=\"consent\" value=\"deny\">Deny</button>\
+         </form>",
+        escape(&prompt.name),
+        escape(return_to),
+        escape(csrf_token)
+    );
+
+    page(HttpResponse::Ok(), "Authorize", &body)
+}
+
+/// Sets `name` to `value` in the query of `uri`, replacing any value it had.
+pub(crate) fn with_param(uri: &str, name: &str, value: &str) -> String {
+    let (path, query) = uri.split_once('?').unwrap_or((uri, ""));
+    let prefix = format!("{name}=");
+
+    let mut pairs: Vec<String> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty() && !pair.starts_with(&prefix))
+        .map(str::to_string)
+        .collect();
+    pairs.push(format!(
+        "{name}={}",
+        form_urlencoded::byte_serialize(value.as_bytes()).collect::<String>()
+    ));
+
+    format!("{path}?{}", pairs.join("&"))
+}
+
+fn page(mut response: HttpResponseBuilder, title: &str, body: &str) -> HttpResponse {
+    response
+        .content_type(mime::TEXT_HTML_UTF_8)
+        .insert_header((CACHE_CONTROL, "no-store"))
+        .insert_header((X_FRAME_OPTIONS, "DENY"))
+        .insert_header((
+            CONTENT_SECURITY_POLICY,
+            "default-src 'none'; style-src 'unsafe-inline'; img-src https: data:; \
+             frame-ancestors 'none'",
+        ))
+        .body(format!(
+            "<!DOCTYPE html><html lang=\"en\"><head><meta charset=\"utf-8\">\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+             <title>{title}</title><style>{STYLE}</style></head><body>{body}</body></html>"
+        ))
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for char in value.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            char => escaped.push(char),
+        }
+    }
+
+    escaped
+}
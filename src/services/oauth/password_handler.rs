@@ -1,30 +1,53 @@
-use crate::repositories::users::USERS_REPOSITORY;
+use crate::services::audit::{AuditAction, AuditEntry};
 use crate::services::oauth::create_token_response;
+use crate::services::{lockout, mfa};
+use crate::statics::REQUIRE_VERIFIED_EMAIL;
 use actix_oauth::error::Oauth2ErrorType;
 use actix_oauth::handler::HandlerReturn;
-use actix_oauth::types::{Password, Username};
+use actix_oauth::types::{MfaToken, Password, Username};
 use actix_web::HttpRequest;
+use serde_json::json;
 
 #[inline]
 #[tracing::instrument(skip_all, level = "debug")]
 pub(crate) async fn password_handler(
-    _: HttpRequest,
+    req: HttpRequest,
     username: Username,
     password: Password,
 ) -> HandlerReturn {
-    let repo = *USERS_REPOSITORY;
-
-    let user = repo
-        .find_by_username(username)
+    let user = lockout::authenticate(username.as_ref(), &password, &req)
         .await
-        .map_err(|_| Oauth2ErrorType::InvalidGrant)?;
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?
+        .ok_or(Oauth2ErrorType::InvalidGrant)?;
+
+    // Only refused after the password checked out, so it does not reveal which users exist.
+    if *REQUIRE_VERIFIED_EMAIL && user.email_verified_at.is_none() {
+        return Err(Oauth2ErrorType::InvalidGrant);
+    }
 
-    match user {
-        Some(user) if repo.verify_password(&password, &user.password_hash) => {
-            create_token_response(user.ext_id.unwrap())
-                .await
-                .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))
-        }
-        _ => Err(Oauth2ErrorType::InvalidGrant),
+    let user_ext_id = user.ext_id.unwrap();
+
+    // The tokens are issued by the `mfa_otp` grant once a code is sent with the token.
+    if mfa::is_enrolled(user_ext_id)
+        .await
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?
+    {
+        return Err(Oauth2ErrorType::MfaRequired(MfaToken::new(mfa::mfa_token(
+            user_ext_id,
+        ))));
     }
+
+    let response = create_token_response(user_ext_id, None, &[])
+        .await
+        .map_err(|err| Oauth2ErrorType::InternalError(err.to_string()))?;
+
+    AuditEntry::new(AuditAction::TokenIssued)
+        .request(&req)
+        .actor_user(Some(user_ext_id))
+        .target_user(user_ext_id)
+        .details(json!({ "grant_type": "password" }))
+        .record()
+        .await;
+
+    Ok(response)
 }
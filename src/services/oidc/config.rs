@@ -0,0 +1,94 @@
+use crate::statics::OIDC_PROVIDERS_FILE;
+use serde::Deserialize;
+use std::fs;
+use std::io::ErrorKind;
+use tracing::{error, info};
+
+/// Configuration of an upstream OpenID Connect provider users can sign in with.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct OidcProviderConfig {
+    /// Identifies the provider in URLs and in the identities linked to users.
+    pub(crate) id: String,
+    /// Name of the provider shown to users.
+    pub(crate) name: String,
+    /// Issuer URL, the provider metadata is discovered from it.
+    pub(crate) issuer: String,
+    pub(crate) client_id: String,
+    /// Left out for providers that register the server as a public client.
+    #[serde(default)]
+    pub(crate) client_secret: Option<String>,
+    /// Scopes requested in addition to `openid`.
+    #[serde(default = "default_scopes")]
+    pub(crate) scopes: Vec<String>,
+    #[serde(default)]
+    pub(crate) claims: ClaimMapping,
+    /// Link identities to existing users with the same email, if the provider has verified it.
+    ///
+    /// Only enable this for providers that are trusted to verify emails.
+    #[serde(default)]
+    pub(crate) link_by_email: bool,
+}
+
+/// Names of the ID token claims user details are taken from.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(crate) struct ClaimMapping {
+    pub(crate) subject: String,
+    pub(crate) username: String,
+    pub(crate) email: String,
+    pub(crate) email_verified: String,
+}
+
+impl Default for ClaimMapping {
+    fn default() -> Self {
+        Self {
+            subject: "sub".to_string(),
+            username: "preferred_username".to_string(),
+            email: "email".to_string(),
+            email_verified: "email_verified".to_string(),
+        }
+    }
+}
+
+fn default_scopes() -> Vec<String> {
+    vec!["email".to_string(), "profile".to_string()]
+}
+
+/// Loads the providers from the JSON file at `OIDC_PROVIDERS_FILE`.
+///
+/// A missing file means no providers are configured, an invalid file is logged and ignored so
+/// a broken config does not keep local users from signing in.
+pub(crate) fn load_providers() -> Vec<OidcProviderConfig> {
+    let path = OIDC_PROVIDERS_FILE.as_str();
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            info!(path, "No OpenID Connect providers configured");
+
+            return Vec::new();
+        }
+        Err(error) => {
+            error!(path, error = %error, "Failed to read OpenID Connect providers");
+
+            return Vec::new();
+        }
+    };
+
+    match serde_json::from_str::<Vec<OidcProviderConfig>>(&content) {
+        Ok(providers) => {
+            info!(
+                path,
+                count = providers.len(),
+                "Loaded OpenID Connect providers"
+            );
+
+            providers
+        }
+        Err(error) => {
+            error!(path, error = %error, "Failed to parse OpenID Connect providers");
+
+            Vec::new()
+        }
+    }
+}
@@ -0,0 +1,486 @@
+//! Sign in through upstream OpenID Connect providers.
+//!
+//! Users are sent to the provider with the authorization code flow and PKCE. When they come
+//! back the ID token is verified against the keys the provider publishes, and the identity is
+//! linked to the signed in user, an existing user with the same verified email if the provider
+//! allows it, or a newly provisioned user.
+//!
+//! Providers are configured in the JSON file at `OIDC_PROVIDERS_FILE`, see
+//! `oidc_providers.example.json`. The `mock-idp` service in `docker-compose.yml` can be used as
+//! a provider when testing locally.
+
+use crate::ApiResult;
+use crate::error::ApiError;
+use crate::models::oidc_login_state::OidcLoginState;
+use crate::models::user::User;
+use crate::models::user_identity::UserIdentity;
+use crate::repositories::oidc_login_states::OIDC_LOGIN_STATES_REPOSITORY;
+use crate::repositories::user_identities::USER_IDENTITIES_REPOSITORY;
+use crate::repositories::users::USERS_REPOSITORY;
+use crate::services::oauth::OAUTH_BASE_PATH;
+use crate::statics::BASE_URL;
+use actix_web::HttpResponse;
+use actix_web::cookie::{Cookie, SameSite, time};
+use actix_web::http::header::LOCATION;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{Local, TimeDelta};
+use openidconnect::core::{CoreAuthenticationFlow, CoreClient, CoreIdToken, CoreProviderMetadata};
+use openidconnect::reqwest;
+use openidconnect::{
+    AuthorizationCode, ClaimsVerificationError, ClientId, ClientSecret, CsrfToken,
+    EndpointMaybeSet, EndpointNotSet, EndpointSet, IssuerUrl, Nonce, PkceCodeChallenge,
+    PkceCodeVerifier, RedirectUrl, Scope, SignatureVerificationError, TokenResponse,
+};
+use serde_json::{Map, Value};
+use sqlx_utils::traits::Repository;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+pub(crate) mod config;
+
+use config::{OidcProviderConfig, load_providers};
+
+/// How long users have to sign in at the provider.
+const LOGIN_LIFETIME: TimeDelta = TimeDelta::minutes(10);
+
+/// Cookie binding a login to the browser that started it.
+///
+/// Holds a random nonce whose digest is stored with the login, so a callback URL of someone
+/// else's login cannot be used to sign a victim into the attacker's account.
+pub(crate) const LOGIN_COOKIE: &str = "oidc_login";
+
+/// How long discovered metadata, including the signing keys, is used before it is discovered
+/// again.
+const METADATA_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// How many suffixed usernames are tried before provisioning a user gives up.
+const MAX_USERNAME_ATTEMPTS: usize = 100;
+
+type OidcClient = CoreClient<
+    EndpointSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointMaybeSet,
+    EndpointMaybeSet,
+>;
+
+/// The providers users can sign in with, loaded once on first use.
+pub(crate) static OIDC_PROVIDERS: LazyLock<Vec<OidcProvider>> = LazyLock::new(|| {
+    load_providers()
+        .into_iter()
+        .map(|config| OidcProvider {
+            config,
+            metadata: RwLock::new(None),
+        })
+        .collect()
+});
+
+/// Redirects are not followed so requests cannot be sent somewhere the provider did not intend.
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::ClientBuilder::new()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("Failed to build the OpenID Connect HTTP client")
+});
+
+/// A login that was just started, with the nonce only the browser keeps.
+pub(crate) struct StartedLogin {
+    /// URL of the provider to send the user to.
+    url: String,
+    binding: String,
+}
+
+impl StartedLogin {
+    /// Redirects the user to the provider and sets the [`LOGIN_COOKIE`].
+    pub(crate) fn redirect(self) -> HttpResponse {
+        // `Lax` so the cookie is sent when the provider redirects back to the callback.
+        let cookie = Cookie::build(LOGIN_COOKIE, self.binding)
+            .path(format!("{OAUTH_BASE_PATH}/oidc"))
+            .http_only(true)
+            .secure(BASE_URL.starts_with("https://"))
+            .same_site(SameSite::Lax)
+            .max_age(time::Duration::seconds(LOGIN_LIFETIME.num_seconds()))
+            .finish();
+
+        HttpResponse::Found()
+            .insert_header((LOCATION, self.url))
+            .cookie(cookie)
+            .finish()
+    }
+}
+
+/// Removes the [`LOGIN_COOKIE`] once the login it binds is finished.
+pub(crate) fn clear_login_cookie(response: &mut HttpResponse) {
+    let cookie = Cookie::build(LOGIN_COOKIE, "")
+        .path(format!("{OAUTH_BASE_PATH}/oidc"))
+        .finish();
+
+    // Only fails for cookies that are not valid header values, which this one always is.
+    let _ = response.add_removal_cookie(&cookie);
+}
+
+/// Metadata of a provider and when it was discovered.
+#[derive(Clone)]
+struct DiscoveredMetadata {
+    metadata: CoreProviderMetadata,
+    discovered_at: Instant,
+}
+
+/// An upstream provider and its discovered metadata.
+pub(crate) struct OidcProvider {
+    pub(crate) config: OidcProviderConfig,
+    metadata: RwLock<Option<DiscoveredMetadata>>,
+}
+
+impl OidcProvider {
+    /// Gets a configured provider by its id.
+    pub(crate) fn get(id: &str) -> Option<&'static OidcProvider> {
+        OIDC_PROVIDERS
+            .iter()
+            .find(|provider| provider.config.id == id)
+    }
+
+    /// Gets the metadata of the provider, discovering it when it is older than [`METADATA_TTL`].
+    ///
+    /// `outdated` is when the metadata that turned out to be outdated was discovered, it is
+    /// discovered again unless another request already did so.
+    async fn metadata(&self, outdated: Option<Instant>) -> ApiResult<DiscoveredMetadata> {
+        let usable = |cached: &DiscoveredMetadata| match outdated {
+            Some(outdated) => cached.discovered_at > outdated,
+            None => cached.discovered_at.elapsed() < METADATA_TTL,
+        };
+
+        if let Some(cached) = self
+            .metadata
+            .read()
+            .await
+            .as_ref()
+            .filter(|cached| usable(cached))
+        {
+            return Ok(cached.clone());
+        }
+
+        let mut cached = self.metadata.write().await;
+
+        // Another request may have discovered it while this one waited for the lock.
+        if let Some(cached) = cached.as_ref().filter(|cached| usable(cached)) {
+            return Ok(cached.clone());
+        }
+
+        let issuer = IssuerUrl::new(self.config.issuer.clone())
+            .map_err(|err| dependency_error("Invalid issuer URL", err))?;
+
+        let metadata = CoreProviderMetadata::discover_async(issuer, &*HTTP_CLIENT)
+            .await
+            .map_err(|err| dependency_error("Failed to discover provider metadata", err))?;
+
+        let discovered = DiscoveredMetadata {
+            metadata,
+            discovered_at: Instant::now(),
+        };
+        *cached = Some(discovered.clone());
+
+        Ok(discovered)
+    }
+
+    /// Builds a client for the provider, see [`OidcProvider::metadata`] for `outdated`.
+    ///
+    /// Returns when the metadata the client was built with was discovered.
+    async fn client(&self, outdated: Option<Instant>) -> ApiResult<(OidcClient, Instant)> {
+        let DiscoveredMetadata {
+            metadata,
+            discovered_at,
+        } = self.metadata(outdated).await?;
+
+        let redirect_url = RedirectUrl::new(format!(
+            "{}{OAUTH_BASE_PATH}/oidc/{}/callback",
+            BASE_URL.trim_end_matches('/'),
+            self.config.id
+        ))
+        .map_err(|err| dependency_error("Invalid callback URL", err))?;
+
+        let client = CoreClient::from_provider_metadata(
+            metadata,
+            ClientId::new(self.config.client_id.clone()),
+            self.config.client_secret.clone().map(ClientSecret::new),
+        )
+        .set_redirect_uri(redirect_url);
+
+        Ok((client, discovered_at))
+    }
+
+    /// Starts a login, the returned login redirects the user to the provider.
+    ///
+    /// When `user` is set the identity is linked to that user instead of signing in, and
+    /// `return_to` is the authorization request to continue once the user is signed in.
+    #[tracing::instrument(skip_all, fields(provider = %self.config.id))]
+    pub(crate) async fn start_login(
+        &self,
+        user: Option<Uuid>,
+        return_to: Option<String>,
+    ) -> ApiResult<StartedLogin> {
+        let (client, _) = self.client(None).await?;
+        let binding = CsrfToken::new_random();
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+
+        let (url, state, nonce) = client
+            .authorize_url(
+                CoreAuthenticationFlow::AuthorizationCode,
+                CsrfToken::new_random,
+                Nonce::new_random,
+            )
+            .add_scopes(self.config.scopes.iter().cloned().map(Scope::new))
+            .set_pkce_challenge(pkce_challenge)
+            .url();
+
+        let expires_at = Local::now()
+            .checked_add_signed(LOGIN_LIFETIME)
+            .unwrap()
+            .naive_utc();
+
+        let mut login = OidcLoginState::new(
+            state.secret(),
+            binding.secret(),
+            &self.config.id,
+            nonce.secret(),
+            pkce_verifier.secret(),
+            expires_at,
+        );
+        login.user_ext_id = user;
+        login.return_to = return_to;
+
+        OIDC_LOGIN_STATES_REPOSITORY.insert(&login).await?;
+
+        Ok(StartedLogin {
+            url: url.to_string(),
+            binding: binding.secret().clone(),
+        })
+    }
+
+    /// Finishes a login the provider redirected back with.
+    ///
+    /// `binding` is the nonce in the [`LOGIN_COOKIE`] of the request, the login has to have been
+    /// started by the same browser. Returns the signed in user and the authorization request to
+    /// continue, if any.
+    #[tracing::instrument(skip_all, fields(provider = %self.config.id))]
+    pub(crate) async fn finish_login(
+        &self,
+        code: String,
+        state: &str,
+        binding: &str,
+    ) -> ApiResult<(User, Option<String>)> {
+        let login = OIDC_LOGIN_STATES_REPOSITORY
+            .take(&self.config.id, state, binding)
+            .await?
+            .ok_or_else(|| ApiError::BadRequest("Unknown or expired login".into()))?;
+
+        let (client, discovered_at) = self.client(None).await?;
+
+        let response = client
+            .exchange_code(AuthorizationCode::new(code))
+            .map_err(|err| dependency_error("Provider has no token endpoint", err))?
+            .set_pkce_verifier(PkceCodeVerifier::new(login.pkce_verifier))
+            .request_async(&*HTTP_CLIENT)
+            .await
+            .map_err(|err| dependency_error("Failed to exchange the authorization code", err))?;
+
+        let id_token = response
+            .id_token()
+            .ok_or_else(|| ApiError::FailedDependency {
+                message: "Provider did not return an ID token".into(),
+                error: None,
+            })?;
+
+        // Checks the signature, issuer, audience, expiry and nonce.
+        let nonce = Nonce::new(login.nonce);
+        let verified = id_token
+            .claims(&client.id_token_verifier(), &nonce)
+            .map(|_| ());
+
+        // Providers rotate their signing keys, a key that is not known yet means the keys are
+        // outdated, so they are discovered again and the token is checked once more.
+        let verified = match verified {
+            Err(ClaimsVerificationError::SignatureVerification(
+                SignatureVerificationError::NoMatchingKey,
+            )) => {
+                let (client, _) = self.client(Some(discovered_at)).await?;
+
+                id_token
+                    .claims(&client.id_token_verifier(), &nonce)
+                    .map(|_| ())
+            }
+            verified => verified,
+        };
+
+        verified.map_err(|err| dependency_error("Invalid ID token", err))?;
+
+        let claims = raw_claims(id_token)?;
+        let mapping = &self.config.claims;
+
+        let subject =
+            claim(&claims, &mapping.subject).ok_or_else(|| ApiError::FailedDependency {
+                message: format!("ID token has no '{}' claim", mapping.subject),
+                error: None,
+            })?;
+        let email = claim(&claims, &mapping.email);
+
+        let user_ext_id = match USER_IDENTITIES_REPOSITORY
+            .get_by_subject(&self.config.id, subject)
+            .await?
+        {
+            Some(identity) => {
+                if login
+                    .user_ext_id
+                    .is_some_and(|user| user != identity.user_ext_id)
+                {
+                    return Err(ApiError::BadRequest(
+                        "This identity is linked to another user".into(),
+                    ));
+                }
+
+                let id = identity.id.ok_or(ApiError::InternalError)?;
+                USER_IDENTITIES_REPOSITORY.touch(id, email).await?;
+
+                identity.user_ext_id
+            }
+            None => {
+                let user_ext_id = match login.user_ext_id {
+                    Some(user) => user,
+                    None => self.find_or_provision_user(&claims, email).await?,
+                };
+
+                let identity = UserIdentity {
+                    id: None,
+                    user_ext_id,
+                    provider: self.config.id.clone(),
+                    subject: subject.to_string(),
+                    email: email.map(str::to_string),
+                    created_at: None,
+                    last_login_at: Some(Local::now().naive_utc()),
+                };
+
+                USER_IDENTITIES_REPOSITORY.insert(&identity).await?;
+
+                user_ext_id
+            }
+        };
+
+        let user = USERS_REPOSITORY
+            .get_by_id(user_ext_id)
+            .await?
+            .ok_or(ApiError::InternalError)?;
+
+        Ok((user, login.return_to))
+    }
+
+    /// Gets the user an identity seen for the first time belongs to.
+    ///
+    /// Identities are only linked to existing users by email when the provider is configured
+    /// to allow it and has verified the email, otherwise a new user is provisioned.
+    async fn find_or_provision_user(
+        &self,
+        claims: &Map<String, Value>,
+        email: Option<&str>,
+    ) -> ApiResult<Uuid> {
+        let mapping = &self.config.claims;
+
+        let Some(email) = email else {
+            return Err(ApiError::BadRequest(
+                "The provider did not share an email address".into(),
+            ));
+        };
+
+        let verified = match claims.get(&mapping.email_verified) {
+            Some(Value::Bool(verified)) => *verified,
+            Some(Value::String(verified)) => verified == "true",
+            _ => false,
+        };
+
+        if let Some(existing) = USERS_REPOSITORY.find_by_email(email).await? {
+            if self.config.link_by_email && verified {
+                return existing.ext_id.ok_or(ApiError::InternalError);
+            }
+
+            return Err(ApiError::BadRequest(
+                "A user with this email already exists, sign in and link the provider instead"
+                    .into(),
+            ));
+        }
+
+        let base = claim(claims, &mapping.username)
+            .or_else(|| email.split('@').next())
+            .filter(|username| !username.is_empty())
+            .unwrap_or(self.config.id.as_str());
+
+        for attempt in 0..MAX_USERNAME_ATTEMPTS {
+            let username = match attempt {
+                0 => base.to_string(),
+                n => format!("{base}_{n}"),
+            };
+
+            if USERS_REPOSITORY
+                .find_by_username(&username)
+                .await?
+                .is_some()
+            {
+                continue;
+            }
+
+            // Provisioned users sign in through the provider, the password is random and unknown.
+            let user = USERS_REPOSITORY
+                .create_user(&username, email, &Uuid::new_v4().to_string())
+                .await?;
+            let user_ext_id = user.ext_id.ok_or(ApiError::InternalError)?;
+
+            // The provider vouches for the email, the user does not have to verify it again.
+            if verified {
+                USERS_REPOSITORY
+                    .mark_email_verified(user_ext_id, email)
+                    .await?;
+            }
+
+            return Ok(user_ext_id);
+        }
+
+        Err(ApiError::BadRequest(format!(
+            "Could not find a free username based on '{base}'"
+        )))
+    }
+}
+
+fn dependency_error(message: &str, error: impl std::error::Error + Send + 'static) -> ApiError {
+    ApiError::FailedDependency {
+        message: message.to_string(),
+        error: Some(Box::new(error)),
+    }
+}
+
+/// Gets a claim that is a non-empty string.
+fn claim<'a>(claims: &'a Map<String, Value>, name: &str) -> Option<&'a str> {
+    claims
+        .get(name)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+}
+
+/// All claims of an ID token, which also covers the claims the standard claims leave out.
+///
+/// Only call this after the token has been verified, the payload is decoded as is.
+fn raw_claims(id_token: &CoreIdToken) -> ApiResult<Map<String, Value>> {
+    let jwt = serde_json::to_value(id_token)?;
+
+    let payload = jwt
+        .as_str()
+        .and_then(|jwt| jwt.split('.').nth(1))
+        .and_then(|payload| URL_SAFE_NO_PAD.decode(payload).ok())
+        .ok_or_else(|| ApiError::FailedDependency {
+            message: "Malformed ID token".into(),
+            error: None,
+        })?;
+
+    Ok(serde_json::from_slice(&payload)?)
+}
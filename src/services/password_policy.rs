@@ -0,0 +1,323 @@
+//! Rules new passwords have to follow, applied when users sign up, change their password and
+//! reset it.
+//!
+//! Passwords need `PASSWORD_MIN_LENGTH` characters and at most `PASSWORD_MAX_LENGTH`, at least
+//! `PASSWORD_MIN_CHARACTER_CLASSES` of lowercase letters, uppercase letters, digits and symbols,
+//! and may not contain the username or email of the user. With `PASSWORD_BREACHED_LIST` they
+//! are also checked against a local list of breached passwords, nothing is sent anywhere. The
+//! list is read once at startup by [`load_breached_passwords`].
+//!
+//! Violations are returned as field errors of [`ApiError::Validation`], one for each rule.
+
+use crate::ApiResult;
+use crate::error::ApiError;
+use crate::statics::{
+    PASSWORD_BREACHED_LIST, PASSWORD_MAX_LENGTH, PASSWORD_MIN_CHARACTER_CLASSES,
+    PASSWORD_MIN_LENGTH,
+};
+use sha1::{Digest, Sha1};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::OnceLock;
+use tracing::{error, info, warn};
+use validator::{ValidationError, ValidationErrors};
+
+/// Shortest username or email part that passwords are checked for, shorter ones would reject
+/// too many passwords by chance.
+const MIN_CONTAINED_LENGTH: usize = 3;
+
+/// SHA-1 hashes of the breached passwords from `PASSWORD_BREACHED_LIST`, set at startup by
+/// [`load_breached_passwords`].
+static BREACHED_PASSWORDS: OnceLock<HashSet<[u8; 20]>> = OnceLock::new();
+
+/// The user a password is for.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PasswordOwner<'a> {
+    pub(crate) username: &'a str,
+    pub(crate) email: &'a str,
+}
+
+/// Checks `password` of `owner` against the policy.
+///
+/// Fails with [`ApiError::Validation`] with an error on `field` for every rule it breaks.
+pub(crate) fn check(
+    field: &'static str,
+    password: &str,
+    owner: PasswordOwner<'_>,
+) -> ApiResult<()> {
+    let mut errors = ValidationErrors::new();
+
+    validate(&mut errors, field, password, owner);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::Validation(errors))
+    }
+}
+
+/// Adds an error on `field` to `errors` for every rule `password` of `owner` breaks, for
+/// requests that validate other fields as well.
+pub(crate) fn validate(
+    errors: &mut ValidationErrors,
+    field: &'static str,
+    password: &str,
+    owner: PasswordOwner<'_>,
+) {
+    for violation in violations(password, owner) {
+        errors.add(field, violation);
+    }
+}
+
+/// The rules `password` of `owner` breaks.
+pub(crate) fn violations(password: &str, owner: PasswordOwner<'_>) -> Vec<ValidationError> {
+    let mut violations = Vec::new();
+    let length = password.chars().count();
+
+    if length < *PASSWORD_MIN_LENGTH {
+        violations.push(violation(
+            "password_too_short",
+            format!("Must be at least {} characters", *PASSWORD_MIN_LENGTH),
+            &[("min", *PASSWORD_MIN_LENGTH)],
+        ));
+    }
+
+    if length > *PASSWORD_MAX_LENGTH {
+        violations.push(violation(
+            "password_too_long",
+            format!("Must be at most {} characters", *PASSWORD_MAX_LENGTH),
+            &[("max", *PASSWORD_MAX_LENGTH)],
+        ));
+    }
+
+    let classes = character_classes(password);
+    if classes < *PASSWORD_MIN_CHARACTER_CLASSES {
+        violations.push(violation(
+            "password_too_simple",
+            format!(
+                "Must use at least {} of lowercase letters, uppercase letters, digits and symbols",
+                *PASSWORD_MIN_CHARACTER_CLASSES
+            ),
+            &[
+                ("min_classes", *PASSWORD_MIN_CHARACTER_CLASSES),
+                ("classes", classes),
+            ],
+        ));
+    }
+
+    if contains_identity(password, owner) {
+        violations.push(violation(
+            "password_contains_identity",
+            "Must not contain the username or email".into(),
+            &[],
+        ));
+    }
+
+    if is_breached(password) {
+        violations.push(violation(
+            "password_breached",
+            "Appears in a data breach, choose a different password".into(),
+            &[],
+        ));
+    }
+
+    violations
+}
+
+fn violation(
+    code: &'static str,
+    message: String,
+    params: &[(&'static str, usize)],
+) -> ValidationError {
+    let mut error = ValidationError::new(code).with_message(Cow::Owned(message));
+
+    for (name, value) in params {
+        error.add_param(Cow::Borrowed(*name), value);
+    }
+
+    error
+}
+
+/// How many of lowercase letters, uppercase letters, digits and symbols `password` uses.
+fn character_classes(password: &str) -> usize {
+    [
+        password.chars().any(char::is_lowercase),
+        password.chars().any(char::is_uppercase),
+        password.chars().any(char::is_numeric),
+        password.chars().any(|c| !c.is_alphanumeric()),
+    ]
+    .into_iter()
+    .filter(|&used| used)
+    .count()
+}
+
+/// Whether `password` contains the username, the email or the part of the email before the `@`,
+/// ignoring case.
+fn contains_identity(password: &str, owner: PasswordOwner<'_>) -> bool {
+    let password = password.to_lowercase();
+    let local_part = owner
+        .email
+        .split_once('@')
+        .map_or(owner.email, |(local, _)| local);
+
+    [owner.username, owner.email, local_part]
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| part.chars().count() >= MIN_CONTAINED_LENGTH)
+        .any(|part| password.contains(&part.to_lowercase()))
+}
+
+/// Whether `password` is on the breached password list.
+fn is_breached(password: &str) -> bool {
+    let Some(hashes) = BREACHED_PASSWORDS.get() else {
+        return false;
+    };
+
+    hashes.contains(&<[u8; 20]>::from(Sha1::digest(password.as_bytes())))
+}
+
+/// Loads the breached password list from `PASSWORD_BREACHED_LIST`, if it is set.
+///
+/// Called once on startup, before the server takes requests. The file can be large so it is
+/// read on a blocking thread, failing to read it fails startup rather than silently accepting
+/// every password.
+pub(crate) async fn load_breached_passwords() -> std::io::Result<()> {
+    let Some(path) = PASSWORD_BREACHED_LIST.clone() else {
+        return Ok(());
+    };
+
+    let hashes = tokio::task::spawn_blocking(move || read_breached_passwords(&path))
+        .await
+        .map_err(std::io::Error::other)??;
+
+    // Loading again keeps the list that was loaded first, the file is only read at startup.
+    let _ = BREACHED_PASSWORDS.set(hashes);
+
+    Ok(())
+}
+
+/// Reads the hashes of the breached password list at `path`.
+///
+/// Lines are hex encoded SHA-1 hashes, anything after a `:` is ignored and lines that are not a
+/// full hash are skipped.
+fn read_breached_passwords(path: &str) -> std::io::Result<HashSet<[u8; 20]>> {
+    let file = File::open(path).inspect_err(|error| {
+        error!(path, error = %error, "Failed to open the breached password list");
+    })?;
+
+    let mut hashes = HashSet::new();
+    let mut skipped = 0usize;
+
+    for line in BufReader::new(file).lines() {
+        let line = line.inspect_err(|error| {
+            error!(path, error = %error, "Failed to read the breached password list");
+        })?;
+
+        let hash = line.split(':').next().unwrap_or_default().trim();
+        if hash.is_empty() {
+            continue;
+        }
+
+        let mut digest = [0u8; 20];
+        match hex::decode_to_slice(hash, &mut digest) {
+            Ok(()) => {
+                hashes.insert(digest);
+            }
+            Err(_) => skipped += 1,
+        }
+    }
+
+    if skipped > 0 {
+        warn!(
+            path,
+            skipped, "Skipped lines of the breached password list that are not SHA-1 hashes"
+        );
+    }
+
+    info!(
+        path,
+        count = hashes.len(),
+        "Loaded the breached password list"
+    );
+
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const OWNER: PasswordOwner<'static> = PasswordOwner {
+        username: "alice",
+        email: "alice.smith@example.com",
+    };
+
+    fn codes(password: &str) -> Vec<String> {
+        violations(password, OWNER)
+            .into_iter()
+            .map(|violation| violation.code.into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn accepts_strong_passwords() {
+        assert!(codes("Correct-Horse-7").is_empty());
+    }
+
+    #[test]
+    fn rejects_short_passwords() {
+        assert_eq!(codes("Ab1-"), ["password_too_short"]);
+    }
+
+    #[test]
+    fn rejects_long_passwords() {
+        let password = format!("Ab1-{}", "x".repeat(*PASSWORD_MAX_LENGTH));
+
+        assert_eq!(codes(&password), ["password_too_long"]);
+    }
+
+    #[test]
+    fn rejects_simple_passwords() {
+        assert_eq!(codes("correcthorsebattery"), ["password_too_simple"]);
+    }
+
+    #[test]
+    fn rejects_passwords_with_the_username_or_email() {
+        assert_eq!(codes("My-ALICE-pass-1"), ["password_contains_identity"]);
+        assert_eq!(codes("Alice.Smith-2024"), ["password_contains_identity"]);
+    }
+
+    #[test]
+    fn reports_every_broken_rule() {
+        assert_eq!(
+            codes("alice"),
+            [
+                "password_too_short",
+                "password_too_simple",
+                "password_contains_identity"
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_full_sha1_hashes_and_skips_the_rest() {
+        let path = std::env::temp_dir().join(format!("breached-{}.txt", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8:3861493").unwrap();
+        writeln!(file, "7c4a8d09ca3762af61e59520943dc26494f8941b").unwrap();
+        // A range API line, the first five characters of the hash are missing.
+        writeln!(file, "1E4C9B93F3F0682250B6CF8331B7EE68FD8:3861493").unwrap();
+        writeln!(file).unwrap();
+        drop(file);
+
+        let hashes = read_breached_passwords(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(hashes.len(), 2);
+        assert!(hashes.contains(&<[u8; 20]>::from(Sha1::digest(b"password"))));
+        assert!(hashes.contains(&<[u8; 20]>::from(Sha1::digest(b"123456"))));
+    }
+}
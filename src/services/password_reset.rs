@@ -0,0 +1,147 @@
+//! Password resets, users that forgot their password get a single use token mailed to them.
+//!
+//! The tokens are random and stored as digests in the `password_reset` table. Only the newest
+//! token of a user works, and every session and token of the user is revoked once the password
+//! has been reset.
+
+use crate::ApiResult;
+use crate::models::password_reset::PasswordReset;
+use crate::repositories::oauth_token::OAUTH_TOKEN_REPOSITORY;
+use crate::repositories::password_resets::PASSWORD_RESETS_REPOSITORY;
+use crate::repositories::sessions::SESSIONS_REPOSITORY;
+use crate::repositories::users::USERS_REPOSITORY;
+use crate::services::audit::{AuditAction, AuditEntry};
+use crate::services::lockout;
+use crate::services::mail::{self, Mail};
+use crate::services::password_policy::{self, PasswordOwner};
+use crate::statics::{PASSWORD_RESET_TTL, PASSWORD_RESET_URL};
+use actix_web::HttpRequest;
+use chrono::{TimeDelta, Utc};
+use rand::distributions::{Alphanumeric, DistString};
+use sqlx_utils::traits::Repository;
+use tracing::warn;
+
+const TOKEN_LENGTH: usize = 48;
+
+/// Mails a reset token to the user with the email `email`, if there is one.
+///
+/// Callers should not let the response depend on the outcome, see [`request_reset_in_background`].
+#[tracing::instrument(skip_all)]
+pub(crate) async fn request_reset(email: &str) -> ApiResult<()> {
+    let Some(user) = USERS_REPOSITORY.find_by_email(email).await? else {
+        return Ok(());
+    };
+    let Some(user_ext_id) = user.ext_id else {
+        return Ok(());
+    };
+
+    let token = Alphanumeric.sample_string(&mut rand::thread_rng(), TOKEN_LENGTH);
+    let ttl = i64::try_from(*PASSWORD_RESET_TTL)
+        .ok()
+        .and_then(TimeDelta::try_seconds)
+        .unwrap_or(TimeDelta::MAX);
+    let expires_at = Utc::now()
+        .checked_add_signed(ttl)
+        .unwrap_or(chrono::DateTime::<Utc>::MAX_UTC)
+        .naive_utc();
+
+    PASSWORD_RESETS_REPOSITORY
+        .delete_by_user(user_ext_id)
+        .await?;
+    PASSWORD_RESETS_REPOSITORY
+        .insert(&PasswordReset::new(&token, user_ext_id, expires_at))
+        .await?;
+
+    let link = format!(
+        "{}?{}",
+        *PASSWORD_RESET_URL,
+        form_urlencoded::Serializer::new(String::new())
+            .append_pair("token", &token)
+            .finish()
+    );
+
+    mail::send(&Mail {
+        to: user.email,
+        subject: "Reset your password".to_string(),
+        body: format!(
+            "Hi {},\n\nOpen the link below to choose a new password:\n\n{link}\n\nThe link works once and expires in {} minutes. If you did not ask to reset your password, you can ignore this mail.",
+            user.username,
+            *PASSWORD_RESET_TTL / 60
+        ),
+    })
+    .await
+}
+
+/// Runs [`request_reset`] without waiting for it, so how long a request takes does not reveal
+/// whether a user has the email.
+pub(crate) fn request_reset_in_background(email: String) {
+    tokio::spawn(async move {
+        if let Err(error) = request_reset(&email).await {
+            warn!(%error, "failed to request a password reset");
+        }
+    });
+}
+
+/// Sets the password of the user a reset token from [`request_reset`] was mailed to.
+///
+/// Returns `false` if the token is invalid, expired or was already used, and fails with
+/// [`ApiError::Validation`](crate::error::ApiError::Validation) if the password breaks the
+/// password policy, which leaves the token usable. Every session and token of the user is
+/// revoked, a lockout of the user is lifted, and the email counts as verified as the user just
+/// received mail at it.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn reset_password(
+    token: &str,
+    password: &str,
+    req: &HttpRequest,
+) -> ApiResult<bool> {
+    let entry = AuditEntry::new(AuditAction::PasswordReset).request(req);
+
+    let Some(reset) = PASSWORD_RESETS_REPOSITORY.get_by_token(token).await? else {
+        entry.failure().record().await;
+        return Ok(false);
+    };
+    let Some(user) = USERS_REPOSITORY.get_by_id(reset.user_ext_id).await? else {
+        entry.failure().record().await;
+        return Ok(false);
+    };
+
+    password_policy::check(
+        "new_password",
+        password,
+        PasswordOwner {
+            username: &user.username,
+            email: &user.email,
+        },
+    )?;
+
+    // Taken only now, so a password the policy rejects does not use up the token.
+    if PASSWORD_RESETS_REPOSITORY.take(token).await?.is_none() {
+        entry.failure().record().await;
+        return Ok(false);
+    }
+
+    USERS_REPOSITORY.change_password(&user, password).await?;
+    USERS_REPOSITORY
+        .mark_email_verified(reset.user_ext_id, &user.email)
+        .await?;
+
+    OAUTH_TOKEN_REPOSITORY
+        .revoke_by_user(reset.user_ext_id, None)
+        .await?;
+    SESSIONS_REPOSITORY
+        .delete_by_user(reset.user_ext_id, None)
+        .await?;
+    PASSWORD_RESETS_REPOSITORY
+        .delete_by_user(reset.user_ext_id)
+        .await?;
+    lockout::unlock_user(&user).await?;
+
+    entry
+        .actor_user(Some(reset.user_ext_id))
+        .target_user(reset.user_ext_id)
+        .record()
+        .await;
+
+    Ok(true)
+}
@@ -0,0 +1,63 @@
+//! Roles grant users permissions beyond managing their own account and resources.
+
+use crate::ApiResult;
+use crate::error::ApiError;
+use crate::repositories::roles::ROLES_REPOSITORY;
+use crate::repositories::users::USERS_REPOSITORY;
+use crate::statics::{BOOTSTRAP_ADMIN_EMAIL, BOOTSTRAP_ADMIN_PASSWORD, BOOTSTRAP_ADMIN_USERNAME};
+use tracing::{info, warn};
+
+/// The role with every permission, seeded by the migrations.
+pub(crate) const ADMIN_ROLE: &str = "admin";
+
+/// Makes the user configured with `BOOTSTRAP_ADMIN_USERNAME` and `BOOTSTRAP_ADMIN_PASSWORD` an
+/// admin, creating them if they do not exist.
+///
+/// Does nothing once anyone has the admin role, so removing the configuration or the role from
+/// the user later is respected. An existing user is only promoted when the configured password is
+/// theirs, so the configuration cannot be used to take over someone else's account.
+#[tracing::instrument]
+pub(crate) async fn bootstrap_admin() -> ApiResult<()> {
+    let (Some(username), Some(password)) = (
+        BOOTSTRAP_ADMIN_USERNAME.as_deref(),
+        BOOTSTRAP_ADMIN_PASSWORD.as_deref(),
+    ) else {
+        return Ok(());
+    };
+
+    if ROLES_REPOSITORY.is_assigned(ADMIN_ROLE).await? {
+        return Ok(());
+    }
+
+    let Some(role) = ROLES_REPOSITORY.get_id_by_name(ADMIN_ROLE).await? else {
+        warn!("the `{ADMIN_ROLE}` role does not exist, skipping creating the bootstrap admin");
+        return Ok(());
+    };
+
+    let user = match USERS_REPOSITORY.find_by_username(username).await? {
+        Some(user) if USERS_REPOSITORY.verify_password(&password, &user.password_hash) => user,
+        Some(_) => {
+            warn!(
+                "the bootstrap admin `{username}` already exists with another password, not making them an admin"
+            );
+            return Ok(());
+        }
+        None => {
+            let user = USERS_REPOSITORY
+                .create_user(username, &BOOTSTRAP_ADMIN_EMAIL, password)
+                .await?;
+
+            info!("created the bootstrap admin `{username}`");
+
+            user
+        }
+    };
+
+    let user_ext_id = user.ext_id.ok_or(ApiError::InternalError)?;
+
+    ROLES_REPOSITORY.assign(user_ext_id, role).await?;
+
+    info!("assigned the `{ADMIN_ROLE}` role to `{username}`");
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Browser sessions, the cookie based alternative to bearer tokens.
+//!
+//! Sessions are kept server side in the `session` table, the browser only holds a random token
+//! in the HttpOnly [`SESSION_COOKIE`]. A session ends when it has not been used for
+//! `SESSION_IDLE_TIMEOUT` seconds, and at the latest `SESSION_LIFETIME` seconds after it started.
+//!
+//! Every session has a CSRF token. Requests made with the session cookie that can change
+//! something have to send it in the [`CSRF_HEADER`], which other sites cannot do. The token is
+//! also set in the [`CSRF_COOKIE`] so scripts of the site can read it.
+
+use crate::ApiResult;
+use crate::dto::SessionDTO;
+use crate::models::session::Session;
+use crate::repositories::sessions::SESSIONS_REPOSITORY;
+use crate::statics::{BASE_URL, SESSION_IDLE_TIMEOUT, SESSION_LIFETIME};
+use actix_web::cookie::{Cookie, SameSite, time};
+use actix_web::http::Method;
+use actix_web::http::header::USER_AGENT;
+use actix_web::{HttpMessage, HttpRequest, HttpResponseBuilder};
+use chrono::{DateTime, TimeDelta, Utc};
+use rand::distributions::{Alphanumeric, DistString};
+use sqlx_utils::traits::Repository;
+use uuid::Uuid;
+
+/// Cookie holding the session token, the name the OpenAPI `cookie_session` scheme documents.
+pub(crate) const SESSION_COOKIE: &str = "id";
+
+/// Cookie holding the CSRF token of the session, readable by scripts.
+pub(crate) const CSRF_COOKIE: &str = "csrf_token";
+
+/// Header unsafe requests made with the session cookie send the CSRF token in.
+pub(crate) const CSRF_HEADER: &str = "X-CSRF-Token";
+
+const TOKEN_LENGTH: usize = 48;
+
+/// A session that was just started, with the raw tokens only the browser keeps.
+pub(crate) struct NewSession {
+    pub(crate) token: String,
+    pub(crate) csrf_token: String,
+    pub(crate) session: Session,
+}
+
+impl NewSession {
+    /// Sets the session and CSRF cookies on `response`.
+    pub(crate) fn set_cookies(&self, response: &mut HttpResponseBuilder) {
+        response
+            .cookie(cookie(SESSION_COOKIE, self.token.clone(), true))
+            .cookie(cookie(CSRF_COOKIE, self.csrf_token.clone(), false));
+    }
+
+    /// The session as it is returned to the browser.
+    pub(crate) fn to_dto(&self) -> SessionDTO {
+        SessionDTO {
+            csrf_token: self.csrf_token.clone(),
+            expires_at: self.session.expires_at,
+            idle_timeout_seconds: *SESSION_IDLE_TIMEOUT,
+        }
+    }
+}
+
+/// Starts a session for a user that signed in with the request `req`.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn create_session(user_ext_id: Uuid, req: &HttpRequest) -> ApiResult<NewSession> {
+    let token = Alphanumeric.sample_string(&mut rand::thread_rng(), TOKEN_LENGTH);
+    let csrf_token = Alphanumeric.sample_string(&mut rand::thread_rng(), TOKEN_LENGTH);
+
+    let user_agent = req
+        .headers()
+        .get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let expires_at = Utc::now()
+        .checked_add_signed(seconds(*SESSION_LIFETIME))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+        .naive_utc();
+
+    let session = Session::new(&token, &csrf_token, user_ext_id, user_agent, expires_at);
+
+    SESSIONS_REPOSITORY.insert(&session).await?;
+
+    Ok(NewSession {
+        token,
+        csrf_token,
+        session,
+    })
+}
+
+/// Gets the session of the request and marks it as used, `None` if it has no valid session.
+#[tracing::instrument(skip_all, level = "debug")]
+pub(crate) async fn session_from_request(req: &HttpRequest) -> ApiResult<Option<Session>> {
+    let Some(cookie) = req.cookie(SESSION_COOKIE) else {
+        return Ok(None);
+    };
+
+    SESSIONS_REPOSITORY
+        .touch(cookie.value(), idle_timeout())
+        .await
+}
+
+/// Whether a request made with `session` passes the CSRF check.
+///
+/// Requests that cannot change anything pass, everything else has to send the CSRF token of the
+/// session in the [`CSRF_HEADER`].
+pub(crate) fn verify_csrf(req: &HttpRequest, session: &Session) -> bool {
+    if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return true;
+    }
+
+    req.headers()
+        .get(CSRF_HEADER)
+        .is_some_and(|token| session.verify_csrf(token.as_bytes()))
+}
+
+/// Ends the session with the raw token `token`, returns whether it existed.
+pub(crate) async fn end_session(token: &str) -> ApiResult<bool> {
+    SESSIONS_REPOSITORY.delete_by_token(token).await
+}
+
+/// Removes the session and CSRF cookies from the browser.
+pub(crate) fn clear_cookies(response: &mut HttpResponseBuilder) {
+    for name in [SESSION_COOKIE, CSRF_COOKIE] {
+        let mut cookie = Cookie::build(name, "").path("/").finish();
+        cookie.make_removal();
+
+        response.cookie(cookie);
+    }
+}
+
+/// How long sessions stay valid without being used.
+pub(crate) fn idle_timeout() -> TimeDelta {
+    seconds(*SESSION_IDLE_TIMEOUT)
+}
+
+fn seconds(seconds: u64) -> TimeDelta {
+    seconds
+        .try_into()
+        .ok()
+        .and_then(TimeDelta::try_seconds)
+        .unwrap_or(TimeDelta::MAX)
+}
+
+/// The cookies are `Lax` so they are sent when a client sends the user to the authorization
+/// endpoint, and last until the session ends at the latest.
+fn cookie(name: &'static str, value: String, http_only: bool) -> Cookie<'static> {
+    Cookie::build(name, value)
+        .path("/")
+        .http_only(http_only)
+        .secure(BASE_URL.starts_with("https://"))
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::seconds(
+            (*SESSION_LIFETIME).try_into().unwrap_or(i64::MAX),
+        ))
+        .finish()
+}
@@ -0,0 +1,152 @@
+//! Email verification, users prove they own their email by sending back a token mailed to it.
+//!
+//! The tokens are signed rather than stored. They carry the user, the email they were sent to
+//! and when they expire, so a token stops working once the user changes their email.
+
+use crate::ApiResult;
+use crate::models::user::User;
+use crate::repositories::users::USERS_REPOSITORY;
+use crate::services::mail::{self, Mail};
+use crate::statics::{EMAIL_VERIFICATION_TTL, EMAIL_VERIFICATION_URL};
+use crate::utils::signing;
+use chrono::{DateTime, TimeDelta, Utc};
+use tracing::warn;
+use uuid::Uuid;
+
+const PURPOSE: &str = "verify-email";
+
+/// Creates a token that verifies `email` for the user `user` until it expires.
+pub(crate) fn verification_token(user: Uuid, email: &str) -> String {
+    let ttl = i64::try_from(*EMAIL_VERIFICATION_TTL)
+        .ok()
+        .and_then(TimeDelta::try_seconds)
+        .unwrap_or(TimeDelta::MAX);
+    let expires_at = Utc::now()
+        .checked_add_signed(ttl)
+        .unwrap_or(DateTime::<Utc>::MAX_UTC);
+
+    signing::sign(
+        PURPOSE,
+        &format!("{user}:{}:{email}", expires_at.timestamp()),
+    )
+}
+
+/// Mails `user` a link to verify their email.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn send_verification_email(user: &User) -> ApiResult<()> {
+    let Some(mail) = verification_mail(user) else {
+        return Ok(());
+    };
+
+    mail::send(&mail).await
+}
+
+/// The mail with a link to verify the email of `user`, `None` if the user is not stored yet.
+fn verification_mail(user: &User) -> Option<Mail> {
+    let ext_id = user.ext_id?;
+
+    let token = verification_token(ext_id, &user.email);
+    let link = format!(
+        "{}?{}",
+        *EMAIL_VERIFICATION_URL,
+        form_urlencoded::Serializer::new(String::new())
+            .append_pair("token", &token)
+            .finish()
+    );
+
+    Some(Mail {
+        to: user.email.clone(),
+        subject: "Verify your email".to_string(),
+        body: format!(
+            "Hi {},\n\nOpen the link below to verify your email:\n\n{link}\n\nIf you did not sign up, you can ignore this mail.",
+            user.username
+        ),
+    })
+}
+
+/// Like [`send_verification_email`], but only logs failures.
+///
+/// For flows where the change that needs verification already happened, the user can ask for a
+/// new mail later.
+pub(crate) async fn try_send_verification_email(user: &User) {
+    if let Err(error) = send_verification_email(user).await {
+        warn!(%error, "failed to send the verification email");
+    }
+}
+
+/// Verifies the email a token from [`verification_token`] was sent to.
+///
+/// Returns `false` if the token is invalid or expired, the user changed their email since, or
+/// it was already verified.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn verify_email(token: &str) -> ApiResult<bool> {
+    let Some((user, email)) = verified_email(token) else {
+        return Ok(false);
+    };
+
+    USERS_REPOSITORY.mark_email_verified(user, &email).await
+}
+
+/// The user and email a token from [`verification_token`] verifies, `None` if the token is
+/// invalid or expired.
+fn verified_email(token: &str) -> Option<(Uuid, String)> {
+    let payload = signing::verify(PURPOSE, token)?;
+
+    let mut parts = payload.splitn(3, ':');
+    let (user, expires_at, email) = (parts.next()?, parts.next()?, parts.next()?);
+    let (user, expires_at) = (user.parse::<Uuid>().ok()?, expires_at.parse::<i64>().ok()?);
+
+    (expires_at > Utc::now().timestamp()).then(|| (user, email.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mail::{MailTransport, MemoryTransport};
+
+    #[tokio::test]
+    async fn mailed_tokens_verify_the_email() {
+        let user = User {
+            ext_id: Some(Uuid::new_v4()),
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            ..Default::default()
+        };
+        let transport = MemoryTransport::default();
+
+        transport
+            .send(&verification_mail(&user).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(transport.sent().len(), 1);
+
+        let mail = transport.take().remove(0);
+        let (_, query) = mail
+            .body
+            .lines()
+            .find(|line| line.starts_with(EMAIL_VERIFICATION_URL.as_str()))
+            .and_then(|link| link.split_once('?'))
+            .unwrap();
+        let token = form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == "token")
+            .map(|(_, token)| token.into_owned())
+            .unwrap();
+
+        assert_eq!(mail.to, "alice@example.com");
+        assert_eq!(
+            verified_email(&token),
+            Some((user.ext_id.unwrap(), user.email.clone()))
+        );
+        assert!(transport.take().is_empty());
+    }
+
+    #[test]
+    fn tampered_tokens_verify_nothing() {
+        let token = verification_token(Uuid::new_v4(), "alice@example.com");
+        let (payload, signature) = token.split_once('.').unwrap();
+
+        assert_eq!(verified_email(&format!("{payload}x.{signature}")), None);
+        assert_eq!(verified_email("not a token"), None);
+    }
+}
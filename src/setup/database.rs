@@ -1,4 +1,7 @@
 use crate::ServerResult;
+use crate::services::maintenance;
+use crate::services::oauth::hash_plaintext_client_secrets;
+use crate::services::roles::bootstrap_admin;
 use crate::statics::DATABASE_URL;
 use sqlx::PgPool;
 use sqlx::postgres::PgPoolOptions;
@@ -16,3 +19,17 @@ pub async fn db_pool() -> ServerResult<PgPool> {
         .connect(&DATABASE_URL)
         .await?)
 }
+
+/// Brings existing data up to date with what the server expects.
+///
+/// Has to run after the database pool is initialized, the maintenance jobs start once it is done.
+#[inline]
+#[tracing::instrument]
+pub async fn prepare_database() -> ServerResult<()> {
+    hash_plaintext_client_secrets().await?;
+    bootstrap_admin().await?;
+
+    maintenance::database_ready();
+
+    Ok(())
+}
@@ -10,10 +10,17 @@ use tracing_appender::non_blocking::WorkerGuard;
 use crate::ServerResult;
 use crate::env::init_env;
 use crate::logging::init_tracing;
+use crate::services::{maintenance, password_policy};
 
 #[inline]
 pub async fn setup() -> ServerResult<WorkerGuard> {
     init_env()?;
 
-    init_tracing()
+    let guard = init_tracing()?;
+
+    password_policy::load_breached_passwords().await?;
+
+    maintenance::start();
+
+    Ok(guard)
 }
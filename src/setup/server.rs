@@ -4,6 +4,7 @@
 macro_rules! server {
     () => {{
         ::sqlx_utils::pool::initialize_db_pool($crate::setup::database::db_pool().await?);
+        $crate::setup::database::prepare_database().await?;
 
         ::actix_web::HttpServer::new(move || {
             let cors = $crate::config::cors();
@@ -54,12 +55,22 @@ config! {
         let state = block_on_fut!(!match app_state());
         let token_repo = *OAUTH_TOKEN_REPOSITORY;
         let client_repo = *OAUTH_CLIENTS_REPOSITORY;
+        let client_secret_repo = *OAUTH_CLIENT_SECRETS_REPOSITORY;
         let user_repo = *USERS_REPOSITORY;
+        let consent_repo = *USER_CONSENT_REPOSITORY;
+        let role_repo = *ROLES_REPOSITORY;
+        let session_repo = *SESSIONS_REPOSITORY;
+        let audit_repo = *AUDIT_EVENTS_REPOSITORY;
 
         cfg.app_data(state)
            .app_data(web::Data::new(token_repo))
            .app_data(web::Data::new(client_repo))
-           .app_data(web::Data::new(user_repo));
+           .app_data(web::Data::new(client_secret_repo))
+           .app_data(web::Data::new(user_repo))
+           .app_data(web::Data::new(consent_repo))
+           .app_data(web::Data::new(role_repo))
+           .app_data(web::Data::new(session_repo))
+           .app_data(web::Data::new(audit_repo));
     }
 }
 
@@ -83,8 +94,13 @@ config! {
 }
 
 use crate::endpoints::index_scope;
+use crate::repositories::audit_events::AUDIT_EVENTS_REPOSITORY;
+use crate::repositories::oauth_client_secrets::OAUTH_CLIENT_SECRETS_REPOSITORY;
 use crate::repositories::oauth_clients::OAUTH_CLIENTS_REPOSITORY;
 use crate::repositories::oauth_token::OAUTH_TOKEN_REPOSITORY;
+use crate::repositories::roles::ROLES_REPOSITORY;
+use crate::repositories::sessions::SESSIONS_REPOSITORY;
+use crate::repositories::user_consent::USER_CONSENT_REPOSITORY;
 use crate::repositories::users::USERS_REPOSITORY;
 use crate::state::app_state;
 use crate::statics::EXTERNAL_RESOURCES;
@@ -14,6 +14,7 @@
 /// - API scope creation with configurable endpoints
 /// - Optional version prefixing
 /// - Guard integration
+/// - Required permissions, checked after the scope's middleware has authenticated the request
 /// - Service registration
 /// - Path registration
 /// - Comprehensive OpenAPI documentation including:
@@ -66,6 +67,17 @@
 /// }
 /// ```
 ///
+/// Requiring permissions for every route in a scope:
+/// ```rust
+/// api_scope! {
+///     pub admin_api = "/admin";
+///
+///     middleware: [auth: || async { Ok::<_, ApiError>(AuthMiddleware::new(/* ... */)) }];
+///     permissions: [Permission::UsersRead];
+///     paths: [list_users];
+/// }
+/// ```
+///
 /// # Internal Working Details
 ///
 /// ## Version-specific Generation
@@ -90,6 +102,8 @@
 /// 1. Sets up an actix-web scope with the specified endpoint
 /// 2. Applies any specified guards
 /// 3. Registers all provided services and paths
+/// 4. Wraps the scope in [`RequirePermissions`](crate::middleware::RequirePermissions) when
+///    `permissions` are given, inside the listed middleware so the request is authenticated first
 ///
 /// # Technical Notes
 ///
@@ -127,6 +141,7 @@ macro_rules! api_scope {
         $(guard: $guard_ident:ident;)?
         $(services: [$($service:ident),* $(,)?] ;)?
         $(middleware: [$($middleware:ident: $expr:expr),* $(,)?];)?
+        $(permissions: [$($permission:expr),* $(,)?];)?
         $(paths: [$($path:path),* $(,)?] ;)?
 
         docs: {
@@ -161,7 +176,7 @@ macro_rules! api_scope {
                     .service(::utoipa_scalar::Scalar::with_url("/scalar", openapi.clone()))
             }
 
-            $crate::utils::api_scope! {$vis $ident = $endpoint;$(guard: $guard_ident;)?$( services: [$($service),*] ; )?$( middleware: [$($middleware: $expr),*] ; )?$( paths: [$($path),*] ; )?
+            $crate::utils::api_scope! {$vis $ident = $endpoint;$(guard: $guard_ident;)?$( services: [$($service),*] ; )?$( middleware: [$($middleware: $expr),*] ; )?$( permissions: [$($permission),*] ; )?$( paths: [$($path),*] ; )?
                 docs: {
                     $(extra_paths: [$($extra_path),*];)?
                     $(tags: [$($tag),*];)?
@@ -180,6 +195,7 @@ macro_rules! api_scope {
         $(guard: $guard_ident:ident;)?
         $(services: [$($service:ident),* $(,)?];)?
         $(middleware: [$($middleware:ident: $expr:expr),* $(,)?];)?
+        $(permissions: [$($permission:expr),* $(,)?];)?
         $(paths: [$($path:path),* $(,)?];)?
 
         docs: {
@@ -217,7 +233,7 @@ macro_rules! api_scope {
             $vis struct [<$ident:camel API>];
         }
 
-        $crate::utils::api_scope! {$vis $ident = $endpoint;$(guard: $guard_ident;)?$( services: [$($service),*] ; )?$( middleware: [$($middleware: $expr),*] ; )?$( paths: [$($path),*] ; )?}
+        $crate::utils::api_scope! {$vis $ident = $endpoint;$(guard: $guard_ident;)?$( services: [$($service),*] ; )?$( middleware: [$($middleware: $expr),*] ; )?$( permissions: [$($permission),*] ; )?$( paths: [$($path),*] ; )?}
     };
 
     {
@@ -226,6 +242,7 @@ macro_rules! api_scope {
         $(guard: $guard_ident:ident;)?
         $(services: [$($service:ident),* $(,)?];)?
         $(middleware: [$($middleware:ident: $expr:expr),* $(,)?];)?
+        $(permissions: [$($permission:expr),* $(,)?];)?
         $(paths: [$($path:path),* $(,)?];)?
     } => {
         ::paste::paste! {
@@ -249,6 +266,9 @@ macro_rules! api_scope {
                         .service($service().await?)
                     )*
                 )?
+                $(
+                    .wrap($crate::middleware::RequirePermissions::new(&[$($permission),*]))
+                )?
                 $(
                     $(
                         .wrap(($middleware)().await?)
@@ -0,0 +1,57 @@
+//! Encryption for secrets that have to be read back, such as TOTP secrets.
+//!
+//! Secrets that only have to be compared are hashed instead, see [`super::hashing`]. Values are
+//! sealed with XChaCha20-Poly1305 under a key derived from `ENCRYPTION_KEY`, the random nonce
+//! is stored in front of the ciphertext.
+
+use crate::ApiResult;
+use crate::error::ApiError;
+use crate::statics::ENCRYPTION_KEY;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use sha2::{Digest, Sha256};
+use std::sync::LazyLock;
+use tracing::error;
+
+const NONCE_LENGTH: usize = 24;
+
+static CIPHER: LazyLock<XChaCha20Poly1305> = LazyLock::new(|| {
+    let key = Sha256::new()
+        .chain_update(b"ferric encryption key\0")
+        .chain_update(&*ENCRYPTION_KEY)
+        .finalize();
+
+    XChaCha20Poly1305::new(&key)
+});
+
+/// Encrypts `plaintext`, the result can only be read with [`decrypt`].
+pub(crate) fn encrypt(plaintext: &[u8]) -> ApiResult<Vec<u8>> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = CIPHER.encrypt(&nonce, plaintext).map_err(|_| {
+        error!("Failed to encrypt a secret");
+        ApiError::InternalError
+    })?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypts a value from [`encrypt`].
+///
+/// Fails if the value was tampered with or encrypted under another key.
+pub(crate) fn decrypt(sealed: &[u8]) -> ApiResult<Vec<u8>> {
+    if sealed.len() < NONCE_LENGTH {
+        error!("Encrypted secret is too short to hold a nonce");
+        return Err(ApiError::InternalError);
+    }
+
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+
+    CIPHER
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| {
+            error!("Failed to decrypt a secret, was ENCRYPTION_KEY changed?");
+            ApiError::InternalError
+        })
+}
@@ -0,0 +1,228 @@
+//! Hashing for passwords, tokens and other secrets that are stored at rest.
+//!
+//! Passwords and client secrets are hashed with Argon2id, with the parameters configured with
+//! `ARGON2_MEMORY_KIB`, `ARGON2_ITERATIONS` and `ARGON2_PARALLELISM` and peppered with
+//! `HASH_PEPPER` when it is set. Hashes keep the parameters they were made with, so raising them
+//! does not break existing hashes, [`needs_rehash`] tells which ones to replace. Tokens and
+//! authorization codes are random and long enough that a plain SHA-256 digest is sufficient,
+//! which also lets them be looked up by their digest.
+
+use crate::ApiResult;
+use crate::statics::{ARGON2_ITERATIONS, ARGON2_MEMORY_KIB, ARGON2_PARALLELISM, HASH_PEPPER};
+use argon2::{
+    Algorithm, Argon2, KeyId, Params, ParamsBuilder, Version,
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
+};
+use sha2::{Digest, Sha256};
+use std::sync::LazyLock;
+use tracing::error;
+
+/// Id of `HASH_PEPPER` stored in the hashes made with it, so hashes from before the pepper was
+/// set can still be verified and a changed pepper is noticed.
+static PEPPER_ID: LazyLock<Option<[u8; 4]>> =
+    LazyLock::new(|| HASH_PEPPER.as_deref().map(pepper_id));
+
+/// Argon2 with the configured parameters and pepper, used for every new hash.
+static ARGON2: LazyLock<Argon2<'static>> = LazyLock::new(|| {
+    let params = params(*ARGON2_MEMORY_KIB, *ARGON2_ITERATIONS, *ARGON2_PARALLELISM)
+        .unwrap_or_else(|error| {
+            error!(error = %error, "Invalid Argon2 parameters, using the defaults");
+
+            params(
+                Params::DEFAULT_M_COST,
+                Params::DEFAULT_T_COST,
+                Params::DEFAULT_P_COST,
+            )
+            .expect("The default Argon2 parameters are valid")
+        });
+
+    match HASH_PEPPER.as_deref() {
+        Some(pepper) => {
+            Argon2::new_with_secret(pepper, Algorithm::Argon2id, Version::V0x13, params)
+                .expect("Failed to set up Argon2 with the pepper")
+        }
+        None => Argon2::new(Algorithm::Argon2id, Version::V0x13, params),
+    }
+});
+
+/// Hash that [`verify_dummy`] verifies against, generated once per process.
+static DUMMY_HASH: LazyLock<String> = LazyLock::new(|| {
+    hash_secret(b"dummy secret used to equalize verification time")
+        .expect("Failed to hash dummy secret")
+});
+
+/// Id of `pepper`, the first four bytes of its SHA-256 digest.
+fn pepper_id(pepper: &[u8]) -> [u8; 4] {
+    let digest = Sha256::digest(pepper);
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+/// Argon2 parameters with the costs `m_cost`, `t_cost` and `p_cost`, and the id of the pepper.
+fn params(m_cost: u32, t_cost: u32, p_cost: u32) -> argon2::Result<Params> {
+    let mut builder = ParamsBuilder::new();
+    builder.m_cost(m_cost).t_cost(t_cost).p_cost(p_cost);
+
+    if let Some(id) = PEPPER_ID.as_ref() {
+        builder.keyid(KeyId::new(id)?);
+    }
+
+    builder.build()
+}
+
+/// Hashes `secret` with a random salt and returns the hash as a PHC string.
+#[tracing::instrument(skip_all)]
+pub(crate) fn hash_secret(secret: impl AsRef<[u8]>) -> ApiResult<String> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    Ok(ARGON2.hash_password(secret.as_ref(), &salt)?.to_string())
+}
+
+/// Verifies `secret` against a PHC string produced by [`hash_secret`].
+///
+/// Hashes made with other parameters or without the pepper are verified as they were made. The
+/// hash comparison itself is constant time, a malformed hash or one made with a pepper that is
+/// not configured is logged and treated as a mismatch.
+#[tracing::instrument(skip_all)]
+pub(crate) fn verify_secret(secret: impl AsRef<[u8]>, hash: &str) -> bool {
+    let parsed_hash = match PasswordHash::new(hash) {
+        Ok(hash) => hash,
+        Err(error) => {
+            error!(error = %error);
+
+            return false;
+        }
+    };
+
+    let keyid = Params::try_from(&parsed_hash)
+        .map(|params| params.keyid().to_vec())
+        .unwrap_or_default();
+
+    let result = if keyid.is_empty() {
+        Argon2::default().verify_password(secret.as_ref(), &parsed_hash)
+    } else if PEPPER_ID.is_some_and(|id| id[..] == keyid[..]) {
+        ARGON2.verify_password(secret.as_ref(), &parsed_hash)
+    } else {
+        error!("hash was made with a pepper that is not configured");
+
+        return false;
+    };
+
+    result.is_ok()
+}
+
+/// Whether `hash` was made with another algorithm, other parameters or without the current
+/// pepper, and should be replaced with a new hash once the secret is known.
+///
+/// Malformed hashes cannot be verified and are never reported.
+pub(crate) fn needs_rehash(hash: &str) -> bool {
+    let Ok(hash) = PasswordHash::new(hash) else {
+        return false;
+    };
+    let Ok(params) = Params::try_from(&hash) else {
+        return true;
+    };
+    let current = ARGON2.params();
+
+    hash.algorithm != Algorithm::Argon2id.ident()
+        || hash.version != Some(Version::V0x13.into())
+        || params.m_cost() != current.m_cost()
+        || params.t_cost() != current.t_cost()
+        || params.p_cost() != current.p_cost()
+        || params.keyid() != current.keyid()
+        || hash.hash.map(|output| output.len())
+            != Some(current.output_len().unwrap_or(Params::DEFAULT_OUTPUT_LEN))
+}
+
+/// Runs a verification that always fails.
+///
+/// Used when the record to verify against does not exist so the response takes as long as it
+/// would for an existing record, which keeps callers from probing which records exist.
+#[tracing::instrument(skip_all)]
+pub(crate) fn verify_dummy(secret: impl AsRef<[u8]>) {
+    let _ = verify_secret(secret, &DUMMY_HASH);
+}
+
+/// Whether `value` is a PHC string rather than a plaintext secret.
+#[inline]
+pub(crate) fn is_hashed(value: &str) -> bool {
+    PasswordHash::new(value).is_ok()
+}
+
+/// SHA-256 digest of a token or authorization code as a lowercase hex string.
+///
+/// This is what gets stored in the database, so it matches `encode(sha256(token), 'hex')`.
+#[inline]
+pub(crate) fn token_digest(token: impl AsRef<[u8]>) -> String {
+    hex::encode(Sha256::digest(token.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hashes `secret` with Argon2id, the costs `m_cost` and `t_cost` and optionally a pepper.
+    fn hash_with(secret: &[u8], m_cost: u32, t_cost: u32, pepper: Option<&[u8]>) -> String {
+        let mut builder = ParamsBuilder::new();
+        builder.m_cost(m_cost).t_cost(t_cost).p_cost(1);
+
+        if let Some(pepper) = pepper {
+            builder.keyid(KeyId::new(&pepper_id(pepper)).unwrap());
+        }
+
+        let params = builder.build().unwrap();
+        let argon2 = match pepper {
+            Some(pepper) => {
+                Argon2::new_with_secret(pepper, Algorithm::Argon2id, Version::V0x13, params)
+                    .unwrap()
+            }
+            None => Argon2::new(Algorithm::Argon2id, Version::V0x13, params),
+        };
+
+        argon2
+            .hash_password(secret, &SaltString::generate(&mut OsRng))
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn new_hashes_do_not_need_a_rehash() {
+        let hash = hash_secret(b"secret").unwrap();
+
+        assert!(verify_secret(b"secret", &hash));
+        assert!(!verify_secret(b"other", &hash));
+        assert!(!needs_rehash(&hash));
+    }
+
+    #[test]
+    fn hashes_with_other_parameters_need_a_rehash() {
+        let hash = hash_with(b"secret", Params::MIN_M_COST, 1, None);
+
+        assert!(needs_rehash(&hash));
+    }
+
+    #[test]
+    fn hashes_with_another_pepper_need_a_rehash_and_do_not_verify() {
+        let hash = hash_with(
+            b"secret",
+            *ARGON2_MEMORY_KIB,
+            *ARGON2_ITERATIONS,
+            Some(b"other"),
+        );
+
+        assert!(needs_rehash(&hash));
+        assert!(!verify_secret(b"secret", &hash));
+    }
+
+    #[test]
+    fn malformed_hashes_do_not_need_a_rehash() {
+        assert!(!needs_rehash("plaintext"));
+        assert!(!verify_secret(b"plaintext", "plaintext"));
+    }
+
+    #[test]
+    fn pepper_ids_are_stable_and_distinct() {
+        assert_eq!(pepper_id(b"pepper"), pepper_id(b"pepper"));
+        assert_ne!(pepper_id(b"pepper"), pepper_id(b"salt"));
+        assert_eq!(pepper_id(b"pepper")[..], Sha256::digest(b"pepper")[..4]);
+    }
+}
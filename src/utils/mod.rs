@@ -1,5 +1,8 @@
 pub(crate) mod api_scope;
+pub(crate) mod encryption;
+pub(crate) mod hashing;
 pub(crate) mod middleware_macros;
 pub mod mod_def;
+pub(crate) mod signing;
 
 pub(crate) use api_scope::api_scope;
@@ -0,0 +1,79 @@
+//! Tokens the server hands out and later takes back without storing them.
+//!
+//! A signed token is its payload and an HMAC-SHA256 of it, both base64url encoded. Every token
+//! is signed for a purpose so a token issued for one thing is never accepted for another.
+
+use crate::statics::SIGNING_KEY;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn mac(purpose: &str, payload: &[u8]) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(&SIGNING_KEY).expect("HMAC accepts keys of any length");
+
+    mac.update(purpose.as_bytes());
+    mac.update(b"\0");
+    mac.update(payload);
+    mac
+}
+
+/// Signs `payload` for `purpose` and returns the token.
+pub(crate) fn sign(purpose: &str, payload: &str) -> String {
+    let signature = mac(purpose, payload.as_bytes()).finalize().into_bytes();
+
+    format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(payload),
+        URL_SAFE_NO_PAD.encode(signature)
+    )
+}
+
+/// Gets the payload of a token from [`sign`], `None` unless it was signed for `purpose`.
+///
+/// The signature is compared in constant time.
+pub(crate) fn verify(purpose: &str, token: &str) -> Option<String> {
+    let (payload, signature) = token.split_once('.')?;
+    let payload = URL_SAFE_NO_PAD.decode(payload).ok()?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+
+    mac(purpose, &payload).verify_slice(&signature).ok()?;
+
+    String::from_utf8(payload).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_tokens_signed_for_the_purpose() {
+        let token = sign("email_verification", "user:42");
+
+        assert_eq!(
+            verify("email_verification", &token).as_deref(),
+            Some("user:42")
+        );
+    }
+
+    #[test]
+    fn rejects_tokens_signed_for_another_purpose() {
+        let token = sign("email_verification", "user:42");
+
+        assert_eq!(verify("password_reset", &token), None);
+    }
+
+    #[test]
+    fn rejects_tampered_tokens() {
+        let token = sign("email_verification", "user:42");
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!("{}.{signature}", URL_SAFE_NO_PAD.encode("user:43"));
+
+        assert_eq!(verify("email_verification", &forged), None);
+        assert_eq!(verify("email_verification", "user:42"), None);
+        assert_eq!(verify("email_verification", ""), None);
+    }
+}